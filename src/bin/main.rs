@@ -4,37 +4,20 @@ extern crate web_server;
 
 use web_server::server::*;
 use web_server::http::*;
-use std::fs::File;
+use web_server::http::responses;
+use web_server::http::target::RequestTarget;
 use std::io::prelude::*;
 use std::io;
-use std::thread::sleep;
-use std::time::Duration;
 
 fn main() {
-    let mut srv = Server::start("127.0.0.1:8080", 4,
-        move |listener, mut workers, receiver, _| {
-            listener.set_nonblocking(true)
-                .expect("Server cannot be set to nonblocking.");
-            
-            loop {
-                sleep(Duration::new(0, 250));
-                if let Ok((stream, _)) = listener.accept() {
-                    workers.send_job(
-                        || {
-                            handle_connection(stream);
-                        }
-                    ).expect("Failed to send job to WorkerPool.");
-                }
-                
-                if let Ok(Message::Shutdown) = receiver.try_recv() {
-                    if let Err(e) = workers.shutdown() {
-                        panic!(e);
-                    }
-                    break;
-                }
-            }
-        },
-    ());
+    let files = StaticFiles::new("html");
+    let mut router = Router::new();
+    
+    // Every file can be fetched but nothing else can be done to it.
+    router.get("/*path", move |req, params| files.handle(req, params))
+        .wrap(server_wide);
+    
+    let mut srv = Server::with_router("127.0.0.1:8080", 4, router);
     
     loop {
         let mut command = String::new();
@@ -47,7 +30,7 @@ fn main() {
             break;
         } else {
             print!("Did not recognise command '");
-            io::stdout().write(command.as_bytes()).expect("Error writing to standard output.");
+            io::stdout().write_all(command.as_bytes()).expect("Error writing to standard output.");
             print!("'");
         }
     }
@@ -56,42 +39,15 @@ fn main() {
         .expect("Failed to join on the Server.");
 }
 
-fn handle_connection(mut stream: TcpStream) {
-    let mut buffer = [0; 512];
-    if let Ok(_) = stream.read(&mut buffer) {
-        let message = MessageHTTP::from_utf8(buffer.to_vec()).unwrap();
-        
-        let (status_line, filename) = if let ("GET", target, _) = message.start_line.request() {
-            if target == "/" {
-                ("HTTP/1.1 200 OK\r\n\r\n", String::from("html/index.html"))
-            } else {
-                ("HTTP/1.1 200 OK\r\n\r\n", format!("html{}.html", target))
-            }
-        } else {
-            ("HTTP/1.1 404 NOT FOUND\r\n\r\n", String::from("html/404.html"))
-        };
-
-        if let Ok(mut file) = File::open(filename) {
-            let mut contents = String::new();
-
-            if let Ok(_) = file.read_to_string(&mut contents) {
-                let response = format!("{}{}", status_line, contents);
-
-                if let Ok(_) = stream.write(response.as_bytes()) {
-                    stream.flush().expect("Error sending response to client.");
-                }
-            }
-        } else if let Ok(mut file) = File::open("html/404.html") {
-            let status_line = "HTTP/1.1 404 NOT FOUND\r\n\r\n";
-            let mut contents = String::new();
-
-            if let Ok(_) = file.read_to_string(&mut contents) {
-                let response = format!("{}{}", status_line, contents);
-
-                if let Ok(_) = stream.write(response.as_bytes()) {
-                    stream.flush().expect("Error sending response to client.");
-                }
-            }
-        }
+/// Answers the requests which are not for any file.
+fn server_wide(req: &MessageHTTP, next: &dyn Fn(&MessageHTTP) -> MessageHTTP) -> MessageHTTP {
+    // The method is parsed but the server does not know what to do with it.
+    if req.start_line.method().is_some_and(|method| !method.is_recognized()) {
+        return responses::not_implemented();
+    }
+    // A server-wide `OPTIONS` request is not for any file.
+    if let Some(Ok(RequestTarget::AsteriskForm)) = req.start_line.target_form() {
+        return responses::server_options();
     }
+    next(req)
 }
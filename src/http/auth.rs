@@ -0,0 +1,184 @@
+//! `auth` is a module to handle the credentials sent in the `Authorization` Header Field and
+//! the challenges sent to request them.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::string::String;
+use super::{MessageHTTP, BuildError, ResponseBuilder, StatusCode};
+use super::headers;
+use super::base64;
+use super::token;
+
+/// Returns the user-id and password of a request using the `Basic` authentication scheme.
+///
+/// The scheme is case-insensitive and the password is everything after the first `:`
+/// so it may itself contain colons.</br>
+/// Returns `None` if there is no `Authorization`, it uses another scheme, or the
+/// credentials are not valid base64 encoded utf8 containing a `:`.
+///
+/// # Params
+///
+/// request --- The request to get the credentials of.
+pub fn basic_credentials(request: &MessageHTTP) -> Option<(String, String)> {
+    let mut parts = request.header(headers::AUTHORIZATION)?.trim().splitn(2, ' ');
+    if !parts.next()?.eq_ignore_ascii_case("Basic") {
+        return None;
+    }
+    
+    let credentials = String::from_utf8(base64::decode(parts.next()?.trim())?).ok()?;
+    let index = credentials.find(':')?;
+    Some((String::from(&credentials[..index]), String::from(&credentials[index + 1..])))
+}
+
+/// Returns the token of a request using the `Bearer` authentication scheme.
+///
+/// The scheme is case-insensitive and any whitespace around the scheme and token is
+/// ignored.</br>
+/// Returns `None` if there is no `Authorization`, it uses another scheme, or the token
+/// is empty or not in the token68 syntax.
+///
+/// # Params
+///
+/// request --- The request to get the token of.
+pub fn bearer_token(request: &MessageHTTP) -> Option<&str> {
+    let authorization = request.header(headers::AUTHORIZATION)?.trim();
+    let index = authorization.find(char::is_whitespace)?;
+    if !authorization[..index].eq_ignore_ascii_case("Bearer") {
+        return None;
+    }
+    
+    let token = authorization[index..].trim();
+    // A token68 is made of these characters followed by any `=` padding.
+    let data = token.trim_end_matches('=');
+    if data.is_empty() || !data.bytes().all(|b| b.is_ascii_alphanumeric() || b"-._~+/".contains(&b)) {
+        return None;
+    }
+    Some(token)
+}
+
+/// Returns a `401 Unauthorized` response challenging the client for a valid `Bearer` token.
+pub fn bearer_challenge() -> MessageHTTP {
+    ResponseBuilder::new(StatusCode::UNAUTHORIZED)
+        .header(headers::WWW_AUTHENTICATE, "Bearer error=\"invalid_token\"")
+        .body(Vec::new())
+        .build()
+        .expect("The challenge is always a valid Header Field value.")
+}
+
+/// Returns a `401 Unauthorized` response challenging the client for `Basic` credentials.
+///
+/// Any quotes or backslashes in the realm are escaped.</br>
+/// Fails if the realm contains a CR or LF.
+///
+/// # Params
+///
+/// realm --- The protection space the credentials are for.
+pub fn basic_challenge(realm: &str) -> Result<MessageHTTP, BuildError> {
+    ResponseBuilder::new(StatusCode::UNAUTHORIZED)
+        .header(headers::WWW_AUTHENTICATE, &format!("Basic realm={}", token::quote_string(realm)))
+        .body(Vec::new())
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::HTTP;
+    
+    /// Returns a request with the passed `Authorization`.
+    fn request(authorization: &str) -> MessageHTTP {
+        MessageHTTP::from(&format!("GET / HTTP/1.1\r\nAuthorization: {}\r\n\r\n", authorization)).unwrap()
+    }
+
+    #[test]
+    fn test_basic_credentials() {
+        assert_eq!(
+            basic_credentials(&request("Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==")),
+            Some((String::from("Aladdin"), String::from("open sesame"))),
+            "Test auth::basic_credentials-1 failed."
+        );
+        
+        // `user:pa:ss:`
+        assert_eq!(
+            basic_credentials(&request("bASIC  dXNlcjpwYTpzczo=")),
+            Some((String::from("user"), String::from("pa:ss:"))),
+            "Test auth::basic_credentials-2 failed."
+        );
+        
+        // `:`
+        assert_eq!(
+            basic_credentials(&request("Basic Og==")),
+            Some((String::new(), String::new())),
+            "Test auth::basic_credentials-3 failed."
+        );
+        
+        // `Aladdin` without a colon, invalid base64, invalid utf8, the wrong scheme and no credentials.
+        for authorization in ["Basic QWxhZGRpbg==", "Basic QWxh!GRpbjpvcGVuIHNlc2FtZQ==", "Basic /w==", "Bearer QWxhZGRpbjpvcGVuIHNlc2FtZQ==", "Basic"].iter() {
+            assert_eq!(
+                basic_credentials(&request(authorization)),
+                None,
+                "Test auth::basic_credentials-4 failed for `{}`.", authorization
+            );
+        }
+        
+        assert_eq!(
+            basic_credentials(&MessageHTTP::from("GET / HTTP/1.1\r\n\r\n").unwrap()),
+            None,
+            "Test auth::basic_credentials-5 failed."
+        );
+    }
+    #[test]
+    fn test_bearer_token() {
+        let message = request("Bearer mF_9.B5f-4.1JqM");
+        assert_eq!(
+            bearer_token(&message),
+            Some("mF_9.B5f-4.1JqM"),
+            "Test auth::bearer_token-1 failed."
+        );
+        
+        let message = request("bearer   abc/DEF+123~==  ");
+        assert_eq!(
+            bearer_token(&message),
+            Some("abc/DEF+123~=="),
+            "Test auth::bearer_token-2 failed."
+        );
+        
+        let message = request("BEARER\tabc");
+        assert_eq!(
+            bearer_token(&message),
+            Some("abc"),
+            "Test auth::bearer_token-3 failed."
+        );
+        
+        for authorization in ["Bearer ", "Bearer", "Bearer ==", "Bearer abc def", "Bearer a=b", "Bearer ab\"c", "Basic abc", "Bearerabc"].iter() {
+            assert_eq!(
+                bearer_token(&request(authorization)),
+                None,
+                "Test auth::bearer_token-4 failed for `{}`.", authorization
+            );
+        }
+        
+        assert_eq!(
+            bearer_challenge().to_http().unwrap(),
+            "HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Bearer error=\"invalid_token\"\r\nContent-Length: 0\r\n\r\n",
+            "Test auth::bearer_challenge-1 failed."
+        );
+    }
+    #[test]
+    fn test_basic_challenge() {
+        assert_eq!(
+            basic_challenge("Staff \"only\" \\ admins").unwrap().to_http().unwrap(),
+            "HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Basic realm=\"Staff \\\"only\\\" \\\\ admins\"\r\nContent-Length: 0\r\n\r\n",
+            "Test auth::basic_challenge-1 failed."
+        );
+        
+        assert_eq!(
+            basic_challenge("a\r\nb"),
+            Err(BuildError::InvalidHeaderValue(String::from("Basic realm=\"a\r\nb\""))),
+            "Test auth::basic_challenge-2 failed."
+        );
+    }
+}
@@ -0,0 +1,133 @@
+//! `base64` is a module to handle the base64 encoding used by the `Authorization` Header
+//! Field and by serialized message bodies.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+#[cfg(feature = "serde")]
+/// The characters of the standard base64 alphabet, in order of their six bit values.
+static ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Returns the six bit value of a character of the standard base64 alphabet.
+///
+/// # Params
+///
+/// byte --- The character to convert.
+fn value(byte: u8) -> Option<u32> {
+    match byte {
+        b'A'..=b'Z' => Some(u32::from(byte - b'A')),
+        b'a'..=b'z' => Some(u32::from(byte - b'a') + 26),
+        b'0'..=b'9' => Some(u32::from(byte - b'0') + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None
+    }
+}
+
+/// Decodes standard base64, with or without padding.</br>
+/// Returns `None` if the passed `str` is not valid base64.
+///
+/// # Params
+///
+/// encoded --- The `str` to decode.
+pub fn decode(encoded: &str) -> Option<Vec<u8>> {
+    let bytes = encoded.as_bytes();
+    // Padding is only valid to complete the final group of four.
+    let data = if bytes.len().is_multiple_of(4) {
+        let padding = bytes.iter().rev().take(2).take_while(|b| **b == b'=').count();
+        &bytes[..bytes.len() - padding]
+    } else {
+        bytes
+    };
+    if data.len() % 4 == 1 {
+        return None;
+    }
+    
+    let mut res = Vec::with_capacity(data.len() * 3 / 4);
+    for group in data.chunks(4) {
+        let mut bits = 0;
+        for (i, byte) in group.iter().enumerate() {
+            bits |= value(*byte)? << (18 - 6 * i);
+        }
+        
+        let decoded = [(bits >> 16) as u8, (bits >> 8) as u8, bits as u8];
+        // A partial group of `n` characters holds `n - 1` bytes.
+        res.extend_from_slice(&decoded[..group.len() - 1]);
+    }
+    Some(res)
+}
+
+#[cfg(feature = "serde")]
+/// Encodes the passed bytes as standard base64, with padding.
+///
+/// # Params
+///
+/// bytes --- The bytes to encode.
+pub fn encode(bytes: &[u8]) -> String {
+    let mut res = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    
+    for group in bytes.chunks(3) {
+        let bits = group.iter().enumerate().fold(0, |bits, (i, byte)| bits | u32::from(*byte) << (16 - 8 * i));
+        
+        // A partial group of `n` bytes needs `n + 1` characters.
+        for i in 0..4 {
+            if i <= group.len() {
+                res.push(ALPHABET[(bits >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                res.push('=');
+            }
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        let cases: [(&str, &[u8]); 7] = [
+            ("", b""), ("Zg==", b"f"), ("Zm8=", b"fo"), ("Zm9v", b"foo"),
+            ("Zm9vYg", b"foob"), ("Zm9vYmE=", b"fooba"), ("QWxhZGRpbjpvcGVuIHNlc2FtZQ==", b"Aladdin:open sesame")
+        ];
+        for &(encoded, decoded) in cases.iter() {
+            assert_eq!(
+                decode(encoded),
+                Some(decoded.to_vec()),
+                "Test base64::decode-1 failed for `{}`.", encoded
+            );
+        }
+        
+        assert_eq!(
+            decode("/+8="),
+            Some(vec![0xFF, 0xEF]),
+            "Test base64::decode-2 failed."
+        );
+        
+        for encoded in ["Z", "Zm9v!", "Zm=v", "Z===", "Zm9v Yg==", "====="].iter() {
+            assert_eq!(
+                decode(encoded),
+                None,
+                "Test base64::decode-3 failed for `{}`.", encoded
+            );
+        }
+    }
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_encode() {
+        let cases: [(&str, &[u8]); 8] = [
+            ("", b""), ("Zg==", b"f"), ("Zm8=", b"fo"), ("Zm9v", b"foo"), ("Zm9vYg==", b"foob"),
+            ("Zm9vYmE=", b"fooba"), ("QWxhZGRpbjpvcGVuIHNlc2FtZQ==", b"Aladdin:open sesame"), ("/+8=", &[0xFF, 0xEF])
+        ];
+        for &(encoded, decoded) in cases.iter() {
+            assert_eq!(
+                (encode(decoded), decode(encoded)),
+                (String::from(encoded), Some(decoded.to_vec())),
+                "Test base64::encode-1 failed for `{}`.", encoded
+            );
+        }
+    }
+}
@@ -0,0 +1,395 @@
+//! `body` is a module to handle message bodies which are streamed rather than held in memory.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::fmt;
+use std::io;
+use std::io::prelude::*;
+use super::{MessageHTTP, Method};
+use super::headers;
+use super::chunked::Encoder;
+use super::header_field::HeaderField;
+use super::start_line::StartLine;
+
+/// The number of bytes a `Reader` body is streamed in at a time.
+pub const CHUNK_SIZE: usize = 8 * 1024;
+
+/// A `Body` is the body of a `Response`, either held in memory or read as it is sent.
+pub enum Body {
+    /// There is no body.
+    Empty,
+    /// The body is held in memory.
+    Bytes(Vec<u8>),
+    /// The body is read as it is sent, with its length if it is known.
+    Reader(Box<dyn Read + Send>, Option<u64>)
+}
+
+impl Body {
+    /// Returns the length of the body, or `None` if it is a `Reader` of unknown length.
+    pub fn known_length(&self) -> Option<u64> {
+        match *self {
+            Body::Empty => Some(0),
+            Body::Bytes(ref bytes) => Some(bytes.len() as u64),
+            Body::Reader(_, len) => len
+        }
+    }
+}
+
+impl From<Vec<u8>> for Body {
+    /// Returns a `Bytes` body holding the passed bytes.
+    fn from(bytes: Vec<u8>) -> Body {
+        Body::Bytes(bytes)
+    }
+}
+
+impl fmt::Debug for Body {
+    /// Writes the variant and length of the body, a `Reader` can not be inspected.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Body::Empty => write!(f, "Empty"),
+            Body::Bytes(ref bytes) => write!(f, "Bytes(<{} bytes>)", bytes.len()),
+            Body::Reader(_, Some(len)) => write!(f, "Reader(<{} bytes>)", len),
+            Body::Reader(_, None) => write!(f, "Reader(<unknown length>)")
+        }
+    }
+}
+
+#[derive(Debug)]
+/// A `Response` is a HTTP message whose `Body` may be streamed, e.g. a large file.
+///
+/// Unlike a `MessageHTTP` it can not be cloned or compared as its body may only be
+/// read once.
+pub struct Response {
+    /// The first line of the message, normally a `StatusLine`.
+    pub start_line: StartLine,
+    /// The fields of the message.
+    pub header_fields: Vec<HeaderField>,
+    /// The body of the message.
+    pub body: Body
+}
+
+impl Response {
+    /// Returns a new `Response` built from the given parts.
+    ///
+    /// # Params
+    ///
+    /// start_line --- The `StartLine` for the message.</br>
+    /// header_fields --- The `HeaderField`s to modify the message.</br>
+    /// body --- The body of the message.
+    pub fn new(start_line: StartLine, header_fields: Vec<HeaderField>, body: Body) -> Response {
+        Response { start_line, header_fields, body }
+    }
+    /// Writes the `Response` to `writer`, reading a `Reader` body `CHUNK_SIZE` bytes at a
+    /// time so it is never held in memory.
+    ///
+    /// A body of known length is sent with a `Content-Length`, `0` for an `Empty` body, and
+    /// a `Reader` of unknown length with `Transfer-Encoding: chunked`, either replacing any
+    /// framing fields on the message. The body of a `1xx`, `204 No Content` or
+    /// `304 Not Modified` response is not sent and leaves the Header Fields as they are.</br>
+    /// Fails if writing fails or if a `Reader` ends before its known length. Fails with
+    /// `InvalidData`, before anything is written, if a `1xx`, `204 No Content` or
+    /// `304 Not Modified` response has a body, `Content-Length` or `Transfer-Encoding`.
+    ///
+    /// # Params
+    ///
+    /// writer --- The writer to send the message to.
+    pub fn write_to<W: Write>(self, writer: &mut W) -> io::Result<()> {
+        self.write_parts_to(writer, None)
+    }
+    /// Writes the `Response` to `writer` as `write_to` would, without the body if a
+    /// response to the passed `Method` must not have one.
+    ///
+    /// A response to a `HEAD` request still has the framing fields of its body, so the
+    /// client learns the `Content-Length` it would have had, but the body is never read.
+    /// An `Empty` response to a `HEAD` request keeps the framing fields it has, as its body
+    /// was already left out.
+    ///
+    /// # Params
+    ///
+    /// writer --- The writer to send the message to.</br>
+    /// method --- The method of the request being responded to.
+    pub fn write_response_to<W: Write>(self, writer: &mut W, method: &Method) -> io::Result<()> {
+        self.write_parts_to(writer, Some(method))
+    }
+    /// Writes the `Response` to `writer`.
+    ///
+    /// # Params
+    ///
+    /// writer --- The writer to send the message to.</br>
+    /// method --- The method of the request being responded to, if it is known.
+    fn write_parts_to<W: Write>(self, writer: &mut W, method: Option<&Method>) -> io::Result<()> {
+        // A client will not read a body for these responses, so it would be read as the
+        // next response instead.
+        if self.start_line.as_status().is_some_and(|(_, code, _)| !code.allows_body()) {
+            let framed = self.header_fields.iter()
+                .any(|field| field.name_eq(headers::CONTENT_LENGTH) || field.name_eq(headers::TRANSFER_ENCODING));
+            
+            if framed || self.body.known_length() != Some(0) {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "The response can not have a body."));
+            }
+        }
+        let send_body = match self.start_line.as_status() {
+            Some((_, code, _)) => method.map_or(code.allows_body(), |method| method.response_body_allowed(code)),
+            None => true
+        };
+        let framing = match self.body {
+            Body::Empty if method == Some(&Method::Head) => None,
+            ref body if send_body || method == Some(&Method::Head) => Some(body.known_length()),
+            _ => None
+        };
+        
+        let mut head = format!("{}\r\n", self.start_line);
+        for field in self.header_fields.iter() {
+            if framing.is_some() && (field.name_eq(headers::CONTENT_LENGTH) || field.name_eq(headers::TRANSFER_ENCODING)) {
+                continue;
+            }
+            head.push_str(&format!("{}: {}\r\n", field.canonical_name(), field.value));
+        }
+        match framing {
+            Some(Some(len)) => head.push_str(&format!("{}: {}\r\n", headers::CONTENT_LENGTH, len)),
+            Some(None) => head.push_str(&format!("{}: chunked\r\n", headers::TRANSFER_ENCODING)),
+            None => ()
+        }
+        head.push_str("\r\n");
+        writer.write_all(head.as_bytes())?;
+        if !send_body {
+            return Ok(());
+        }
+        
+        match self.body {
+            Body::Empty => Ok(()),
+            Body::Bytes(bytes) => writer.write_all(&bytes),
+            Body::Reader(reader, Some(len)) => {
+                if copy_chunks(&mut reader.take(len), writer)? < len {
+                    return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "The body ended before its Content-Length."));
+                }
+                Ok(())
+            },
+            Body::Reader(mut reader, None) => {
+                let mut encoder = Encoder::new(writer);
+                
+                copy_chunks(&mut reader, &mut encoder)?;
+                encoder.finish()?;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl From<MessageHTTP> for Response {
+    /// Returns a `Response` with the same parts as the `MessageHTTP`, with a `Bytes` body
+    /// or an `Empty` body if the message has none.
+    fn from(message: MessageHTTP) -> Response {
+        let body = if message.message_body.is_empty() {
+            Body::Empty
+        } else {
+            Body::Bytes(message.message_body)
+        };
+        
+        Response::new(message.start_line, message.header_fields, body)
+    }
+}
+
+/// Copies everything from `reader` to `writer`, `CHUNK_SIZE` bytes at a time.</br>
+/// Returns the number of bytes copied.
+///
+/// # Params
+///
+/// reader --- The reader to copy from.</br>
+/// writer --- The writer to copy to.
+fn copy_chunks<R: Read + ?Sized, W: Write>(reader: &mut R, writer: &mut W) -> io::Result<u64> {
+    let mut buffer = [0; CHUNK_SIZE];
+    let mut copied = 0;
+    
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => return Ok(copied),
+            Ok(read) => read,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e)
+        };
+        writer.write_all(&buffer[..read])?;
+        copied += read as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// The length of the synthetic bodies, 10 MB.
+    const LEN: u64 = 10 * 1024 * 1024;
+    
+    /// A reader of `remaining` bytes which are generated as they are read.
+    struct Synthetic {
+        remaining: u64
+    }
+    
+    impl Read for Synthetic {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let read = (buf.len() as u64).min(self.remaining) as usize;
+            
+            for (i, b) in buf[..read].iter_mut().enumerate() {
+                *b = b'a' + (i % 26) as u8;
+            }
+            self.remaining -= read as u64;
+            Ok(read)
+        }
+    }
+    
+    /// A writer which only keeps the start and end of what is written.
+    #[derive(Default)]
+    struct Sink {
+        /// The first bytes written.
+        start: Vec<u8>,
+        /// The last bytes written.
+        end: Vec<u8>,
+        /// The number of bytes written.
+        len: u64
+    }
+    
+    impl Write for Sink {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let keep = buf.len().min(128usize.saturating_sub(self.start.len()));
+            
+            self.start.extend_from_slice(&buf[..keep]);
+            self.end.extend_from_slice(buf);
+            let excess = self.end.len().saturating_sub(16);
+            self.end.drain(..excess);
+            self.len += buf.len() as u64;
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+    
+    /// Returns a `200 OK` `Response` with the passed body and a stale `Content-Length`.
+    fn response(body: Body) -> Response {
+        let message = MessageHTTP::from("HTTP/1.1 200 OK\r\nContent-Length: 3\r\nServer: web_server\r\n\r\nabc").unwrap();
+        
+        Response { body, ..Response::from(message) }
+    }
+
+    #[test]
+    fn test_write_to_known_length() {
+        let mut sink = Sink::default();
+        
+        response(Body::Reader(Box::new(Synthetic { remaining: LEN }), Some(LEN))).write_to(&mut sink).unwrap();
+        let head = "HTTP/1.1 200 OK\r\nServer: web_server\r\nContent-Length: 10485760\r\n\r\n";
+        assert_eq!(
+            (&sink.start[..head.len()], sink.len),
+            (head.as_bytes(), head.len() as u64 + LEN),
+            "Test Response::write_to-1 failed."
+        );
+        
+        let result = response(Body::Reader(Box::new(Synthetic { remaining: 10 }), Some(11))).write_to(&mut Sink::default());
+        assert_eq!(
+            result.map_err(|e| e.kind()),
+            Err(io::ErrorKind::UnexpectedEof),
+            "Test Response::write_to-2 failed."
+        );
+    }
+    #[test]
+    fn test_write_to_chunked() {
+        let mut sink = Sink::default();
+        
+        response(Body::Reader(Box::new(Synthetic { remaining: LEN }), None)).write_to(&mut sink).unwrap();
+        let head = "HTTP/1.1 200 OK\r\nServer: web_server\r\nTransfer-Encoding: chunked\r\n\r\n";
+        // Every chunk is `CHUNK_SIZE` bytes framed by `2000\r\n` and `\r\n`, then `0\r\n\r\n`.
+        let chunks = LEN / CHUNK_SIZE as u64;
+        assert_eq!(
+            (&sink.start[..head.len() + 9], sink.len),
+            (format!("{}2000\r\nabc", head).as_bytes(), head.len() as u64 + LEN + chunks * 8 + 5),
+            "Test Response::write_to-3 failed."
+        );
+        assert!(
+            sink.end.ends_with(b"\r\n0\r\n\r\n"),
+            "Test Response::write_to-4 failed."
+        );
+    }
+    #[test]
+    fn test_write_to_buffered() {
+        let mut bytes = Vec::new();
+        
+        response(Body::from(b"hello".to_vec())).write_to(&mut bytes).unwrap();
+        assert_eq!(
+            bytes,
+            b"HTTP/1.1 200 OK\r\nServer: web_server\r\nContent-Length: 5\r\n\r\nhello".to_vec(),
+            "Test Response::write_to-5 failed."
+        );
+        
+        let mut bytes = Vec::new();
+        Response::from(MessageHTTP::from("HTTP/1.1 204 No Content\r\nServer: web_server\r\n\r\n").unwrap())
+            .write_to(&mut bytes)
+            .unwrap();
+        assert_eq!(
+            bytes,
+            b"HTTP/1.1 204 No Content\r\nServer: web_server\r\n\r\n".to_vec(),
+            "Test Response::write_to-6 failed."
+        );
+        
+        let mut bytes = Vec::new();
+        response(Body::Empty).write_to(&mut bytes).unwrap();
+        assert_eq!(
+            bytes,
+            b"HTTP/1.1 200 OK\r\nServer: web_server\r\nContent-Length: 0\r\n\r\n".to_vec(),
+            "Test Response::write_to-7 failed."
+        );
+        
+        let mut bytes = Vec::new();
+        response(Body::Empty).write_response_to(&mut bytes, &Method::Head).unwrap();
+        assert_eq!(
+            bytes,
+            b"HTTP/1.1 200 OK\r\nContent-Length: 3\r\nServer: web_server\r\n\r\n".to_vec(),
+            "Test Response::write_to-8 failed."
+        );
+    }
+    #[test]
+    fn test_write_response_to() {
+        let mut bytes = Vec::new();
+        response(Body::Reader(Box::new(Synthetic { remaining: LEN }), Some(LEN))).write_response_to(&mut bytes, &Method::Head).unwrap();
+        assert_eq!(
+            bytes,
+            b"HTTP/1.1 200 OK\r\nServer: web_server\r\nContent-Length: 10485760\r\n\r\n".to_vec(),
+            "Test Response::write_response_to-1 failed."
+        );
+        
+        let mut bytes = Vec::new();
+        response(Body::from(b"hello".to_vec())).write_response_to(&mut bytes, &Method::Get).unwrap();
+        assert_eq!(
+            bytes,
+            b"HTTP/1.1 200 OK\r\nServer: web_server\r\nContent-Length: 5\r\n\r\nhello".to_vec(),
+            "Test Response::write_response_to-2 failed."
+        );
+        
+        // A `304 Not Modified` can not have a body or framing, whatever the method.
+        for method in [None, Some(Method::Get)].iter() {
+            let mut response = response(Body::from(b"hello".to_vec()));
+            let mut bytes = Vec::new();
+            
+            response.start_line = StartLine::from("HTTP/1.1 304 Not Modified").unwrap();
+            let result = match *method {
+                Some(ref method) => response.write_response_to(&mut bytes, method),
+                None => response.write_to(&mut bytes)
+            };
+            assert_eq!(
+                (result.map_err(|e| e.kind()), bytes),
+                (Err(io::ErrorKind::InvalidData), Vec::new()),
+                "Test Response::write_response_to-3 failed for `{:?}`.", method
+            );
+        }
+        
+        let mut response = response(Body::Empty);
+        let mut bytes = Vec::new();
+        response.start_line = StartLine::from("HTTP/1.1 304 Not Modified").unwrap();
+        assert_eq!(
+            response.write_to(&mut bytes).map_err(|e| e.kind()),
+            Err(io::ErrorKind::InvalidData),
+            "Test Response::write_response_to-4 failed."
+        );
+    }
+}
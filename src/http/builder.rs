@@ -0,0 +1,565 @@
+//! `builder` is a module to handle the construction of complete HTTP messages one component at a time.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::string::String;
+use super::{MessageHTTP, BuildError, StatusCode, Version, Method};
+use super::headers;
+use super::header_field::HeaderField;
+use super::start_line::StartLine;
+#[cfg(feature = "json")]
+use serde::Serialize;
+
+/// A `ResponseBuilder` constructs a response `MessageHTTP` one component at a time.
+pub struct ResponseBuilder {
+    /// The status code of the response.
+    code: StatusCode,
+    /// The optional reason given for the response.
+    reason: Option<String>,
+    /// The `HeaderField`s of the response.
+    header_fields: Vec<HeaderField>,
+    /// The body of the response, if one has been set.
+    body: Option<Vec<u8>>
+}
+
+impl ResponseBuilder {
+    /// Returns a new `ResponseBuilder` for a response with the passed status code.
+    ///
+    /// # Params
+    ///
+    /// code --- The status code of the response.
+    pub fn new<C: Into<StatusCode>>(code: C) -> ResponseBuilder {
+        ResponseBuilder {
+            code: code.into(),
+            reason: None,
+            header_fields: Vec::new(),
+            body: None
+        }
+    }
+    /// Sets the reason given for the response, replacing the code's canonical reason phrase.
+    ///
+    /// # Params
+    ///
+    /// reason --- The reason for the response.
+    pub fn reason(mut self, reason: &str) -> ResponseBuilder {
+        self.reason = Some(String::from(reason));
+        self
+    }
+    /// Appends a `HeaderField` to the response.
+    ///
+    /// # Params
+    ///
+    /// name --- The name of the `HeaderField`.</br>
+    /// value --- The value of the `HeaderField`.
+    pub fn header(mut self, name: &str, value: &str) -> ResponseBuilder {
+        self.header_fields.push(
+            HeaderField {
+                name: String::from(name),
+                value: String::from(value)
+            }
+        );
+        self
+    }
+    /// Sets the body of the response.
+    ///
+    /// # Params
+    ///
+    /// body --- The bytes of the body.
+    pub fn body<B: Into<Vec<u8>>>(mut self, body: B) -> ResponseBuilder {
+        self.body = Some(body.into());
+        self
+    }
+    #[cfg(feature = "json")]
+    /// Sets the body of the response to the passed value serialized as JSON and sets
+    /// the `Content-Type` to `application/json`.
+    ///
+    /// # Params
+    ///
+    /// value --- The value to serialize.
+    pub fn json<T: Serialize + ?Sized>(mut self, value: &T) -> Result<ResponseBuilder, BuildError> {
+        match ::serde_json::to_vec(value) {
+            Ok(body) => {
+                self.header_fields.retain(|field| !field.name_eq(headers::CONTENT_TYPE));
+                self.header_fields.push(
+                    HeaderField {
+                        name: String::from(headers::CONTENT_TYPE),
+                        value: String::from("application/json")
+                    }
+                );
+                Ok(self.body(body))
+            },
+            Err(e) => Err(BuildError::InvalidJson(e.to_string()))
+        }
+    }
+    /// Returns the built `MessageHTTP`.
+    ///
+    /// The version is always `HTTP/1.1`, if no reason was set the code's canonical reason
+    /// phrase is used and, if a body was set, the `Content-Length` is set to the length
+    /// of the body.</br>
+    /// Fails if the code is not from 100 to 599, if a `1xx`, `204 No Content` or
+    /// `304 Not Modified` response has a body, `Content-Length` or `Transfer-Encoding`, if
+    /// the reason contains a control character other than a horizontal tab or if any
+    /// `HeaderField` name is not a token or value contains a CR, LF or NUL.
+    pub fn build(self) -> Result<MessageHTTP, BuildError> {
+        if !self.code.is_valid() {
+            return Err(BuildError::StatusCodeOutOfRange(self.code.as_u16()));
+        }
+        // A CR or LF in the reason would end the Status line early.
+        if let Some(ref reason) = self.reason {
+            if reason.chars().any(|c| c.is_control() && c != '\t') {
+                return Err(BuildError::InvalidReason(reason.clone()));
+            }
+        }
+        // A client will not read a body for these responses, so it would be read as the
+        // next response instead.
+        if !self.code.allows_body() && (
+            self.body.is_some() || self.header_fields.iter()
+                .any(|field| field.name_eq(headers::CONTENT_LENGTH) || field.name_eq(headers::TRANSFER_ENCODING))
+        ) {
+            return Err(BuildError::BodyNotAllowed(self.code.as_u16()));
+        }
+        check_header_fields(&self.header_fields)?;
+        
+        let code = self.code;
+        let message = MessageHTTP::new(
+            StartLine::StatusLine {
+                version: Version::HTTP_11,
+                code,
+                reason: self.reason.or_else(|| code.reason_phrase().map(String::from))
+            },
+            self.header_fields,
+            Vec::new()
+        );
+        Ok(with_body(message, self.body))
+    }
+}
+
+/// A `RequestBuilder` constructs a request `MessageHTTP` one component at a time.
+pub struct RequestBuilder {
+    /// The method of the request.
+    method: String,
+    /// The resource target of the request.
+    target: String,
+    /// The `HeaderField`s of the request.
+    header_fields: Vec<HeaderField>,
+    /// The body of the request, if one has been set.
+    body: Option<Vec<u8>>
+}
+
+impl RequestBuilder {
+    /// Returns a new `RequestBuilder` for a request with the passed method and target.
+    ///
+    /// # Params
+    ///
+    /// method --- The method of the request, case-insensitive.</br>
+    /// target --- The resource target of the request.
+    pub fn new(method: &str, target: &str) -> RequestBuilder {
+        RequestBuilder {
+            method: String::from(method),
+            target: String::from(target),
+            header_fields: Vec::new(),
+            body: None
+        }
+    }
+    /// Returns a new `RequestBuilder` for a `GET` request.
+    ///
+    /// # Params
+    ///
+    /// target --- The resource target of the request.
+    pub fn get(target: &str) -> RequestBuilder {
+        RequestBuilder::new("GET", target)
+    }
+    /// Returns a new `RequestBuilder` for a `HEAD` request.
+    ///
+    /// # Params
+    ///
+    /// target --- The resource target of the request.
+    pub fn head(target: &str) -> RequestBuilder {
+        RequestBuilder::new("HEAD", target)
+    }
+    /// Returns a new `RequestBuilder` for a `POST` request.
+    ///
+    /// # Params
+    ///
+    /// target --- The resource target of the request.
+    pub fn post(target: &str) -> RequestBuilder {
+        RequestBuilder::new("POST", target)
+    }
+    /// Returns a new `RequestBuilder` for a `PUT` request.
+    ///
+    /// # Params
+    ///
+    /// target --- The resource target of the request.
+    pub fn put(target: &str) -> RequestBuilder {
+        RequestBuilder::new("PUT", target)
+    }
+    /// Returns a new `RequestBuilder` for a `DELETE` request.
+    ///
+    /// # Params
+    ///
+    /// target --- The resource target of the request.
+    pub fn delete(target: &str) -> RequestBuilder {
+        RequestBuilder::new("DELETE", target)
+    }
+    /// Returns a new `RequestBuilder` for a `CONNECT` request.
+    ///
+    /// # Params
+    ///
+    /// target --- The resource target of the request.
+    pub fn connect(target: &str) -> RequestBuilder {
+        RequestBuilder::new("CONNECT", target)
+    }
+    /// Returns a new `RequestBuilder` for an `OPTIONS` request.
+    ///
+    /// # Params
+    ///
+    /// target --- The resource target of the request.
+    pub fn options(target: &str) -> RequestBuilder {
+        RequestBuilder::new("OPTIONS", target)
+    }
+    /// Returns a new `RequestBuilder` for a `TRACE` request.
+    ///
+    /// # Params
+    ///
+    /// target --- The resource target of the request.
+    pub fn trace(target: &str) -> RequestBuilder {
+        RequestBuilder::new("TRACE", target)
+    }
+    /// Returns a new `RequestBuilder` for a `PATCH` request.
+    ///
+    /// # Params
+    ///
+    /// target --- The resource target of the request.
+    pub fn patch(target: &str) -> RequestBuilder {
+        RequestBuilder::new("PATCH", target)
+    }
+    /// Appends a `HeaderField` to the request.
+    ///
+    /// # Params
+    ///
+    /// name --- The name of the `HeaderField`.</br>
+    /// value --- The value of the `HeaderField`.
+    pub fn header(mut self, name: &str, value: &str) -> RequestBuilder {
+        self.header_fields.push(
+            HeaderField {
+                name: String::from(name),
+                value: String::from(value)
+            }
+        );
+        self
+    }
+    /// Sets the body of the request.
+    ///
+    /// # Params
+    ///
+    /// body --- The bytes of the body.
+    pub fn body<B: Into<Vec<u8>>>(mut self, body: B) -> RequestBuilder {
+        self.body = Some(body.into());
+        self
+    }
+    /// Returns the built `MessageHTTP`.
+    ///
+    /// The version is always `HTTP/1.1` and, if a body was set, the `Content-Length` is
+    /// set to the length of the body.</br>
+    /// Fails if the method is not a token, if the target is empty or contains
+    /// whitespace or control characters, or if any `HeaderField` name is not a token or
+    /// value contains a CR, LF or NUL.
+    pub fn build(self) -> Result<MessageHTTP, BuildError> {
+        let method = match Method::from(&self.method) {
+            Ok(method) => method,
+            Err(_) => return Err(BuildError::InvalidMethod(self.method))
+        };
+        check_target(&self.target)?;
+        check_header_fields(&self.header_fields)?;
+        
+        let message = MessageHTTP::new(
+            StartLine::RequestLine {
+                method,
+                target: self.target,
+                version: Version::HTTP_11
+            },
+            self.header_fields,
+            Vec::new()
+        );
+        Ok(with_body(message, self.body))
+    }
+}
+
+/// Fails if the request target is empty or contains whitespace or control characters.
+///
+/// # Params
+///
+/// target --- The request target to check.
+pub(crate) fn check_target(target: &str) -> Result<(), BuildError> {
+    if target.is_empty() || target.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        Err(BuildError::InvalidTarget(String::from(target)))
+    } else {
+        Ok(())
+    }
+}
+
+/// Fails if any of the `HeaderField` names are not tokens or values contain a CR, LF or NUL.
+///
+/// # Params
+///
+/// header_fields --- The `HeaderField`s to check.
+fn check_header_fields(header_fields: &[HeaderField]) -> Result<(), BuildError> {
+    header_fields.iter().try_for_each(HeaderField::validate)
+}
+
+/// Returns the message with the passed body and a matching `Content-Length`, if there is a body.
+///
+/// # Params
+///
+/// message --- The message to set the body of.</br>
+/// body --- The body to set.
+fn with_body(mut message: MessageHTTP, body: Option<Vec<u8>>) -> MessageHTTP {
+    if let Some(body) = body {
+        message.set_header(headers::CONTENT_LENGTH, &body.len().to_string())
+            .expect("A `Content-Length` is always a valid Header Field value.");
+        message.message_body = body;
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::HTTP;
+
+    #[test]
+    fn test_response_builder() {
+        let message = ResponseBuilder::new(StatusCode::OK)
+            .reason("OK")
+            .header("Content-Type", "text/html")
+            .body("<h1>Hello</h1>")
+            .build()
+            .unwrap();
+        
+        assert_eq!(
+            message,
+            MessageHTTP {
+                start_line: StartLine::StatusLine {
+                    version: Version::HTTP_11,
+                    code: StatusCode::OK,
+                    reason: Some(String::from("OK"))
+                },
+                header_fields: vec![
+                    HeaderField {
+                        name: String::from("Content-Type"),
+                        value: String::from("text/html")
+                    },
+                    HeaderField {
+                        name: String::from("Content-Length"),
+                        value: String::from("14")
+                    }
+                ],
+                message_body: b"<h1>Hello</h1>".to_vec()
+            },
+            "Test ResponseBuilder-1 failed."
+        );
+        
+        assert_eq!(
+            message.to_http().unwrap(),
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: 14\r\n\r\n<h1>Hello</h1>",
+            "Test ResponseBuilder-2 failed."
+        );
+        
+        assert_eq!(
+            ResponseBuilder::new(404).build().unwrap().to_http().unwrap(),
+            "HTTP/1.1 404 Not Found\r\n\r\n",
+            "Test ResponseBuilder-3 failed."
+        );
+        
+        assert_eq!(
+            ResponseBuilder::new(200).header("Content-Length", "99").body(Vec::new()).build().unwrap().to_http().unwrap(),
+            "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n",
+            "Test ResponseBuilder-4 failed."
+        );
+        
+        assert_eq!(
+            ResponseBuilder::new(302).header("Location", "/a\r\nSet-Cookie: evil").build(),
+            Err(BuildError::InvalidHeaderValue(String::from("/a\r\nSet-Cookie: evil"))),
+            "Test ResponseBuilder-5 failed."
+        );
+        
+        assert_eq!(
+            ResponseBuilder::new(200).reason("OK\r\nSet-Cookie: admin=1").build(),
+            Err(BuildError::InvalidReason(String::from("OK\r\nSet-Cookie: admin=1"))),
+            "Test ResponseBuilder-5 failed for the reason."
+        );
+        
+        assert_eq!(
+            ResponseBuilder::new(200).reason("OK\0").build(),
+            Err(BuildError::InvalidReason(String::from("OK\0"))),
+            "Test ResponseBuilder-5 failed for a NUL in the reason."
+        );
+        
+        assert_eq!(
+            ResponseBuilder::new(299).build().unwrap().to_http().unwrap(),
+            "HTTP/1.1 299\r\n\r\n",
+            "Test ResponseBuilder-6 failed."
+        );
+        
+        assert_eq!(
+            ResponseBuilder::new(StatusCode::NOT_FOUND).reason("Gone Fishing").build().unwrap().to_http().unwrap(),
+            "HTTP/1.1 404 Gone Fishing\r\n\r\n",
+            "Test ResponseBuilder-7 failed."
+        );
+        
+        assert_eq!(
+            ResponseBuilder::new(200).header("X Header", "value").build(),
+            Err(BuildError::InvalidHeaderName(String::from("X Header"))),
+            "Test ResponseBuilder-8 failed."
+        );
+        
+        assert_eq!(
+            ResponseBuilder::new(200).header("", "value").build(),
+            Err(BuildError::InvalidHeaderName(String::new())),
+            "Test ResponseBuilder-9 failed."
+        );
+        
+        for &code in [0, 99, 600, u16::MAX].iter() {
+            assert_eq!(
+                ResponseBuilder::new(code).build(),
+                Err(BuildError::StatusCodeOutOfRange(code)),
+                "Test ResponseBuilder-10 failed for `{}`.", code
+            );
+        }
+        
+        for &code in [100, 101, 204, 304].iter() {
+            assert_eq!(
+                (
+                    ResponseBuilder::new(code).body("stale").build(),
+                    ResponseBuilder::new(code).body(Vec::new()).build(),
+                    ResponseBuilder::new(code).header("Content-Length", "0").build(),
+                    ResponseBuilder::new(code).header("Transfer-Encoding", "chunked").build()
+                ),
+                (
+                    Err(BuildError::BodyNotAllowed(code)),
+                    Err(BuildError::BodyNotAllowed(code)),
+                    Err(BuildError::BodyNotAllowed(code)),
+                    Err(BuildError::BodyNotAllowed(code))
+                ),
+                "Test ResponseBuilder-11 failed for `{}`.", code
+            );
+        }
+        assert_eq!(
+            ResponseBuilder::new(304).header("ETag", "\"a\"").build().map(|message| message.to_http().unwrap()),
+            Ok(String::from("HTTP/1.1 304 Not Modified\r\nETag: \"a\"\r\n\r\n")),
+            "Test ResponseBuilder-12 failed."
+        );
+    }
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_response_builder_json() {
+        use std::collections::BTreeMap;
+        
+        let mut value = BTreeMap::new();
+        value.insert("a", vec![1, 2]);
+        let message = ResponseBuilder::new(200)
+            .header("Content-Type", "text/plain")
+            .json(&value)
+            .unwrap()
+            .build()
+            .unwrap();
+        
+        assert_eq!(
+            message.to_http().unwrap(),
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 11\r\n\r\n{\"a\":[1,2]}",
+            "Test ResponseBuilder::json-1 failed."
+        );
+        assert_eq!(
+            message.json::<BTreeMap<String, Vec<u32>>>().unwrap().get("a"),
+            Some(&vec![1, 2]),
+            "Test ResponseBuilder::json-2 failed."
+        );
+        
+        let mut value = BTreeMap::new();
+        value.insert(vec![1], 1);
+        match ResponseBuilder::new(200).json(&value) {
+            Err(BuildError::InvalidJson(_)) => (),
+            _ => panic!("Test ResponseBuilder::json-3 failed.")
+        }
+    }
+    #[test]
+    fn test_request_builder() {
+        let message = RequestBuilder::get("/index.html")
+            .header("Host", "example.com")
+            .build()
+            .unwrap();
+        
+        assert_eq!(
+            message.to_http().unwrap(),
+            "GET /index.html HTTP/1.1\r\nHost: example.com\r\n\r\n",
+            "Test RequestBuilder-1 failed."
+        );
+        assert_eq!(
+            MessageHTTP::from(&message.to_http().unwrap()).unwrap(),
+            message,
+            "Test RequestBuilder-2 failed."
+        );
+        
+        let message = RequestBuilder::new("post", "/submit?a=1")
+            .header("Content-Type", "text/plain")
+            .body("hello\r\nworld")
+            .build()
+            .unwrap();
+        assert_eq!(
+            message.to_http().unwrap(),
+            "POST /submit?a=1 HTTP/1.1\r\nContent-Type: text/plain\r\nContent-Length: 12\r\n\r\nhello\r\nworld",
+            "Test RequestBuilder-3 failed."
+        );
+        assert_eq!(
+            MessageHTTP::from(&message.to_http().unwrap()).unwrap(),
+            message,
+            "Test RequestBuilder-4 failed."
+        );
+        
+        let builders = [
+            RequestBuilder::head("/"), RequestBuilder::put("/"), RequestBuilder::delete("/"),
+            RequestBuilder::connect("example.com:443"), RequestBuilder::options("*"),
+            RequestBuilder::trace("/"), RequestBuilder::patch("/")
+        ];
+        for (builder, method) in builders.iter().zip(Method::ALL.iter().skip(1).filter(|m| **m != Method::Post)) {
+            assert_eq!(
+                builder.method,
+                method.as_str(),
+                "Test RequestBuilder-5 failed for `{}`.", method
+            );
+        }
+        
+        assert_eq!(
+            RequestBuilder::new("BR EW", "/").build(),
+            Err(BuildError::InvalidMethod(String::from("BR EW"))),
+            "Test RequestBuilder-6 failed."
+        );
+        
+        assert_eq!(
+            RequestBuilder::get("/a b").build(),
+            Err(BuildError::InvalidTarget(String::from("/a b"))),
+            "Test RequestBuilder-7 failed."
+        );
+        
+        assert_eq!(
+            RequestBuilder::get("/a\0").build(),
+            Err(BuildError::InvalidTarget(String::from("/a\0"))),
+            "Test RequestBuilder-8 failed."
+        );
+        
+        assert_eq!(
+            RequestBuilder::get("").build(),
+            Err(BuildError::InvalidTarget(String::new())),
+            "Test RequestBuilder-9 failed."
+        );
+        
+        assert_eq!(
+            RequestBuilder::get("/").header("Host ", "localhost").build(),
+            Err(BuildError::InvalidHeaderName(String::from("Host "))),
+            "Test RequestBuilder-10 failed."
+        );
+    }
+}
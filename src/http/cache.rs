@@ -0,0 +1,498 @@
+//! `cache` is a module to handle the directives of the `Cache-Control` Header Field and
+//! the freshness of cached responses.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::fmt;
+use std::string::String;
+use std::time::{Duration, SystemTime};
+use super::{MessageHTTP, ParseError};
+use super::headers;
+use super::token;
+use super::date;
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+/// A `CacheControl` holds the directives of a `Cache-Control` Header Field.
+///
+/// Directives are written in the order `public`, `private`, `no-cache`, `no-store`,
+/// `max-age`, `s-maxage`, `must-revalidate`, `immutable`, `stale-while-revalidate`
+/// then any extensions.
+pub struct CacheControl {
+    /// The `no-cache` directive, a cache must revalidate before using a stored response.
+    pub no_cache: bool,
+    /// The `no-store` directive, a cache must not store the message.
+    pub no_store: bool,
+    /// The `private` directive, a shared cache must not store the response.
+    pub private: bool,
+    /// The `public` directive, any cache may store the response.
+    pub public: bool,
+    /// The `must-revalidate` directive, a stale response must not be used unrevalidated.
+    pub must_revalidate: bool,
+    /// The `immutable` directive, the response will not change while it is fresh.
+    pub immutable: bool,
+    /// The `max-age` directive, the number of seconds the response is fresh for.
+    pub max_age: Option<u64>,
+    /// The `s-maxage` directive, the `max_age` for shared caches.
+    pub s_maxage: Option<u64>,
+    /// The `stale-while-revalidate` directive, the number of seconds a stale response may
+    /// be used while it is revalidated in the background.
+    pub stale_while_revalidate: Option<u64>,
+    /// Any other directives in order, with their unquoted arguments.
+    pub extensions: Vec<(String, Option<String>)>
+}
+
+impl CacheControl {
+    /// Returns a new `CacheControl` with no directives.
+    pub fn new() -> CacheControl {
+        CacheControl::default()
+    }
+    /// Converts the passed `Cache-Control` value to a `CacheControl`.
+    ///
+    /// Directive names are case-insensitive and arguments may be quoted. Field names
+    /// given as the argument of `no-cache` or `private` are ignored, so the directive
+    /// applies to the whole response. If a numeric directive is repeated the smallest
+    /// value is kept.</br>
+    /// Fails if a directive is not a token or a numeric directive does not have a
+    /// non-negative integer argument.
+    ///
+    /// # Params
+    ///
+    /// value --- The value of the `Cache-Control` Header Field.
+    pub fn from(value: &str) -> Result<CacheControl, ParseError> {
+        let mut res = CacheControl::new();
+        
+        for directive in token::split_list(value) {
+            let invalid = || ParseError::InvalidCacheControl(String::from(directive));
+            let (name, argument) = match directive.find('=') {
+                Some(index) => (directive[..index].trim(), Some(token::unquote(directive[index + 1..].trim()))),
+                None => (directive, None)
+            };
+            if !token::is_token(name) {
+                return Err(invalid());
+            }
+            
+            let seconds = match name.to_ascii_lowercase().as_str() {
+                "max-age" => &mut res.max_age,
+                "s-maxage" => &mut res.s_maxage,
+                "stale-while-revalidate" => &mut res.stale_while_revalidate,
+                lowercase => {
+                    match lowercase {
+                        "no-cache" => res.no_cache = true,
+                        "no-store" => res.no_store = true,
+                        "private" => res.private = true,
+                        "public" => res.public = true,
+                        "must-revalidate" => res.must_revalidate = true,
+                        "immutable" => res.immutable = true,
+                        _ => res.extensions.push((String::from(name), argument))
+                    }
+                    continue;
+                }
+            };
+            
+            // Only plain digits are valid, `parse` would also accept a sign.
+            let argument = match argument {
+                Some(ref argument) if !argument.is_empty() && argument.bytes().all(|b| b.is_ascii_digit()) => argument,
+                _ => return Err(invalid())
+            };
+            // A delta-seconds too large to represent is treated as the largest value.
+            let value = argument.parse().unwrap_or(u64::MAX);
+            *seconds = Some(seconds.map_or(value, |seconds| seconds.min(value)));
+        }
+        Ok(res)
+    }
+    /// Sets the `no-cache` directive.
+    ///
+    /// # Params
+    ///
+    /// no_cache --- If a cache must revalidate before using a stored response.
+    pub fn no_cache(mut self, no_cache: bool) -> CacheControl {
+        self.no_cache = no_cache;
+        self
+    }
+    /// Sets the `no-store` directive.
+    ///
+    /// # Params
+    ///
+    /// no_store --- If a cache must not store the message.
+    pub fn no_store(mut self, no_store: bool) -> CacheControl {
+        self.no_store = no_store;
+        self
+    }
+    /// Sets the `private` directive.
+    ///
+    /// # Params
+    ///
+    /// private --- If a shared cache must not store the response.
+    pub fn private(mut self, private: bool) -> CacheControl {
+        self.private = private;
+        self
+    }
+    /// Sets the `public` directive.
+    ///
+    /// # Params
+    ///
+    /// public --- If any cache may store the response.
+    pub fn public(mut self, public: bool) -> CacheControl {
+        self.public = public;
+        self
+    }
+    /// Sets the `must-revalidate` directive.
+    ///
+    /// # Params
+    ///
+    /// must_revalidate --- If a stale response must not be used unrevalidated.
+    pub fn must_revalidate(mut self, must_revalidate: bool) -> CacheControl {
+        self.must_revalidate = must_revalidate;
+        self
+    }
+    /// Sets the `immutable` directive.
+    ///
+    /// # Params
+    ///
+    /// immutable --- If the response will not change while it is fresh.
+    pub fn immutable(mut self, immutable: bool) -> CacheControl {
+        self.immutable = immutable;
+        self
+    }
+    /// Sets the `max-age` directive.
+    ///
+    /// # Params
+    ///
+    /// max_age --- The number of seconds the response is fresh for.
+    pub fn max_age(mut self, max_age: u64) -> CacheControl {
+        self.max_age = Some(max_age);
+        self
+    }
+    /// Sets the `s-maxage` directive.
+    ///
+    /// # Params
+    ///
+    /// s_maxage --- The number of seconds the response is fresh for in a shared cache.
+    pub fn s_maxage(mut self, s_maxage: u64) -> CacheControl {
+        self.s_maxage = Some(s_maxage);
+        self
+    }
+    /// Sets the `stale-while-revalidate` directive.
+    ///
+    /// # Params
+    ///
+    /// stale_while_revalidate --- The number of seconds a stale response may be used
+    /// while it is revalidated.
+    pub fn stale_while_revalidate(mut self, stale_while_revalidate: u64) -> CacheControl {
+        self.stale_while_revalidate = Some(stale_while_revalidate);
+        self
+    }
+    /// Appends an extension directive.
+    ///
+    /// # Params
+    ///
+    /// name --- The name of the directive.</br>
+    /// argument --- The unquoted argument of the directive, if any.
+    pub fn extension(mut self, name: &str, argument: Option<&str>) -> CacheControl {
+        self.extensions.push((String::from(name), argument.map(String::from)));
+        self
+    }
+}
+
+impl fmt::Display for CacheControl {
+    /// Writes the directives as the value of a `Cache-Control` Header Field, quoting any
+    /// extension argument which is not a token.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut directives = Vec::new();
+        
+        for &(set, name) in [(self.public, "public"), (self.private, "private"), (self.no_cache, "no-cache"), (self.no_store, "no-store")].iter() {
+            if set {
+                directives.push(String::from(name));
+            }
+        }
+        if let Some(max_age) = self.max_age {
+            directives.push(format!("max-age={}", max_age));
+        }
+        if let Some(s_maxage) = self.s_maxage {
+            directives.push(format!("s-maxage={}", s_maxage));
+        }
+        for &(set, name) in [(self.must_revalidate, "must-revalidate"), (self.immutable, "immutable")].iter() {
+            if set {
+                directives.push(String::from(name));
+            }
+        }
+        if let Some(stale_while_revalidate) = self.stale_while_revalidate {
+            directives.push(format!("stale-while-revalidate={}", stale_while_revalidate));
+        }
+        for (name, argument) in self.extensions.iter() {
+            directives.push(
+                match *argument {
+                    Some(ref argument) if token::is_token(argument) => format!("{}={}", name, argument),
+                    Some(ref argument) => format!("{}={}", name, token::quote_string(argument)),
+                    None => name.clone()
+                }
+            );
+        }
+        write!(f, "{}", directives.join(", "))
+    }
+}
+
+/// The `Age` a response is taken to have if its `Age` Header Field is larger, as in RFC
+/// 9111 section 1.2.2.
+pub const MAX_AGE_VALUE: u64 = 1 << 31;
+
+/// Returns how long the passed response is fresh for after it was generated in a private
+/// cache, as in RFC 7234 section 4.2.1, or `None` if it has no explicit expiration time.
+///
+/// A `max-age` directive takes precedence over the `Expires` Header Field, which is
+/// measured from the `Date` Header Field or from now if the response has no valid `Date`.
+/// An invalid `Expires` is treated as a time in the past, so the response is already
+/// stale. Each directive is parsed on its own, so an invalid directive does not hide a
+/// valid `max-age`.
+///
+/// # Params
+///
+/// resp --- The response to get the freshness lifetime of.
+pub fn freshness_lifetime(resp: &MessageHTTP) -> Option<Duration> {
+    lifetime(resp, false)
+}
+
+/// Returns how long the passed response is fresh for after it was generated in a shared
+/// cache, as by `freshness_lifetime` but with an `s-maxage` directive taking precedence
+/// over a `max-age`.
+///
+/// # Params
+///
+/// resp --- The response to get the freshness lifetime of.
+pub fn shared_freshness_lifetime(resp: &MessageHTTP) -> Option<Duration> {
+    lifetime(resp, true)
+}
+
+/// Returns the freshness lifetime of the passed response.
+///
+/// # Params
+///
+/// resp --- The response to get the freshness lifetime of.</br>
+/// shared --- If the response is held by a shared cache.
+fn lifetime(resp: &MessageHTTP, shared: bool) -> Option<Duration> {
+    let mut max_age = None;
+    let mut s_maxage = None;
+    
+    if let Some(value) = resp.combined_header(headers::CACHE_CONTROL) {
+        for directive in token::split_list(&value).into_iter().filter_map(|directive| CacheControl::from(directive).ok()) {
+            max_age = min_seconds(max_age, directive.max_age);
+            s_maxage = min_seconds(s_maxage, directive.s_maxage);
+        }
+    }
+    let seconds = if shared { s_maxage.or(max_age) } else { max_age };
+    if let Some(seconds) = seconds {
+        return Some(Duration::from_secs(seconds));
+    }
+    
+    let expires = match date::parse(resp.header(headers::EXPIRES)?) {
+        Ok(expires) => expires,
+        Err(_) => return Some(Duration::from_secs(0))
+    };
+    let date = resp.header(headers::DATE)
+        .and_then(|value| date::parse(value).ok())
+        .unwrap_or_else(SystemTime::now);
+    Some(expires.duration_since(date).unwrap_or_default())
+}
+
+/// Returns the smaller of two repeated numeric directives.
+///
+/// # Params
+///
+/// seconds --- The value of the directive so far.</br>
+/// value --- The value of the next directive.
+fn min_seconds(seconds: Option<u64>, value: Option<u64>) -> Option<u64> {
+    match (seconds, value) {
+        (Some(seconds), Some(value)) => Some(seconds.min(value)),
+        (seconds, value) => seconds.or(value)
+    }
+}
+
+/// Returns the current age of the passed response, as in RFC 7234 section 4.2.3.
+///
+/// The age is the larger of the `Age` Header Field and the time between the `Date` Header
+/// Field and `received_at`, plus the time the response has been held since it was
+/// received. The request is taken to have been sent when the response was received. An
+/// `Age` larger than `MAX_AGE_VALUE` is taken to be `MAX_AGE_VALUE`.
+///
+/// # Params
+///
+/// resp --- The response to get the age of.</br>
+/// received_at --- The time the response was received.
+pub fn current_age(resp: &MessageHTTP, received_at: SystemTime) -> Duration {
+    age_at(resp, received_at, SystemTime::now())
+}
+
+/// Returns the age of the passed response at the time `now`.
+///
+/// # Params
+///
+/// resp --- The response to get the age of.</br>
+/// received_at --- The time the response was received.</br>
+/// now --- The time to get the age at.
+fn age_at(resp: &MessageHTTP, received_at: SystemTime, now: SystemTime) -> Duration {
+    let apparent_age = resp.header(headers::DATE)
+        .and_then(|value| date::parse(value).ok())
+        .and_then(|date| received_at.duration_since(date).ok())
+        .unwrap_or_default();
+    // An invalid `Age` is ignored.
+    let age_value = resp.header(headers::AGE)
+        .map(str::trim)
+        .filter(|age| !age.is_empty() && age.bytes().all(|b| b.is_ascii_digit()))
+        .map(|age| Duration::from_secs(age.parse().unwrap_or(MAX_AGE_VALUE).min(MAX_AGE_VALUE)))
+        .unwrap_or_default();
+    let resident_time = now.duration_since(received_at).unwrap_or_default();
+    
+    apparent_age.max(age_value).saturating_add(resident_time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_control_from() {
+        assert_eq!(
+            CacheControl::from("max-age=3600, no-cache, private, s-maxage=600").unwrap(),
+            CacheControl::new().max_age(3600).no_cache(true).private(true).s_maxage(600),
+            "Test CacheControl::from-1 failed."
+        );
+        assert_eq!(
+            CacheControl::from("Public,MAX-AGE=60 , Immutable, Stale-While-Revalidate=30, must-revalidate, no-store").unwrap(),
+            CacheControl::new().public(true).max_age(60).immutable(true).stale_while_revalidate(30).must_revalidate(true).no_store(true),
+            "Test CacheControl::from-2 failed."
+        );
+        assert_eq!(
+            CacheControl::from("max-age=\"120\", private=\"Set-Cookie, Authorization\", community=\"UCI, \\\"x\\\"\"").unwrap(),
+            CacheControl::new().max_age(120).private(true).extension("community", Some("UCI, \"x\"")),
+            "Test CacheControl::from-3 failed."
+        );
+        assert_eq!(
+            CacheControl::from("max-age=600, no-cache, max-age=60, max-age=300, no-cache").unwrap(),
+            CacheControl::new().max_age(60).no_cache(true),
+            "Test CacheControl::from-4 failed."
+        );
+        assert_eq!(
+            CacheControl::from("").unwrap(),
+            CacheControl::new(),
+            "Test CacheControl::from-5 failed."
+        );
+        assert_eq!(
+            CacheControl::from("max-age=99999999999999999999999").unwrap().max_age,
+            Some(u64::MAX),
+            "Test CacheControl::from-6 failed."
+        );
+        
+        for &(value, directive) in [("max-age=-1", "max-age=-1"), ("no-cache, s-maxage", "s-maxage"), ("max-age=ten", "max-age=ten"), ("a b", "a b")].iter() {
+            assert_eq!(
+                CacheControl::from(value),
+                Err(ParseError::InvalidCacheControl(String::from(directive))),
+                "Test CacheControl::from-7 failed for `{}`.", value
+            );
+        }
+    }
+    #[test]
+    fn test_cache_control_display() {
+        assert_eq!(
+            CacheControl::new().s_maxage(600).max_age(3600).private(true).no_cache(true).to_string(),
+            "private, no-cache, max-age=3600, s-maxage=600",
+            "Test CacheControl::fmt-1 failed."
+        );
+        assert_eq!(
+            CacheControl::new().public(true).max_age(31536000).immutable(true).to_string(),
+            "public, max-age=31536000, immutable",
+            "Test CacheControl::fmt-2 failed."
+        );
+        assert_eq!(
+            CacheControl::new().no_store(true).extension("ext", None).extension("community", Some("UCI, \"x\"")).to_string(),
+            "no-store, ext, community=\"UCI, \\\"x\\\"\"",
+            "Test CacheControl::fmt-3 failed."
+        );
+        assert_eq!(
+            CacheControl::new().to_string(),
+            "",
+            "Test CacheControl::fmt-4 failed."
+        );
+        
+        let cache_control = CacheControl::new().public(true).must_revalidate(true).stale_while_revalidate(30).extension("x-token", Some("a"));
+        assert_eq!(
+            CacheControl::from(&cache_control.to_string()),
+            Ok(cache_control),
+            "Test CacheControl::fmt-5 failed to round-trip."
+        );
+    }
+    #[test]
+    fn test_freshness_lifetime() {
+        let tests = [
+            ("Date: Sun, 06 Nov 1994 08:49:37 GMT\r\nCache-Control: public, max-age=600\r\nExpires: Sun, 06 Nov 1994 09:49:37 GMT", Some(600)),
+            ("Date: Sun, 06 Nov 1994 08:49:37 GMT\r\nExpires: Sun, 06 Nov 1994 09:49:37 GMT", Some(3600)),
+            ("Date: Sun, 06 Nov 1994 08:49:37 GMT\r\nCache-Control: no-cache\r\nExpires: Sunday, 06-Nov-94 08:50:37 GMT", Some(60)),
+            ("Date: Sun, 06 Nov 1994 08:49:37 GMT\r\nExpires: Sun, 06 Nov 1994 08:00:00 GMT", Some(0)),
+            ("Date: Sun, 06 Nov 1994 08:49:37 GMT\r\nExpires: 0", Some(0)),
+            ("Date: Sun, 06 Nov 1994 08:49:37 GMT\r\nCache-Control: max-age=ten\r\nExpires: Sun, 06 Nov 1994 08:59:37 GMT", Some(600)),
+            ("Cache-Control: max-age=600, s-maxage=ten", Some(600)),
+            ("Cache-Control: no-cache, max-age=600\r\nCache-Control: a b, max-age=60", Some(60)),
+            ("Cache-Control: s-maxage=60, max-age=600", Some(600)),
+            ("Date: Sun, 06 Nov 1994 08:49:37 GMT", None)
+        ];
+        
+        for &(fields, expected) in tests.iter() {
+            let resp = MessageHTTP::from(&format!("HTTP/1.1 200 OK\r\n{}\r\n\r\n", fields)).unwrap();
+            
+            assert_eq!(
+                freshness_lifetime(&resp),
+                expected.map(Duration::from_secs),
+                "Test cache::freshness_lifetime failed for `{}`.", fields
+            );
+        }
+        
+        let tests = [
+            ("Cache-Control: s-maxage=60, max-age=600", Some(60)),
+            ("Cache-Control: s-maxage=ten, max-age=600", Some(600)),
+            ("Cache-Control: private, s-maxage=60", Some(60)),
+            ("Cache-Control: no-cache", None)
+        ];
+        
+        for &(fields, expected) in tests.iter() {
+            let resp = MessageHTTP::from(&format!("HTTP/1.1 200 OK\r\n{}\r\n\r\n", fields)).unwrap();
+            
+            assert_eq!(
+                shared_freshness_lifetime(&resp),
+                expected.map(Duration::from_secs),
+                "Test cache::shared_freshness_lifetime failed for `{}`.", fields
+            );
+        }
+    }
+    #[test]
+    fn test_current_age() {
+        let received_at = date::parse("Sun, 06 Nov 1994 08:50:07 GMT").unwrap();
+        let now = received_at + Duration::from_secs(100);
+        let tests = [
+            ("Date: Sun, 06 Nov 1994 08:49:37 GMT", 130),
+            ("Date: Sun, 06 Nov 1994 08:49:37 GMT\r\nAge: 10", 130),
+            ("Date: Sun, 06 Nov 1994 08:49:37 GMT\r\nAge: 60", 160),
+            ("Date: Sun, 06 Nov 1994 08:55:00 GMT\r\nAge: 5", 105),
+            ("Age: -5", 100),
+            ("Age: 2147483649", MAX_AGE_VALUE + 100),
+            ("Age: 99999999999999999999999", MAX_AGE_VALUE + 100)
+        ];
+        
+        for &(fields, expected) in tests.iter() {
+            let resp = MessageHTTP::from(&format!("HTTP/1.1 200 OK\r\n{}\r\n\r\n", fields)).unwrap();
+            
+            assert_eq!(
+                age_at(&resp, received_at, now),
+                Duration::from_secs(expected),
+                "Test cache::current_age failed for `{}`.", fields
+            );
+        }
+        
+        let resp = MessageHTTP::from("HTTP/1.1 200 OK\r\nAge: 60\r\n\r\n").unwrap();
+        assert!(
+            current_age(&resp, SystemTime::now()) >= Duration::from_secs(60),
+            "Test cache::current_age-1 failed."
+        );
+    }
+}
@@ -0,0 +1,303 @@
+//! `chunked` is a module to handle the chunked transfer coding of HTTP message bodies.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::io;
+use std::io::prelude::*;
+use std::str;
+use super::ParseError;
+use super::headers;
+use super::header_field::HeaderField;
+
+/// The Header fields which may not be sent in the trailer of a chunked body, as in
+/// RFC 7230 section 4.1.2; they control framing, routing, authentication or how the
+/// body is processed and must be known before the body is read.
+pub static FORBIDDEN_TRAILERS: [&str; 29] = [
+    headers::TRANSFER_ENCODING, headers::CONTENT_LENGTH, headers::HOST, headers::CACHE_CONTROL, headers::EXPECT,
+    headers::MAX_FORWARDS, headers::PRAGMA, headers::RANGE, headers::TE, headers::IF_MATCH, headers::IF_NONE_MATCH,
+    headers::IF_MODIFIED_SINCE, headers::IF_UNMODIFIED_SINCE, headers::IF_RANGE, headers::AUTHORIZATION,
+    headers::WWW_AUTHENTICATE, headers::PROXY_AUTHENTICATE, headers::PROXY_AUTHORIZATION, headers::SET_COOKIE,
+    headers::AGE, headers::DATE, headers::EXPIRES, headers::LOCATION, headers::RETRY_AFTER, headers::VARY, headers::CONTENT_ENCODING,
+    headers::CONTENT_TYPE, headers::CONTENT_RANGE, headers::TRAILER
+];
+
+/// An `Encoder` wraps a writer and frames everything written through it as chunks.
+///
+/// Each call to `write` produces exactly one chunk and `finish` writes the last chunk
+/// which terminates the body.
+pub struct Encoder<W: Write> {
+    /// The writer which receives the framed chunks.
+    writer: W
+}
+
+impl<W: Write> Encoder<W> {
+    /// Returns a new `Encoder` which writes chunks to `writer`.
+    ///
+    /// # Params
+    ///
+    /// writer --- The writer to write the framed chunks to.
+    pub fn new(writer: W) -> Encoder<W> {
+        Encoder { writer }
+    }
+    /// Writes the terminating last chunk and returns the wrapped writer.
+    pub fn finish(self) -> io::Result<W> {
+        self.finish_with_trailers(&[])
+    }
+    /// Writes the terminating last chunk followed by the passed trailer fields and
+    /// returns the wrapped writer.
+    ///
+    /// Fails without writing anything if any of the trailers is forbidden or invalid.
+    ///
+    /// # Params
+    ///
+    /// trailers --- The `HeaderField`s to send after the body.
+    pub fn finish_with_trailers(mut self, trailers: &[HeaderField]) -> io::Result<W> {
+        let mut last = String::from("0\r\n");
+        
+        check_trailers(trailers)?;
+        for field in trailers {
+            last.push_str(&format!("{}: {}\r\n", field.canonical_name(), field.value));
+        }
+        last.push_str("\r\n");
+        self.writer.write_all(last.as_bytes())?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // An empty chunk would terminate the body so nothing is written.
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        
+        write!(self.writer, "{:X}\r\n", buf.len())?;
+        self.writer.write_all(buf)?;
+        self.writer.write_all(b"\r\n")?;
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Decodes a chunked message body.</br>
+/// Returns the decoded body and the number of bytes of `body` which were consumed.
+///
+/// Chunk extensions and trailer fields are ignored.
+///
+/// # Params
+///
+/// body --- The chunked bytes to decode.
+pub fn decode(body: &[u8]) -> Result<(Vec<u8>, usize), ParseError> {
+    decode_with_trailers(body).map(|(res, _, position)| (res, position))
+}
+
+/// Decodes a chunked message body.</br>
+/// Returns the decoded body, the trailer fields and the number of bytes of `body` which
+/// were consumed.
+///
+/// Chunk extensions are ignored, as are any `FORBIDDEN_TRAILERS`.
+///
+/// # Params
+///
+/// body --- The chunked bytes to decode.
+pub fn decode_with_trailers(body: &[u8]) -> Result<(Vec<u8>, Vec<HeaderField>, usize), ParseError> {
+    let mut res = Vec::new();
+    let mut position = 0;
+    
+    loop {
+        let size = chunk_size(read_line(body, &mut position)?)?;
+        
+        // The last chunk is followed by the trailer section and an empty line.
+        if size == 0 {
+            let mut trailers = Vec::new();
+            
+            loop {
+                let line = read_line(body, &mut position)?;
+                if line.is_empty() {
+                    return Ok((res, trailers, position));
+                }
+                
+                let field = HeaderField::from(line)?;
+                if is_allowed_trailer(&field.name) {
+                    trailers.push(field);
+                }
+            }
+        }
+        
+        // A chunk size near `usize::MAX` can not fit in any body.
+        let end = match position.checked_add(size).and_then(|end| end.checked_add(2)) {
+            Some(end) => end,
+            None => return Err(ParseError::InvalidChunk(format!("{:X}", size)))
+        };
+        if body.len() < end {
+            return Err(ParseError::IncompleteChunk);
+        }
+        res.extend_from_slice(&body[position..position + size]);
+        position += size;
+        // Every chunk's data is terminated by a line break.
+        if &body[position..position + 2] != b"\r\n" {
+            return Err(ParseError::InvalidChunk(String::from("missing CRLF after chunk data")));
+        }
+        position += 2;
+    }
+}
+
+/// Returns the size of a chunk from its chunk size line, ignoring any chunk extensions.
+///
+/// # Params
+///
+/// line --- The chunk size line without its line break, e.g. `1A;name=value`.
+pub fn chunk_size(line: &str) -> Result<usize, ParseError> {
+    let size = line.split(';').next().unwrap_or_default().trim();
+    // Only plain hex digits are valid, `from_str_radix` would also accept a sign.
+    if size.is_empty() || !size.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(ParseError::InvalidChunk(String::from(line)));
+    }
+    match usize::from_str_radix(size, 16) {
+        Ok(size) => Ok(size),
+        Err(_) => Err(ParseError::InvalidChunk(String::from(line)))
+    }
+}
+
+/// Fails with `InvalidInput` if any of the trailers is forbidden or invalid.
+///
+/// # Params
+///
+/// trailers --- The `HeaderField`s to send after a chunked body.
+pub fn check_trailers(trailers: &[HeaderField]) -> io::Result<()> {
+    match trailers.iter().find(|field| !is_allowed_trailer(&field.name) || field.validate().is_err()) {
+        Some(field) => Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Bad trailer field `{}`.", field.name))),
+        None => Ok(())
+    }
+}
+
+/// Returns `true` if a Header field with the passed name may be sent as a trailer.
+///
+/// # Params
+///
+/// name --- The name of the Header field.
+fn is_allowed_trailer(name: &str) -> bool {
+    !FORBIDDEN_TRAILERS.iter().any(|forbidden| forbidden.eq_ignore_ascii_case(name))
+}
+
+/// Returns the next line of `body` starting at `position`, advancing `position` past the line break.
+///
+/// # Params
+///
+/// body --- The bytes to read the line from.</br>
+/// position --- The index in `body` the line starts at.
+fn read_line<'a>(body: &'a [u8], position: &mut usize) -> Result<&'a str, ParseError> {
+    match body[*position..].windows(2).position(|w| w == b"\r\n") {
+        Some(len) => {
+            let line = &body[*position..*position + len];
+            
+            *position += len + 2;
+            match str::from_utf8(line) {
+                Ok(line) => Ok(line),
+                Err(_) => Err(ParseError::InvalidUtf8)
+            }
+        },
+        None => Err(ParseError::IncompleteChunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encoder() {
+        let mut encoder = Encoder::new(Vec::new());
+        
+        encoder.write_all(b"Hello, ").unwrap();
+        encoder.write_all(b"").unwrap();
+        encoder.write_all(b"chunked world!").unwrap();
+        let res = encoder.finish().unwrap();
+        
+        assert_eq!(
+            res,
+            b"7\r\nHello, \r\nE\r\nchunked world!\r\n0\r\n\r\n".to_vec(),
+            "Test Encoder-1 failed."
+        );
+        
+        assert_eq!(
+            decode(&res).unwrap(),
+            (b"Hello, chunked world!".to_vec(), res.len()),
+            "Test Encoder-2 failed."
+        );
+    }
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            decode(b"4;ext=1\r\nWiki\r\n5\r\npedia\r\n0\r\nExpires: never\r\n\r\nNEXT").unwrap(),
+            (b"Wikipedia".to_vec(), 46),
+            "Test chunked::decode-1 failed."
+        );
+        
+        assert_eq!(
+            decode(b"4\r\nWiki\r\n5\r\npe"),
+            Err(ParseError::IncompleteChunk),
+            "Test chunked::decode-2 failed."
+        );
+        
+        assert_eq!(
+            decode(b"Z\r\nWiki\r\n0\r\n\r\n"),
+            Err(ParseError::InvalidChunk(String::from("Z"))),
+            "Test chunked::decode-3 failed."
+        );
+        
+        assert!(
+            decode(b"4\r\nWikipedia\r\n0\r\n\r\n").is_err(),
+            "Test chunked::decode-4 failed."
+        );
+        
+        assert_eq!(
+            decode(b"FFFFFFFFFFFFFFFF\r\nabc\r\n"),
+            Err(ParseError::InvalidChunk(format!("{:X}", usize::MAX))),
+            "Test chunked::decode-5 failed."
+        );
+    }
+    #[test]
+    fn test_trailers() {
+        let trailers = [
+            HeaderField::from("Content-MD5: Q2hlY2sgSW50ZWdyaXR5IQ==").unwrap(),
+            HeaderField::from("Grpc-Status: 0").unwrap()
+        ];
+        let mut encoder = Encoder::new(Vec::new());
+        
+        encoder.write_all(b"Wikipedia").unwrap();
+        let res = encoder.finish_with_trailers(&trailers).unwrap();
+        assert_eq!(
+            res,
+            b"9\r\nWikipedia\r\n0\r\nContent-MD5: Q2hlY2sgSW50ZWdyaXR5IQ==\r\nGrpc-Status: 0\r\n\r\n".to_vec(),
+            "Test chunked::trailers-1 failed."
+        );
+        assert_eq!(
+            decode_with_trailers(&res).unwrap(),
+            (b"Wikipedia".to_vec(), trailers.to_vec(), res.len()),
+            "Test chunked::trailers-2 failed."
+        );
+        
+        assert_eq!(
+            decode_with_trailers(b"0\r\nContent-Length: 10\r\nhost: evil\r\nExpires: never\r\nX-Checksum: 1\r\n\r\n").unwrap().1,
+            vec![HeaderField::from("X-Checksum: 1").unwrap()],
+            "Test chunked::trailers-3 failed."
+        );
+        assert_eq!(
+            decode_with_trailers(b"0\r\nBad Trailer: 1\r\n\r\n"),
+            Err(ParseError::MalformedHeader(String::from("Bad Trailer: 1"))),
+            "Test chunked::trailers-4 failed."
+        );
+        
+        assert!(
+            Encoder::new(Vec::new()).finish_with_trailers(&[HeaderField::from("Content-Length: 1").unwrap()]).is_err(),
+            "Test chunked::trailers-5 failed."
+        );
+    }
+}
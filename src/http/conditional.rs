@@ -0,0 +1,128 @@
+//! `conditional` is a module to handle conditional requests using the `Last-Modified` validator.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::fs::Metadata;
+use std::io;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use super::MessageHTTP;
+use super::headers;
+use super::date;
+
+/// Returns `true` if the representation has not been modified since the request's
+/// `If-Modified-Since` Header Field, so a `304 Not Modified` can be sent.
+///
+/// `If-Modified-Since` is only used by `GET` and `HEAD` requests without an
+/// `If-None-Match` Header Field, and is ignored if it is not a valid HTTP-date.
+/// Times are compared to the second.
+///
+/// # Params
+///
+/// request --- The request being responded to.</br>
+/// last_modified --- The time the representation was last modified.
+pub fn not_modified(request: &MessageHTTP, last_modified: SystemTime) -> bool {
+    match request.start_line.as_request() {
+        Some(("GET", _, _)) | Some(("HEAD", _, _)) => (),
+        _ => return false
+    }
+    // An `If-None-Match` takes precedence over an `If-Modified-Since`.
+    if request.header(headers::IF_NONE_MATCH).is_some() {
+        return false;
+    }
+    
+    match request.header(headers::IF_MODIFIED_SINCE).map(date::parse) {
+        Some(Ok(since)) => truncate(last_modified) <= since,
+        _ => false
+    }
+}
+
+/// Sets the `Last-Modified` Header Field of the response to the modification time in
+/// the passed `Metadata`.
+///
+/// # Params
+///
+/// response --- The response to set the `Last-Modified` of.</br>
+/// metadata --- The `Metadata` of the file being sent.
+pub fn set_last_modified(response: &mut MessageHTTP, metadata: &Metadata) -> io::Result<()> {
+    response.set_header(headers::LAST_MODIFIED, &date::format(metadata.modified()?))
+        .expect("A HTTP-date is always a valid Header Field value.");
+    Ok(())
+}
+
+/// Returns the passed time without any fraction of a second.
+///
+/// # Params
+///
+/// time --- The time to truncate.
+fn truncate(time: SystemTime) -> SystemTime {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(since) => UNIX_EPOCH + Duration::from_secs(since.as_secs()),
+        Err(_) => time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs::{self, File};
+
+    #[test]
+    fn test_not_modified() {
+        let last_modified = UNIX_EPOCH + Duration::new(784111777, 500_000_000);
+        let request = |head: &str| MessageHTTP::from(&format!("{}\r\n\r\n", head)).unwrap();
+        
+        assert!(
+            not_modified(&request("GET / HTTP/1.1\r\nIf-Modified-Since: Sun, 06 Nov 1994 08:49:37 GMT"), last_modified),
+            "Test conditional::not_modified-1 failed."
+        );
+        
+        assert!(
+            not_modified(&request("HEAD / HTTP/1.1\r\nIf-Modified-Since: Sunday, 06-Nov-94 09:00:00 GMT"), last_modified),
+            "Test conditional::not_modified-2 failed."
+        );
+        
+        assert!(
+            !not_modified(&request("GET / HTTP/1.1\r\nIf-Modified-Since: Sun, 06 Nov 1994 08:49:36 GMT"), last_modified),
+            "Test conditional::not_modified-3 failed."
+        );
+        
+        assert!(
+            !not_modified(&request("GET / HTTP/1.1\r\nIf-Modified-Since: yesterday"), last_modified),
+            "Test conditional::not_modified-4 failed."
+        );
+        
+        assert!(
+            !not_modified(&request("GET / HTTP/1.1\r\nIf-None-Match: \"xyzzy\"\r\nIf-Modified-Since: Sun, 06 Nov 1994 08:49:37 GMT"), last_modified),
+            "Test conditional::not_modified-5 failed."
+        );
+        
+        assert!(
+            !not_modified(&request("POST / HTTP/1.1\r\nIf-Modified-Since: Sun, 06 Nov 1994 08:49:37 GMT"), last_modified),
+            "Test conditional::not_modified-6 failed."
+        );
+        
+        assert!(
+            !not_modified(&request("GET / HTTP/1.1"), last_modified),
+            "Test conditional::not_modified-7 failed."
+        );
+    }
+    #[test]
+    fn test_set_last_modified() {
+        let path = env::temp_dir().join(format!("web_server_conditional_{}", std::process::id()));
+        File::create(&path).unwrap();
+        let metadata = fs::metadata(&path).unwrap();
+        let mut response = MessageHTTP::from("HTTP/1.1 200 OK\r\n\r\n").unwrap();
+        
+        set_last_modified(&mut response, &metadata).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            response.header("Last-Modified").map(date::parse),
+            Some(Ok(truncate(metadata.modified().unwrap()))),
+            "Test conditional::set_last_modified-1 failed."
+        );
+    }
+}
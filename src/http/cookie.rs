@@ -0,0 +1,337 @@
+//! `cookie` is a module to handle the cookies sent in the `Cookie` Header Field of a HTTP message.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::string::String;
+use std::time::SystemTime;
+use super::BuildError;
+use super::date;
+use super::token;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// A `Cookie` is a single `name=value` pair sent by a client.
+pub struct Cookie {
+    /// The `name` of the `Cookie`.
+    pub name: String,
+    /// The `value` of the `Cookie`, without any surrounding quotes.
+    pub value: String
+}
+
+/// Returns the `Cookie`s in the value of a `Cookie` Header Field, in order.
+///
+/// Pairs are separated by `;` with optional whitespace, values may be quoted and
+/// may contain `=`. Malformed pairs are skipped.
+///
+/// # Params
+///
+/// header --- The value of the `Cookie` Header Field.
+pub fn parse(header: &str) -> Vec<Cookie> {
+    header.split(';')
+        .filter_map(
+            |pair| {
+                // A pair without an `=` or without a name is malformed.
+                let index = pair.find('=')?;
+                let name = pair[..index].trim();
+                if name.is_empty() {
+                    return None;
+                }
+                
+                let value = pair[index + 1..].trim();
+                // Quotes around the value are not part of the value, but a cookie value is
+                // opaque so backslashes inside them are kept.
+                let value = if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+                    &value[1..value.len() - 1]
+                } else {
+                    value
+                };
+                
+                Some(
+                    Cookie {
+                        name: String::from(name),
+                        value: String::from(value)
+                    }
+                )
+            }
+        ).collect()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// The `SameSite` attribute of a `SetCookie`.
+pub enum SameSite {
+    /// The cookie is only sent with same-site requests.
+    Strict,
+    /// The cookie is also sent with top-level cross-site navigations.
+    Lax,
+    /// The cookie is sent with all requests.
+    None
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// A `SetCookie` builds the value of a `Set-Cookie` Header Field.
+///
+/// Attributes are always written in the order `Expires`, `Max-Age`, `Domain`, `Path`,
+/// `Secure`, `HttpOnly` then `SameSite`.
+pub struct SetCookie {
+    /// The `name` of the cookie.
+    name: String,
+    /// The `value` of the cookie.
+    value: String,
+    /// The time the cookie expires.
+    expires: Option<SystemTime>,
+    /// The number of seconds until the cookie expires.
+    max_age: Option<u64>,
+    /// The host the cookie is sent to.
+    domain: Option<String>,
+    /// The path the cookie is sent to.
+    path: Option<String>,
+    /// If the cookie is only sent over secure connections.
+    secure: bool,
+    /// If the cookie is hidden from scripts.
+    http_only: bool,
+    /// The cross-site policy of the cookie.
+    same_site: Option<SameSite>
+}
+
+impl SetCookie {
+    /// Returns a new `SetCookie` with the passed name and value and no attributes.
+    ///
+    /// # Params
+    ///
+    /// name --- The name of the cookie.</br>
+    /// value --- The value of the cookie.
+    pub fn new(name: &str, value: &str) -> SetCookie {
+        SetCookie {
+            name: String::from(name),
+            value: String::from(value),
+            expires: None,
+            max_age: None,
+            domain: None,
+            path: None,
+            secure: false,
+            http_only: false,
+            same_site: None
+        }
+    }
+    /// Sets the `Expires` attribute.
+    ///
+    /// # Params
+    ///
+    /// expires --- The time the cookie expires.
+    pub fn expires(mut self, expires: SystemTime) -> SetCookie {
+        self.expires = Some(expires);
+        self
+    }
+    /// Sets the `Max-Age` attribute.
+    ///
+    /// # Params
+    ///
+    /// max_age --- The number of seconds until the cookie expires.
+    pub fn max_age(mut self, max_age: u64) -> SetCookie {
+        self.max_age = Some(max_age);
+        self
+    }
+    /// Sets the `Domain` attribute.
+    ///
+    /// # Params
+    ///
+    /// domain --- The host the cookie is sent to.
+    pub fn domain(mut self, domain: &str) -> SetCookie {
+        self.domain = Some(String::from(domain));
+        self
+    }
+    /// Sets the `Path` attribute.
+    ///
+    /// # Params
+    ///
+    /// path --- The path the cookie is sent to.
+    pub fn path(mut self, path: &str) -> SetCookie {
+        self.path = Some(String::from(path));
+        self
+    }
+    /// Sets the `Secure` attribute.
+    ///
+    /// # Params
+    ///
+    /// secure --- If the cookie is only sent over secure connections.
+    pub fn secure(mut self, secure: bool) -> SetCookie {
+        self.secure = secure;
+        self
+    }
+    /// Sets the `HttpOnly` attribute.
+    ///
+    /// # Params
+    ///
+    /// http_only --- If the cookie is hidden from scripts.
+    pub fn http_only(mut self, http_only: bool) -> SetCookie {
+        self.http_only = http_only;
+        self
+    }
+    /// Sets the `SameSite` attribute.
+    ///
+    /// # Params
+    ///
+    /// same_site --- The cross-site policy of the cookie.
+    pub fn same_site(mut self, same_site: SameSite) -> SetCookie {
+        self.same_site = Some(same_site);
+        self
+    }
+    /// Returns the value of the `Set-Cookie` Header Field for this cookie.
+    ///
+    /// The name must be a token, the value must only contain cookie-octets (optionally
+    /// quoted) and the `Domain` and `Path` must not contain controls or `;`.
+    pub fn to_header_value(&self) -> Result<String, BuildError> {
+        if !token::is_token(&self.name) {
+            return Err(BuildError::InvalidCookie(self.name.clone()));
+        }
+        // The value may be wrapped in quotes.
+        let value = if self.value.len() >= 2 && self.value.starts_with('"') && self.value.ends_with('"') {
+            &self.value[1..self.value.len() - 1]
+        } else {
+            self.value.as_str()
+        };
+        if !value.bytes().all(is_cookie_octet) {
+            return Err(BuildError::InvalidCookie(self.value.clone()));
+        }
+        
+        let mut res = format!("{}={}", self.name, self.value);
+        
+        if let Some(expires) = self.expires {
+            res.push_str("; Expires=");
+            res.push_str(&date::format(expires));
+        }
+        if let Some(max_age) = self.max_age {
+            res.push_str(&format!("; Max-Age={}", max_age));
+        }
+        for &(attribute, value) in [("Domain", &self.domain), ("Path", &self.path)].iter() {
+            if let Some(ref value) = *value {
+                if value.bytes().any(|b| b.is_ascii_control() || b == b';') {
+                    return Err(BuildError::InvalidCookie(value.clone()));
+                }
+                res.push_str(&format!("; {}={}", attribute, value));
+            }
+        }
+        if self.secure {
+            res.push_str("; Secure");
+        }
+        if self.http_only {
+            res.push_str("; HttpOnly");
+        }
+        match self.same_site {
+            Some(SameSite::Strict) => res.push_str("; SameSite=Strict"),
+            Some(SameSite::Lax) => res.push_str("; SameSite=Lax"),
+            Some(SameSite::None) => res.push_str("; SameSite=None"),
+            None => ()
+        }
+        Ok(res)
+    }
+}
+
+/// Returns `true` if the byte may appear in the value of a cookie.
+///
+/// # Params
+///
+/// byte --- The byte to check.
+fn is_cookie_octet(byte: u8) -> bool {
+    matches!(byte, 0x21 | 0x23..=0x2B | 0x2D..=0x3A | 0x3C..=0x5B | 0x5D..=0x7E)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    macro_rules! cookie {
+        ($name:expr, $value:expr) => {
+            Cookie { name: String::from($name), value: String::from($value) }
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("a=1; b=2; theme=dark"),
+            vec![cookie!("a", "1"), cookie!("b", "2"), cookie!("theme", "dark")],
+            "Test cookie::parse-1 failed."
+        );
+        
+        assert_eq!(
+            parse("a=1;b=2"),
+            vec![cookie!("a", "1"), cookie!("b", "2")],
+            "Test cookie::parse-2 failed."
+        );
+        
+        assert_eq!(
+            parse("token=\"abc def\"; data=a=b=c"),
+            vec![cookie!("token", "abc def"), cookie!("data", "a=b=c")],
+            "Test cookie::parse-3 failed."
+        );
+        
+        assert_eq!(
+            parse(""),
+            Vec::new(),
+            "Test cookie::parse-4 failed."
+        );
+        
+        assert_eq!(
+            parse("id=1; malformed; =empty; id=2;"),
+            vec![cookie!("id", "1"), cookie!("id", "2")],
+            "Test cookie::parse-5 failed."
+        );
+        
+        assert_eq!(
+            parse("path=\"C:\\dir\\\"; raw=a\\b"),
+            vec![cookie!("path", "C:\\dir\\"), cookie!("raw", "a\\b")],
+            "Test cookie::parse-6 failed."
+        );
+    }
+    #[test]
+    fn test_set_cookie() {
+        use std::time::{Duration, UNIX_EPOCH};
+        
+        assert_eq!(
+            SetCookie::new("id", "a3fWa").to_header_value().unwrap(),
+            "id=a3fWa",
+            "Test SetCookie-1 failed."
+        );
+        
+        assert_eq!(
+            SetCookie::new("id", "a3fWa")
+                .same_site(SameSite::Lax)
+                .http_only(true)
+                .secure(true)
+                .path("/")
+                .domain("example.com")
+                .max_age(3600)
+                .expires(UNIX_EPOCH + Duration::from_secs(784111777))
+                .to_header_value().unwrap(),
+            "id=a3fWa; Expires=Sun, 06 Nov 1994 08:49:37 GMT; Max-Age=3600; Domain=example.com; Path=/; Secure; HttpOnly; SameSite=Lax",
+            "Test SetCookie-2 failed."
+        );
+        
+        assert_eq!(
+            SetCookie::new("quoted", "\"value\"").to_header_value().unwrap(),
+            "quoted=\"value\"",
+            "Test SetCookie-3 failed."
+        );
+        
+        assert_eq!(
+            SetCookie::new("bad name", "value").to_header_value(),
+            Err(BuildError::InvalidCookie(String::from("bad name"))),
+            "Test SetCookie-4 failed."
+        );
+        
+        assert_eq!(
+            SetCookie::new("name", "a;b").to_header_value(),
+            Err(BuildError::InvalidCookie(String::from("a;b"))),
+            "Test SetCookie-5 failed."
+        );
+        
+        assert_eq!(
+            SetCookie::new("name", "value").path("/; Secure").to_header_value(),
+            Err(BuildError::InvalidCookie(String::from("/; Secure"))),
+            "Test SetCookie-6 failed."
+        );
+    }
+}
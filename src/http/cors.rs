@@ -0,0 +1,336 @@
+//! `cors` is a module to handle Cross-Origin Resource Sharing for requests made by browsers.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::string::String;
+use super::{MessageHTTP, ResponseBuilder, StatusCode, Method};
+use super::headers;
+use super::token;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// The origins a `CorsPolicy` allows.
+pub enum AllowedOrigins {
+    /// Every origin is allowed.
+    Any,
+    /// Only the one origin is allowed, e.g. `https://example.com`.
+    Exact(String),
+    /// Only the listed origins are allowed.
+    List(Vec<String>)
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// A `CorsPolicy` decides which cross-origin requests a browser may make and adds the
+/// CORS Header Fields to the responses.
+///
+/// A request from an origin which is not allowed is answered without any CORS Header
+/// Fields, so the browser refuses it, rather than with an error response.
+pub struct CorsPolicy {
+    /// The origins which are allowed.
+    origins: AllowedOrigins,
+    /// The methods which are allowed.
+    methods: Vec<Method>,
+    /// The request Header Fields which are allowed.
+    headers: Vec<String>,
+    /// If requests may include credentials, e.g. cookies.
+    credentials: bool,
+    /// The number of seconds a preflight response may be cached for.
+    max_age: Option<u64>
+}
+
+impl CorsPolicy {
+    /// Returns a new `CorsPolicy` for the passed origins which allows `GET`, `HEAD` and
+    /// `POST` requests without credentials or extra Header Fields.
+    ///
+    /// # Params
+    ///
+    /// origins --- The origins which are allowed.
+    pub fn new(origins: AllowedOrigins) -> CorsPolicy {
+        CorsPolicy {
+            origins,
+            methods: vec![Method::Get, Method::Head, Method::Post],
+            headers: Vec::new(),
+            credentials: false,
+            max_age: None
+        }
+    }
+    /// Sets the methods which are allowed.
+    ///
+    /// # Params
+    ///
+    /// methods --- The methods which are allowed.
+    pub fn methods(mut self, methods: &[Method]) -> CorsPolicy {
+        self.methods = methods.to_vec();
+        self
+    }
+    /// Sets the request Header Fields which are allowed, names which are not tokens are
+    /// ignored.
+    ///
+    /// # Params
+    ///
+    /// headers --- The names of the Header Fields which are allowed.
+    pub fn headers(mut self, headers: &[&str]) -> CorsPolicy {
+        self.headers = headers.iter()
+            .filter(|name| token::is_token(name))
+            .map(|name| String::from(*name))
+            .collect();
+        self
+    }
+    /// Sets if requests may include credentials, e.g. cookies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `credentials` is `true` and every origin is allowed, which would let any
+    /// site read credentialed responses.
+    ///
+    /// # Params
+    ///
+    /// credentials --- If requests may include credentials.
+    pub fn credentials(mut self, credentials: bool) -> CorsPolicy {
+        assert!(!credentials || self.origins != AllowedOrigins::Any, "Credentials must not be allowed from every origin.");
+        self.credentials = credentials;
+        self
+    }
+    /// Sets the number of seconds a preflight response may be cached for.
+    ///
+    /// # Params
+    ///
+    /// max_age --- The number of seconds a preflight response may be cached for.
+    pub fn max_age(mut self, max_age: u64) -> CorsPolicy {
+        self.max_age = Some(max_age);
+        self
+    }
+    /// Returns the response to a preflight request, or `None` if the request is not an
+    /// `OPTIONS` request with an `Origin` and an `Access-Control-Request-Method`.
+    ///
+    /// The response is `204 No Content`. If the origin, the requested method and every
+    /// requested Header Field are allowed it lists the allowed methods and Header
+    /// Fields, otherwise it has no CORS Header Fields.
+    ///
+    /// # Params
+    ///
+    /// request --- The preflight request.
+    pub fn preflight_response(&self, request: &MessageHTTP) -> Option<MessageHTTP> {
+        if request.start_line.method() != Some(&Method::Options) {
+            return None;
+        }
+        let origin = request.header(headers::ORIGIN)?;
+        let method = request.header(headers::ACCESS_CONTROL_REQUEST_METHOD)?;
+        
+        let mut response = ResponseBuilder::new(StatusCode::NO_CONTENT);
+        if !self.is_wildcard() {
+            response = response.header(headers::VARY, "Origin");
+        }
+        
+        let method_allowed = Method::from(method.trim()).is_ok_and(|method| self.methods.contains(&method));
+        let request_headers = request.combined_header(headers::ACCESS_CONTROL_REQUEST_HEADERS)
+            .unwrap_or_default();
+        let headers_allowed = token::split_list(&request_headers).into_iter()
+            .all(|name| self.headers.iter().any(|header| header.eq_ignore_ascii_case(name)));
+        if let (Some(allow_origin), true, true) = (self.allow_origin(origin), method_allowed, headers_allowed) {
+            response = response.header(headers::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+            if self.credentials {
+                response = response.header(headers::ACCESS_CONTROL_ALLOW_CREDENTIALS, "true");
+            }
+            response = response.header(
+                headers::ACCESS_CONTROL_ALLOW_METHODS,
+                &self.methods.iter().map(Method::as_str).collect::<Vec<&str>>().join(", ")
+            );
+            if !self.headers.is_empty() {
+                response = response.header(headers::ACCESS_CONTROL_ALLOW_HEADERS, &self.headers.join(", "));
+            }
+            if let Some(max_age) = self.max_age {
+                response = response.header(headers::ACCESS_CONTROL_MAX_AGE, &max_age.to_string());
+            }
+        }
+        Some(
+            response.build()
+                .expect("The CORS Header Fields are always valid Header Field values.")
+        )
+    }
+    /// Adds the CORS Header Fields to the response to an actual, non-preflight, request.
+    ///
+    /// Unless every origin is allowed an `Origin` is added to the `Vary`, if the response
+    /// does not already vary on it, so that caches do not serve the response to another
+    /// origin. The response is otherwise unchanged if the request has no `Origin` or the
+    /// origin is not allowed.
+    ///
+    /// # Params
+    ///
+    /// response --- The response to add the CORS Header Fields to.</br>
+    /// request --- The request being responded to.
+    pub fn apply(&self, response: &mut MessageHTTP, request: &MessageHTTP) {
+        let varies = response.header_values(headers::VARY)
+            .any(|name| name == "*" || name.eq_ignore_ascii_case("Origin"));
+        if !self.is_wildcard() && !varies {
+            response.add_header(headers::VARY, "Origin")
+                .expect("`Origin` is always a valid Header Field value.");
+        }
+        
+        if let Some(allow_origin) = request.header(headers::ORIGIN).and_then(|origin| self.allow_origin(origin)) {
+            response.set_header(headers::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin)
+                .expect("The origin is always a valid Header Field value.");
+            if self.credentials {
+                response.set_header(headers::ACCESS_CONTROL_ALLOW_CREDENTIALS, "true")
+                    .expect("`true` is always a valid Header Field value.");
+            }
+        }
+    }
+    /// Returns `true` if the `Access-Control-Allow-Origin` is always `*`.
+    fn is_wildcard(&self) -> bool {
+        self.origins == AllowedOrigins::Any
+    }
+    /// Returns the value of the `Access-Control-Allow-Origin` for the passed origin, or
+    /// `None` if it is not allowed.
+    ///
+    /// # Params
+    ///
+    /// origin --- The `Origin` of the request.
+    fn allow_origin<'a>(&self, origin: &'a str) -> Option<&'a str> {
+        if self.is_wildcard() {
+            return Some("*");
+        }
+        // Only a visible origin is echoed back.
+        let origin = origin.trim();
+        if origin.is_empty() || !origin.bytes().all(|b| b.is_ascii_graphic()) {
+            return None;
+        }
+        
+        let allowed = match self.origins {
+            AllowedOrigins::Any => unreachable!("Every origin is allowed as `*`."),
+            AllowedOrigins::Exact(ref allowed) => allowed.eq_ignore_ascii_case(origin),
+            AllowedOrigins::List(ref list) => list.iter().any(|allowed| allowed.eq_ignore_ascii_case(origin))
+        };
+        if allowed { Some(origin) } else { None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Returns a request with the passed start line and Header Fields.
+    fn request(start_line: &str, fields: &str) -> MessageHTTP {
+        MessageHTTP::from(&format!("{}\r\nHost: api.example.com\r\n{}\r\n", start_line, fields)).unwrap()
+    }
+
+    #[test]
+    fn test_preflight_response() {
+        let policy = CorsPolicy::new(AllowedOrigins::List(vec![String::from("https://app.example.com"), String::from("https://admin.example.com")]))
+            .methods(&[Method::Get, Method::Put])
+            .headers(&["Content-Type", "X-Token"])
+            .max_age(600);
+        let preflight = request("OPTIONS /items/1 HTTP/1.1", "Origin: https://app.example.com\r\nAccess-Control-Request-Method: PUT\r\nAccess-Control-Request-Headers: content-type, x-token\r\n");
+        let response = policy.preflight_response(&preflight).unwrap();
+        
+        assert_eq!(
+            response.start_line.as_status().map(|status| status.1),
+            Some(StatusCode::NO_CONTENT),
+            "Test CorsPolicy::preflight_response-1 failed."
+        );
+        assert_eq!(
+            response.header_fields.iter().map(|field| field.to_string()).collect::<Vec<String>>(),
+            vec![
+                "Vary: Origin",
+                "Access-Control-Allow-Origin: https://app.example.com",
+                "Access-Control-Allow-Methods: GET, PUT",
+                "Access-Control-Allow-Headers: Content-Type, X-Token",
+                "Access-Control-Max-Age: 600"
+            ],
+            "Test CorsPolicy::preflight_response-2 failed."
+        );
+        
+        let denied = [
+            "Origin: https://evil.example.com\r\nAccess-Control-Request-Method: PUT\r\n",
+            "Origin: https://app.example.com\r\nAccess-Control-Request-Method: DELETE\r\n",
+            "Origin: https://app.example.com\r\nAccess-Control-Request-Method: GET\r\nAccess-Control-Request-Headers: X-Other\r\n"
+        ];
+        for fields in denied.iter() {
+            let response = policy.preflight_response(&request("OPTIONS /items/1 HTTP/1.1", fields)).unwrap();
+            
+            assert_eq!(
+                (response.header(headers::ACCESS_CONTROL_ALLOW_ORIGIN), response.header(headers::ACCESS_CONTROL_ALLOW_METHODS)),
+                (None, None),
+                "Test CorsPolicy::preflight_response-3 failed for `{}`.", fields
+            );
+        }
+        
+        assert_eq!(
+            policy.preflight_response(&request("OPTIONS /items/1 HTTP/1.1", "Origin: https://app.example.com\r\n")),
+            None,
+            "Test CorsPolicy::preflight_response-4 failed."
+        );
+        assert_eq!(
+            policy.preflight_response(&request("GET /items/1 HTTP/1.1", "Origin: https://app.example.com\r\nAccess-Control-Request-Method: GET\r\n")),
+            None,
+            "Test CorsPolicy::preflight_response-5 failed."
+        );
+    }
+    #[test]
+    fn test_credentials() {
+        let policy = CorsPolicy::new(AllowedOrigins::Exact(String::from("https://app.example.com"))).credentials(true);
+        let preflight = request("OPTIONS / HTTP/1.1", "Origin: https://app.example.com\r\nAccess-Control-Request-Method: POST\r\n");
+        let response = policy.preflight_response(&preflight).unwrap();
+        
+        assert_eq!(
+            (response.header(headers::ACCESS_CONTROL_ALLOW_ORIGIN), response.header(headers::ACCESS_CONTROL_ALLOW_CREDENTIALS), response.header(headers::VARY)),
+            (Some("https://app.example.com"), Some("true"), Some("Origin")),
+            "Test CorsPolicy credentials-1 failed."
+        );
+        
+        let mut response = MessageHTTP::from("HTTP/1.1 200 OK\r\nVary: Accept\r\n\r\n").unwrap();
+        policy.apply(&mut response, &request("GET / HTTP/1.1", "Origin: https://app.example.com\r\nCookie: id=1\r\n"));
+        assert_eq!(
+            (response.header(headers::ACCESS_CONTROL_ALLOW_ORIGIN), response.header(headers::ACCESS_CONTROL_ALLOW_CREDENTIALS)),
+            (Some("https://app.example.com"), Some("true")),
+            "Test CorsPolicy credentials-2 failed."
+        );
+        assert_eq!(
+            response.combined_header(headers::VARY),
+            Some(String::from("Accept, Origin")),
+            "Test CorsPolicy credentials-3 failed."
+        );
+    }
+    #[test]
+    #[should_panic]
+    fn test_credentials_any_origin() {
+        CorsPolicy::new(AllowedOrigins::Any).credentials(true);
+    }
+    #[test]
+    fn test_apply() {
+        let mut response = MessageHTTP::from("HTTP/1.1 200 OK\r\n\r\n").unwrap();
+        CorsPolicy::new(AllowedOrigins::Any).apply(&mut response, &request("GET / HTTP/1.1", "Origin: https://app.example.com\r\n"));
+        assert_eq!(
+            (response.header(headers::ACCESS_CONTROL_ALLOW_ORIGIN), response.header(headers::VARY)),
+            (Some("*"), None),
+            "Test CorsPolicy::apply-1 failed."
+        );
+        
+        let policy = CorsPolicy::new(AllowedOrigins::Exact(String::from("https://app.example.com")));
+        let mut response = MessageHTTP::from("HTTP/1.1 200 OK\r\n\r\n").unwrap();
+        policy.apply(&mut response, &request("GET / HTTP/1.1", "Origin: https://evil.example.com\r\n"));
+        assert_eq!(
+            (response.header(headers::ACCESS_CONTROL_ALLOW_ORIGIN), response.header(headers::VARY)),
+            (None, Some("Origin")),
+            "Test CorsPolicy::apply-2 failed."
+        );
+        
+        let mut response = MessageHTTP::from("HTTP/1.1 200 OK\r\n\r\n").unwrap();
+        policy.apply(&mut response, &request("GET / HTTP/1.1", ""));
+        assert_eq!(
+            (response.header(headers::ACCESS_CONTROL_ALLOW_ORIGIN), response.header(headers::VARY)),
+            (None, Some("Origin")),
+            "Test CorsPolicy::apply-3 failed."
+        );
+        
+        let mut response = MessageHTTP::from("HTTP/1.1 200 OK\r\nVary: Accept, origin\r\n\r\n").unwrap();
+        policy.apply(&mut response, &request("GET / HTTP/1.1", "Origin: https://app.example.com\r\n"));
+        assert_eq!(
+            (response.header(headers::ACCESS_CONTROL_ALLOW_ORIGIN), response.combined_header(headers::VARY)),
+            (Some("https://app.example.com"), Some(String::from("Accept, origin"))),
+            "Test CorsPolicy::apply-4 failed."
+        );
+    }
+}
@@ -0,0 +1,300 @@
+//! `date` is a module to handle the HTTP-date format used by the `Date`, `Expires` and
+//! `If-Modified-Since` Header Fields among others.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::fmt;
+use std::string::String;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use super::ParseError;
+
+/// The short names of the days of the week, starting from Monday.
+static WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+/// The long names of the days of the week used by RFC 850 dates, starting from Monday.
+static LONG_WEEKDAYS: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+/// The short names of the months of the year.
+static MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+/// Formats the passed time as an IMF-fixdate, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+///
+/// Times before the epoch are formatted as the epoch.
+///
+/// # Params
+///
+/// time --- The time to format.
+pub fn format(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+    let days = secs / 86400;
+    let secs = secs % 86400;
+    
+    // Convert the days since the epoch to a civil date.
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    
+    // The epoch was a Thursday.
+    format!("{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+        WEEKDAYS[((days + 3) % 7) as usize], day, MONTHS[(month - 1) as usize], year,
+        secs / 3600, secs % 3600 / 60, secs % 60
+    )
+}
+
+/// Converts the passed HTTP-date to a `SystemTime`.
+///
+/// IMF-fixdate (`Sun, 06 Nov 1994 08:49:37 GMT`) is accepted along with the obsolete
+/// RFC 850 (`Sunday, 06-Nov-94 08:49:37 GMT`) and asctime (`Sun Nov  6 08:49:37 1994`)
+/// forms. Two digit RFC 850 years below 70 are taken to be in the 2000s.
+///
+/// # Params
+///
+/// date --- The `str` to convert to a `SystemTime`.
+pub fn parse(date: &str) -> Result<SystemTime, ParseError> {
+    let invalid = || ParseError::InvalidDate(String::from(date));
+    let parts: Vec<&str> = date.split_whitespace().collect();
+    
+    // Get the day, month, year and time of day of whichever format the date is in.
+    let (day, month, year, time) = match parts.len() {
+        // IMF-fixdate, `Sun, 06 Nov 1994 08:49:37 GMT`.
+        6 if parts[5] == "GMT" && parts[0].ends_with(',') => {
+            if !WEEKDAYS.contains(&parts[0].trim_end_matches(',')) {
+                return Err(invalid());
+            }
+            (number(parts[1], 2), parts[2], number(parts[3], 4).ok_or_else(invalid)?, parts[4])
+        },
+        // RFC 850, `Sunday, 06-Nov-94 08:49:37 GMT`.
+        4 if parts[3] == "GMT" && parts[0].ends_with(',') => {
+            if !LONG_WEEKDAYS.contains(&parts[0].trim_end_matches(',')) {
+                return Err(invalid());
+            }
+            let date: Vec<&str> = parts[1].split('-').collect();
+            if date.len() != 3 {
+                return Err(invalid());
+            }
+            let year = number(date[2], 2).ok_or_else(invalid)?;
+            
+            (number(date[0], 2), date[1], if year < 70 { 2000 + year } else { 1900 + year }, parts[2])
+        },
+        // asctime, `Sun Nov  6 08:49:37 1994`.
+        5 => {
+            if !WEEKDAYS.contains(&parts[0]) {
+                return Err(invalid());
+            }
+            // The day may be padded with a space rather than a zero.
+            (number(parts[2], 2).or_else(|| number(parts[2], 1)), parts[1], number(parts[4], 4).ok_or_else(invalid)?, parts[3])
+        },
+        _ => return Err(invalid())
+    };
+    
+    let month = match MONTHS.iter().position(|m| *m == month) {
+        Some(month) => month as u64 + 1,
+        None => return Err(invalid())
+    };
+    let day = match day {
+        Some(day) if day >= 1 && day <= days_in_month(year, month) => day,
+        _ => return Err(invalid())
+    };
+    let time: Vec<u64> = time.split(':').filter_map(|n| number(n, 2)).collect();
+    // A second of 60 allows for leap seconds.
+    if time.len() != 3 || time[0] > 23 || time[1] > 59 || time[2] > 60 {
+        return Err(invalid());
+    }
+    
+    let secs = days_from_civil(year, month, day) * 86400 + (time[0] * 3600 + time[1] * 60 + time[2]) as i64;
+    if secs >= 0 {
+        Ok(UNIX_EPOCH + Duration::from_secs(secs as u64))
+    } else {
+        Ok(UNIX_EPOCH - Duration::from_secs(-secs as u64))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// The value of a `Retry-After` Header Field, as in RFC 7231 section 7.1.3.
+pub enum RetryAfter {
+    /// Retry after this many seconds from when the response was received.
+    Delay(Duration),
+    /// Retry after this time.
+    Date(SystemTime)
+}
+
+impl RetryAfter {
+    /// Converts the passed `Retry-After` value to a `RetryAfter`, or returns `None` if it
+    /// is neither a number of seconds nor an HTTP-date.
+    ///
+    /// # Params
+    ///
+    /// value --- The `Retry-After` value to convert.
+    pub fn parse(value: &str) -> Option<RetryAfter> {
+        let value = value.trim();
+        
+        if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) {
+            value.parse().ok().map(|secs| RetryAfter::Delay(Duration::from_secs(secs)))
+        } else {
+            parse(value).ok().map(RetryAfter::Date)
+        }
+    }
+}
+
+impl fmt::Display for RetryAfter {
+    /// Writes the `Retry-After` value, whole seconds for a `Delay` and an IMF-fixdate for
+    /// a `Date`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RetryAfter::Delay(delay) => write!(f, "{}", delay.as_secs()),
+            RetryAfter::Date(time) => write!(f, "{}", format(time))
+        }
+    }
+}
+
+/// Returns the value of `digits` if it is exactly `len` decimal digits.
+///
+/// # Params
+///
+/// digits --- The `str` to convert.</br>
+/// len --- The number of digits required.
+fn number(digits: &str, len: usize) -> Option<u64> {
+    if digits.len() == len && digits.bytes().all(|b| b.is_ascii_digit()) {
+        digits.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Returns the number of days in the passed month.
+///
+/// # Params
+///
+/// year --- The year the month is in.</br>
+/// month --- The month, starting from 1 for January.
+fn days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31
+    }
+}
+
+/// Returns the number of days between the epoch and the passed civil date.
+///
+/// # Params
+///
+/// year --- The year of the date.</br>
+/// month --- The month of the date, starting from 1 for January.</br>
+/// day --- The day of the month, starting from 1.
+fn days_from_civil(year: u64, month: u64, day: u64) -> i64 {
+    // Years are counted from March so that the leap day is the last day of the year.
+    let year = if month <= 2 { year as i64 - 1 } else { year as i64 };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * ((month as i64 + 9) % 12) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    
+    era * 146097 + doe - 719468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        assert_eq!(
+            format(UNIX_EPOCH + Duration::from_secs(784111777)),
+            "Sun, 06 Nov 1994 08:49:37 GMT",
+            "Test date::format-1 failed."
+        );
+        
+        assert_eq!(
+            format(UNIX_EPOCH + Duration::from_secs(951782400)),
+            "Tue, 29 Feb 2000 00:00:00 GMT",
+            "Test date::format-2 failed."
+        );
+        
+        assert_eq!(
+            format(UNIX_EPOCH),
+            "Thu, 01 Jan 1970 00:00:00 GMT",
+            "Test date::format-3 failed."
+        );
+        
+        // Every day of a week starting on Monday 5th January 1970.
+        for (i, weekday) in WEEKDAYS.iter().enumerate() {
+            assert!(
+                format(UNIX_EPOCH + Duration::from_secs((4 + i as u64) * 86400)).starts_with(weekday),
+                "Test date::format-4 failed for `{}`.", weekday
+            );
+        }
+        
+        // The first day of every month of 2001.
+        let mut days = 11323;
+        for (i, month) in MONTHS.iter().enumerate() {
+            assert_eq!(
+                &format(UNIX_EPOCH + Duration::from_secs(days * 86400))[5..16],
+                format!("01 {} 2001", month),
+                "Test date::format-5 failed for `{}`.", month
+            );
+            days += days_in_month(2001, i as u64 + 1);
+        }
+    }
+    #[test]
+    fn test_parse() {
+        let time = UNIX_EPOCH + Duration::from_secs(784111777);
+        
+        assert_eq!(
+            parse("Sun, 06 Nov 1994 08:49:37 GMT").unwrap(),
+            time,
+            "Test date::parse-1 failed."
+        );
+        
+        assert_eq!(
+            parse("Sunday, 06-Nov-94 08:49:37 GMT").unwrap(),
+            time,
+            "Test date::parse-2 failed."
+        );
+        
+        assert_eq!(
+            parse("Sun Nov  6 08:49:37 1994").unwrap(),
+            time,
+            "Test date::parse-3 failed."
+        );
+        
+        assert_eq!(
+            parse("Tue, 29 Feb 2000 00:00:00 GMT").unwrap(),
+            UNIX_EPOCH + Duration::from_secs(951782400),
+            "Test date::parse-4 failed."
+        );
+        
+        assert_eq!(
+            parse("Wed, 31 Dec 1969 23:59:59 GMT").unwrap(),
+            UNIX_EPOCH - Duration::from_secs(1),
+            "Test date::parse-5 failed."
+        );
+        
+        let now = UNIX_EPOCH + Duration::from_secs(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs());
+        assert_eq!(
+            parse(&format(now)).unwrap(),
+            now,
+            "Test date::parse-6 failed."
+        );
+        
+        for date in [
+            "", "Sun, 06 Nov 1994 08:49:37 UTC", "Sun, 06 Nov 94 08:49:37 GMT", "Sun, 29 Feb 1900 08:49:37 GMT",
+            "Sun, 06 Nov 1994 24:00:00 GMT", "Sun, 06 Foo 1994 08:49:37 GMT", "Sunday, 06 Nov 1994 08:49:37 GMT",
+            "Sun, 06-Nov-94 08:49:37 GMT", "Sun Nov  6 08:49 1994", "Sun, 6 Nov 1994 08:49:37 GMT"
+        ].iter() {
+            assert_eq!(
+                parse(date),
+                Err(ParseError::InvalidDate(String::from(*date))),
+                "Test date::parse-7 failed for `{}`.", date
+            );
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! `disposition` is a module to handle the `Content-Disposition` Header Field, as in RFC 6266.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::string::String;
+use super::{ParseError, BuildError};
+use super::token;
+use super::percent;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// A `ContentDisposition` says how the recipient should present a message body or
+/// `multipart/form-data` part, e.g. as a download.
+pub struct ContentDisposition {
+    /// The lowercase disposition type, e.g. `attachment`, `inline` or `form-data`.
+    pub disposition: String,
+    /// The `name` parameter, the form field of a `multipart/form-data` part.
+    pub name: Option<String>,
+    /// The `filename` to save the body as.
+    pub filename: Option<String>
+}
+
+impl ContentDisposition {
+    /// Returns a new `ContentDisposition` of the passed type with no parameters.
+    ///
+    /// # Params
+    ///
+    /// disposition --- The disposition type, e.g. `attachment`.
+    pub fn new(disposition: &str) -> ContentDisposition {
+        ContentDisposition {
+            disposition: disposition.to_lowercase(),
+            name: None,
+            filename: None
+        }
+    }
+    /// Returns a new `attachment` `ContentDisposition`, asking the recipient to save the
+    /// body as the passed filename.
+    ///
+    /// # Params
+    ///
+    /// filename --- The filename to save the body as.
+    pub fn attachment(filename: &str) -> ContentDisposition {
+        ContentDisposition::new("attachment").filename(filename)
+    }
+    /// Sets the `name` parameter.
+    ///
+    /// # Params
+    ///
+    /// name --- The form field of a `multipart/form-data` part.
+    pub fn name(mut self, name: &str) -> ContentDisposition {
+        self.name = Some(String::from(name));
+        self
+    }
+    /// Sets the `filename` parameter.
+    ///
+    /// # Params
+    ///
+    /// filename --- The filename to save the body as.
+    pub fn filename(mut self, filename: &str) -> ContentDisposition {
+        self.filename = Some(String::from(filename));
+        self
+    }
+    /// Converts the passed `Content-Disposition` value to a `ContentDisposition`.
+    ///
+    /// Parameter names are case-insensitive and values may be quoted. A UTF-8 `filename*`
+    /// takes precedence over a `filename`, as in RFC 5987, any other charset is ignored.</br>
+    /// Fails if the disposition type is not a token.
+    ///
+    /// # Params
+    ///
+    /// value --- The value of the `Content-Disposition` Header Field.
+    pub fn from(value: &str) -> Result<ContentDisposition, ParseError> {
+        let disposition = value.split(';').next().unwrap_or_default().trim();
+        if !token::is_token(disposition) {
+            return Err(ParseError::InvalidContentDisposition(String::from(value)));
+        }
+        
+        let mut res = ContentDisposition::new(disposition);
+        let mut extended = None;
+        for (param, value) in token::parameters(value) {
+            match param.as_str() {
+                "name" => res.name = Some(value),
+                "filename" => res.filename = Some(value),
+                "filename*" => extended = decode_extended(&value).or(extended),
+                _ => ()
+            }
+        }
+        if extended.is_some() {
+            res.filename = extended;
+        }
+        Ok(res)
+    }
+    /// Returns the value of the `Content-Disposition` Header Field.
+    ///
+    /// The `name` and `filename` are quoted. If the `filename` is not all ASCII it is also
+    /// written as a UTF-8 `filename*`, with each non-ASCII character of the `filename`
+    /// replaced by `_` for recipients which do not understand `filename*`.</br>
+    /// Fails if the disposition type is not a token or a parameter contains control
+    /// characters.
+    pub fn to_header_value(&self) -> Result<String, BuildError> {
+        if !token::is_token(&self.disposition) {
+            return Err(BuildError::InvalidContentDisposition(self.disposition.clone()));
+        }
+        
+        if let Some(value) = [&self.name, &self.filename].iter().filter_map(|value| value.as_ref()).find(|value| value.chars().any(char::is_control)) {
+            return Err(BuildError::InvalidContentDisposition(value.clone()));
+        }
+        
+        let mut res = self.disposition.clone();
+        if let Some(ref name) = self.name {
+            res.push_str(&format!("; name={}", token::quote_string(name)));
+        }
+        if let Some(ref filename) = self.filename {
+            let fallback = filename.chars()
+                .map(|c| if c.is_ascii() { c } else { '_' })
+                .collect::<String>();
+            
+            res.push_str(&format!("; filename={}", token::quote_string(&fallback)));
+            if !filename.is_ascii() {
+                res.push_str(&format!("; filename*=UTF-8''{}", percent::encode(filename)));
+            }
+        }
+        Ok(res)
+    }
+}
+
+/// Decodes an RFC 5987 `ext-value`, e.g. `UTF-8'en'%e2%82%ac%20rates`.</br>
+/// Returns `None` if the value is malformed or not UTF-8.
+///
+/// # Params
+///
+/// value --- The `ext-value` to decode.
+fn decode_extended(value: &str) -> Option<String> {
+    let mut parts = value.splitn(3, '\'');
+    let charset = parts.next()?;
+    let _language = parts.next()?;
+    let encoded = parts.next()?;
+    
+    if charset.eq_ignore_ascii_case("utf-8") {
+        percent::decode(encoded).ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_disposition_to_header_value() {
+        assert_eq!(
+            ContentDisposition::attachment("report.pdf").to_header_value(),
+            Ok(String::from("attachment; filename=\"report.pdf\"")),
+            "Test ContentDisposition::to_header_value-1 failed."
+        );
+        assert_eq!(
+            ContentDisposition::attachment("my \"big\" re\\port \u{1F4CA}.pdf").to_header_value(),
+            Ok(String::from("attachment; filename=\"my \\\"big\\\" re\\\\port _.pdf\"; filename*=UTF-8''my%20%22big%22%20re%5Cport%20%F0%9F%93%8A.pdf")),
+            "Test ContentDisposition::to_header_value-2 failed."
+        );
+        assert_eq!(
+            ContentDisposition::new("Form-Data").name("photo").filename("a b.png").to_header_value(),
+            Ok(String::from("form-data; name=\"photo\"; filename=\"a b.png\"")),
+            "Test ContentDisposition::to_header_value-3 failed."
+        );
+        assert_eq!(
+            ContentDisposition::new("inline").to_header_value(),
+            Ok(String::from("inline")),
+            "Test ContentDisposition::to_header_value-4 failed."
+        );
+        assert_eq!(
+            ContentDisposition::attachment("a\r\nSet-Cookie: x").to_header_value(),
+            Err(BuildError::InvalidContentDisposition(String::from("a\r\nSet-Cookie: x"))),
+            "Test ContentDisposition::to_header_value-5 failed."
+        );
+        assert_eq!(
+            ContentDisposition::new("attach ment").to_header_value(),
+            Err(BuildError::InvalidContentDisposition(String::from("attach ment"))),
+            "Test ContentDisposition::to_header_value-6 failed."
+        );
+    }
+    #[test]
+    fn test_content_disposition_from() {
+        assert_eq!(
+            ContentDisposition::from("Attachment; FILENAME=plain.txt"),
+            Ok(ContentDisposition::attachment("plain.txt")),
+            "Test ContentDisposition::from-1 failed."
+        );
+        assert_eq!(
+            ContentDisposition::from("attachment; filename*=UTF-8''%e2%82%ac%20rates; filename=\"EURO rates\""),
+            Ok(ContentDisposition::attachment("\u{20AC} rates")),
+            "Test ContentDisposition::from-2 failed."
+        );
+        assert_eq!(
+            ContentDisposition::from("attachment; filename=\"fallback.txt\"; filename*=ISO-8859-1'en'%A3%20rates"),
+            Ok(ContentDisposition::attachment("fallback.txt")),
+            "Test ContentDisposition::from-3 failed."
+        );
+        assert_eq!(
+            ContentDisposition::from("form-data; name=\"field\""),
+            Ok(ContentDisposition::new("form-data").name("field")),
+            "Test ContentDisposition::from-4 failed."
+        );
+        assert_eq!(
+            ContentDisposition::from("; filename=x"),
+            Err(ParseError::InvalidContentDisposition(String::from("; filename=x"))),
+            "Test ContentDisposition::from-5 failed."
+        );
+        
+        let disposition = ContentDisposition::attachment("my \"big\" re\\port \u{1F4CA}.pdf");
+        assert_eq!(
+            ContentDisposition::from(&disposition.to_header_value().unwrap()),
+            Ok(disposition),
+            "Test ContentDisposition::from-6 failed to round-trip."
+        );
+    }
+}
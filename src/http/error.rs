@@ -0,0 +1,314 @@
+//! `error` is a module which defines the errors raised while parsing or building HTTP messages and their components.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// A `ParseError` describes why a HTTP message, or one of its components, could not be parsed.
+pub enum ParseError {
+    /// The message did not contain a Start line.
+    MissingStartLine,
+    /// The Request line used a method which is not a token.
+    InvalidMethod(String),
+    /// The Request line ended before the target.
+    MissingTarget,
+    /// The Request line ended before the HTTP version.
+    MissingVersion,
+    /// The HTTP version was not of the form `HTTP/x.y`.
+    InvalidVersion(String),
+    /// The HTTP version was not exactly `HTTP/` and a digit either side of a `.`, which a
+    /// `Strict` parse requires.
+    NonCanonicalVersion(String),
+    /// A recognised method was not uppercase, which a `Strict` parse does not fold.
+    NonCanonicalMethod(String),
+    /// A Header Field had whitespace between its name and colon, which a `Strict` parse
+    /// rejects.
+    WhitespaceBeforeColon(String),
+    /// The Status line ended before the status code.
+    MissingStatusCode,
+    /// The Status line had a status code which is not an unsigned integer.
+    InvalidStatusCode(String),
+    /// The Status line had a status code outside of 100 to 599.
+    StatusCodeOutOfRange(String),
+    /// The Start line had an unterminated quoted target or content after the HTTP version.
+    MalformedStartLine(String),
+    /// A Header Field could not be parsed.
+    MalformedHeader(String),
+    /// A line was folded onto the line before it where folding is not allowed.
+    ObsoleteFold(String),
+    /// The `Content-Length` Header Field was not a valid length.
+    InvalidContentLength(String),
+    /// The message declared more than one different `Content-Length`.
+    ConflictingContentLength,
+    /// The message body was shorter than its declared `Content-Length`.
+    IncompleteBody {
+        /// The number of bytes declared by the `Content-Length`.
+        expected: usize,
+        /// The number of bytes which were available.
+        received: usize
+    },
+    /// A chunk of a chunked message body was malformed.
+    InvalidChunk(String),
+    /// A chunked message body ended before its last chunk.
+    IncompleteChunk,
+    /// The framing of the message body was invalid, e.g. the final transfer coding of a
+    /// request was not `chunked`.
+    InvalidFraming,
+    /// The message had both a `Transfer-Encoding` and a `Content-Length`, which each server
+    /// a request passes through could read differently.
+    AmbiguousFraming,
+    /// The start line was longer than the limit, e.g. because of a long request target.
+    UriTooLong {
+        /// The most bytes allowed in the start line.
+        limit: usize,
+        /// The bytes of the start line seen when it passed the limit.
+        size: usize
+    },
+    /// The header section, or the trailer section of a chunked body, was larger than the
+    /// limit.
+    HeaderTooLarge {
+        /// The most bytes allowed in the section.
+        limit: usize,
+        /// The bytes of the section seen when it passed the limit.
+        size: usize
+    },
+    /// The header section had more Header fields than the limit.
+    TooManyHeaderFields {
+        /// The most Header fields allowed.
+        limit: usize,
+        /// The number of Header fields in the section.
+        count: usize
+    },
+    /// The message body was larger than the limit.
+    BodyTooLarge,
+    /// The message was the HTTP/2 connection preface, sent by a client speaking HTTP/2.
+    Http2Preface,
+    /// The stream ended before the first byte of a message.
+    ConnectionClosed,
+    /// The stream ended part way through a message.
+    UnexpectedEof,
+    /// Reading the stream failed.
+    Io(String),
+    /// A percent-encoded string contained a `%` which was not followed by two hex digits.
+    InvalidPercentEncoding(String),
+    /// The `Content-Type` Header Field was missing or not the type required.
+    UnexpectedContentType(String),
+    /// A multipart `Content-Type` had no `boundary` parameter.
+    MissingBoundary,
+    /// A multipart message body was malformed.
+    InvalidMultipart(String),
+    /// The `Content-Encoding` of the message body is not supported.
+    UnsupportedContentEncoding(String),
+    /// A compressed message body could not be decompressed, only with the `compression`
+    /// feature.
+    InvalidCompressedBody(String),
+    /// An entity tag was not a quoted string, optionally prefixed by `W/`.
+    InvalidETag(String),
+    /// A request target was not in any of the forms a request target may take.
+    InvalidTarget(String),
+    /// A request path would escape the root directory or named a file outside it.
+    InvalidPath(String),
+    /// A `Host` was not a valid host and optional port.
+    InvalidHost(String),
+    /// A URI or relative reference was not valid.
+    InvalidUri(String),
+    /// A `Cache-Control` directive was not a token or had an invalid argument.
+    InvalidCacheControl(String),
+    /// A `Content-Disposition` did not start with a disposition type.
+    InvalidContentDisposition(String),
+    /// A quoted-string was unterminated, did not start with a quote or contained a control character.
+    InvalidQuotedString(String),
+    /// A HTTP/1.1 request had no `Host` Header Field.
+    MissingHost,
+    /// A request had more than one `Host` Header Field.
+    DuplicateHost,
+    /// A HTTP-date was not in any of the recognised formats.
+    InvalidDate(String),
+    /// The message was not valid utf8.
+    InvalidUtf8,
+    /// The start line or header section contained a control character other than a
+    /// horizontal tab or a line terminator, e.g. a NUL or a bare CR.
+    IllegalCharacter {
+        /// The index of the byte, from the start of the message or of the line which was
+        /// parsed.
+        position: usize
+    },
+    /// A line of the start line or header section ended with a LF which was not preceded by
+    /// a CR, while parsing `Strict`ly.
+    BareLineFeed {
+        /// The index of the LF, from the start of the message or of the line which was
+        /// parsed.
+        position: usize
+    },
+    /// The `charset` of a text message body is not supported.
+    UnsupportedCharset(String),
+    /// A text message body was not valid in its `charset`.
+    InvalidText(String),
+    /// A JSON message body could not be deserialized, only with the `json` feature.
+    InvalidJson(String)
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseError::MissingStartLine => write!(f, "Bad Message string, no Start line."),
+            ParseError::InvalidMethod(ref method) => write!(f, "Bad method for Request line: `{}`", method),
+            ParseError::MissingTarget => write!(f, "Request line missing target."),
+            ParseError::MissingVersion => write!(f, "Request line missing version."),
+            ParseError::InvalidVersion(ref version) => write!(f, "Bad HTTP version, not of the form `HTTP/x.y`: `{}`", version),
+            ParseError::NonCanonicalVersion(ref version) => write!(f, "Bad HTTP version, not exactly `HTTP/x.y`: `{}`", version),
+            ParseError::NonCanonicalMethod(ref method) => write!(f, "Bad method for Request line, not uppercase: `{}`", method),
+            ParseError::WhitespaceBeforeColon(ref field) => write!(f, "Bad Header Field, whitespace before the colon: `{}`", field),
+            ParseError::MissingStatusCode => write!(f, "Status line missing code."),
+            ParseError::InvalidStatusCode(ref code) => write!(f, "Bad code for Status line, not an unsigned integer: `{}`", code),
+            ParseError::StatusCodeOutOfRange(ref code) => write!(f, "Bad code for Status line, not from 100 to 599: `{}`", code),
+            ParseError::MalformedStartLine(ref line) => write!(f, "Bad Start line: `{}`", line),
+            ParseError::MalformedHeader(ref field) => write!(f, "Bad Header Field: `{}`", field),
+            ParseError::ObsoleteFold(ref line) => write!(f, "Bad folded line: `{}`", line),
+            ParseError::InvalidContentLength(ref length) => write!(f, "Bad Content-Length, not an unsigned integer: `{}`", length),
+            ParseError::ConflictingContentLength => write!(f, "Message has conflicting Content-Lengths."),
+            ParseError::IncompleteBody { expected, received } => write!(f, "Incomplete message body, expected {} bytes but received {}.", expected, received),
+            ParseError::InvalidChunk(ref chunk) => write!(f, "Bad chunk in chunked message body: `{}`", chunk),
+            ParseError::IncompleteChunk => write!(f, "Incomplete chunked message body, no last chunk."),
+            ParseError::InvalidFraming => write!(f, "Invalid message body framing."),
+            ParseError::AmbiguousFraming => write!(f, "Ambiguous message body framing, both a Transfer-Encoding and a Content-Length."),
+            ParseError::UriTooLong { limit, size } => write!(f, "Start line too long, {} bytes is over the limit of {}.", size, limit),
+            ParseError::HeaderTooLarge { limit, size } => write!(f, "Header section too large, {} bytes is over the limit of {}.", size, limit),
+            ParseError::TooManyHeaderFields { limit, count } => write!(f, "Too many Header fields, {} is over the limit of {}.", count, limit),
+            ParseError::BodyTooLarge => write!(f, "Message body too large."),
+            ParseError::Http2Preface => write!(f, "HTTP/2 connection preface received."),
+            ParseError::ConnectionClosed => write!(f, "Connection closed before a message was sent."),
+            ParseError::UnexpectedEof => write!(f, "Connection closed part way through a message."),
+            ParseError::Io(ref error) => write!(f, "Failed to read message: {}", error),
+            ParseError::InvalidPercentEncoding(ref encoded) => write!(f, "Bad percent-encoding: `{}`", encoded),
+            ParseError::UnexpectedContentType(ref content_type) => write!(f, "Unexpected Content-Type: `{}`", content_type),
+            ParseError::MissingBoundary => write!(f, "Multipart Content-Type missing boundary."),
+            ParseError::InvalidMultipart(ref reason) => write!(f, "Bad multipart message body, {}", reason),
+            ParseError::UnsupportedContentEncoding(ref encoding) => write!(f, "Unsupported Content-Encoding: `{}`", encoding),
+            ParseError::InvalidCompressedBody(ref error) => write!(f, "Bad compressed message body: {}", error),
+            ParseError::InvalidETag(ref etag) => write!(f, "Bad entity tag: `{}`", etag),
+            ParseError::InvalidTarget(ref target) => write!(f, "Bad request target: `{}`", target),
+            ParseError::InvalidPath(ref path) => write!(f, "Bad request path, outside the root: `{}`", path),
+            ParseError::InvalidHost(ref host) => write!(f, "Bad Host: `{}`", host),
+            ParseError::InvalidUri(ref uri) => write!(f, "Bad URI: `{}`", uri),
+            ParseError::InvalidCacheControl(ref directive) => write!(f, "Bad Cache-Control directive: `{}`", directive),
+            ParseError::InvalidContentDisposition(ref value) => write!(f, "Bad Content-Disposition: `{}`", value),
+            ParseError::InvalidQuotedString(ref value) => write!(f, "Bad quoted-string: `{:?}`", value),
+            ParseError::MissingHost => write!(f, "HTTP/1.1 request missing Host."),
+            ParseError::DuplicateHost => write!(f, "Request has more than one Host."),
+            ParseError::InvalidDate(ref date) => write!(f, "Bad HTTP-date: `{}`", date),
+            ParseError::InvalidUtf8 => write!(f, "Bad bytes for utf8 encoded message."),
+            ParseError::IllegalCharacter { position } => write!(f, "Bad message, illegal control character at byte {}.", position),
+            ParseError::BareLineFeed { position } => write!(f, "Bad message, line feed without a carriage return at byte {}.", position),
+            ParseError::UnsupportedCharset(ref charset) => write!(f, "Unsupported charset: `{}`", charset),
+            ParseError::InvalidText(ref charset) => write!(f, "Bad bytes for `{}` encoded message body.", charset),
+            ParseError::InvalidJson(ref error) => write!(f, "Bad JSON message body: {}", error)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// A `BuildError` describes why a HTTP message, or one of its components, could not be built.
+pub enum BuildError {
+    /// A cookie had a name, value or attribute containing illegal characters.
+    InvalidCookie(String),
+    /// A `Content-Disposition` type was not a token or a parameter contained control characters.
+    InvalidContentDisposition(String),
+    /// A Header Field name was not a token.
+    InvalidHeaderName(String),
+    /// A Header Field value contained illegal characters.
+    InvalidHeaderValue(String),
+    /// A request used a method which is not a token.
+    InvalidMethod(String),
+    /// A request target was empty or contained whitespace or control characters.
+    InvalidTarget(String),
+    /// A reason phrase contained control characters other than a horizontal tab.
+    InvalidReason(String),
+    /// A response had a status code outside of 100 to 599.
+    StatusCodeOutOfRange(u16),
+    /// A `1xx`, `204 No Content` or `304 Not Modified` response had a body or a
+    /// `Content-Length` or `Transfer-Encoding`.
+    BodyNotAllowed(u16),
+    /// A `Retry-After` was given for a response other than a `3xx`, `429 Too Many Requests`
+    /// or `503 Service Unavailable`.
+    RetryAfterNotAllowed(u16),
+    /// A value could not be serialized as JSON, only with the `json` feature.
+    InvalidJson(String)
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BuildError::InvalidCookie(ref cookie) => write!(f, "Bad cookie, illegal characters in: `{}`", cookie),
+            BuildError::InvalidContentDisposition(ref value) => write!(f, "Bad Content-Disposition, illegal characters in: `{:?}`", value),
+            BuildError::InvalidHeaderName(ref name) => write!(f, "Bad Header Field name, not a token: `{:?}`", name),
+            BuildError::InvalidHeaderValue(ref value) => write!(f, "Bad Header Field value, illegal characters in: `{:?}`", value),
+            BuildError::InvalidMethod(ref method) => write!(f, "Bad method for Request line: `{}`", method),
+            BuildError::InvalidTarget(ref target) => write!(f, "Bad request target, empty or illegal characters in: `{:?}`", target),
+            BuildError::InvalidReason(ref reason) => write!(f, "Bad reason phrase, illegal characters in: `{:?}`", reason),
+            BuildError::StatusCodeOutOfRange(code) => write!(f, "Bad status code, not from 100 to 599: `{}`", code),
+            BuildError::BodyNotAllowed(code) => write!(f, "Bad response, a `{}` response can not have a body.", code),
+            BuildError::RetryAfterNotAllowed(code) => write!(f, "Bad response, a `{}` response can not have a Retry-After.", code),
+            BuildError::InvalidJson(ref error) => write!(f, "Value could not be serialized as JSON: {}", error)
+        }
+    }
+}
+
+impl Error for BuildError {}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// A `RangeError` describes why a `Range` Header Field could not be served.
+pub enum RangeError {
+    /// The `Range` was not a valid set of byte ranges and should be ignored.
+    Malformed(String),
+    /// None of the ranges overlap the representation of the passed length, a `416`
+    /// should be sent with a `Content-Range` of `bytes */length`.
+    Unsatisfiable(u64),
+    /// More than one range was requested, which is not supported.
+    MultipleRanges
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RangeError::Malformed(ref range) => write!(f, "Bad Range: `{}`", range),
+            RangeError::Unsatisfiable(length) => write!(f, "Range not satisfiable for a length of {} bytes.", length),
+            RangeError::MultipleRanges => write!(f, "Multiple ranges are not supported.")
+        }
+    }
+}
+
+impl Error for RangeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            ParseError::InvalidMethod(String::from("BR EW")).to_string(),
+            "Bad method for Request line: `BR EW`",
+            "Test ParseError::fmt-1 failed."
+        );
+        
+        assert_eq!(
+            ParseError::MalformedHeader(String::from("no colon")).to_string(),
+            "Bad Header Field: `no colon`",
+            "Test ParseError::fmt-2 failed."
+        );
+        
+        assert_eq!(
+            ParseError::InvalidUtf8.to_string(),
+            "Bad bytes for utf8 encoded message.",
+            "Test ParseError::fmt-3 failed."
+        );
+    }
+}
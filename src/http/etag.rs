@@ -0,0 +1,298 @@
+//! `etag` is a module to handle entity tags and the `If-None-Match` Header Field.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::fmt;
+use std::string::String;
+use super::{MessageHTTP, ParseError, StatusCode};
+use super::headers;
+use super::start_line::StartLine;
+
+/// The Header Fields a `304 Not Modified` response keeps from the response it replaces.
+static NOT_MODIFIED_FIELDS: [&str; 6] = [headers::CACHE_CONTROL, headers::CONTENT_LOCATION, headers::DATE, headers::ETAG, headers::EXPIRES, headers::VARY];
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+/// An `ETag` is an entity tag, an opaque validator for a representation.
+pub struct ETag {
+    /// If the tag is weak, a weak tag only promises the representations are equivalent.
+    pub weak: bool,
+    /// The opaque tag, without quotes.
+    pub tag: String
+}
+
+impl ETag {
+    /// Returns a new strong `ETag`.
+    ///
+    /// # Params
+    ///
+    /// tag --- The opaque tag, without quotes.
+    pub fn strong(tag: &str) -> ETag {
+        ETag { weak: false, tag: String::from(tag) }
+    }
+    /// Returns a new weak `ETag`.
+    ///
+    /// # Params
+    ///
+    /// tag --- The opaque tag, without quotes.
+    pub fn weak(tag: &str) -> ETag {
+        ETag { weak: true, tag: String::from(tag) }
+    }
+    /// Returns a strong `ETag` computed from the length and a 64 bit FNV-1a hash of the
+    /// passed bytes.
+    ///
+    /// # Params
+    ///
+    /// bytes --- The representation to tag.
+    pub fn from_bytes(bytes: &[u8]) -> ETag {
+        let hash = bytes.iter()
+            .fold(0xcbf2_9ce4_8422_2325u64, |hash, b| (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3));
+        
+        ETag { weak: false, tag: format!("{:x}-{:016x}", bytes.len(), hash) }
+    }
+    /// Converts the passed `str` to an `ETag`, e.g. `"xyzzy"` or `W/"xyzzy"`.
+    ///
+    /// # Params
+    ///
+    /// etag --- The `str` to convert to an `ETag`.
+    pub fn from(etag: &str) -> Result<ETag, ParseError> {
+        match parse_list(etag.trim()) {
+            Some(ref mut etags) if etags.len() == 1 => Ok(etags.remove(0)),
+            _ => Err(ParseError::InvalidETag(String::from(etag)))
+        }
+    }
+    /// Returns `true` if the `ETag`s have the same opaque tag, regardless of weakness.
+    ///
+    /// # Params
+    ///
+    /// other --- The `ETag` to compare to.
+    pub fn weak_eq(&self, other: &ETag) -> bool {
+        self.tag == other.tag
+    }
+    /// Returns `true` if both `ETag`s are strong and have the same opaque tag.
+    ///
+    /// # Params
+    ///
+    /// other --- The `ETag` to compare to.
+    pub fn strong_eq(&self, other: &ETag) -> bool {
+        !self.weak && !other.weak && self.tag == other.tag
+    }
+}
+
+impl fmt::Display for ETag {
+    /// Writes the `ETag` as it appears in a Header Field.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.weak {
+            write!(f, "W/\"{}\"", self.tag)
+        } else {
+            write!(f, "\"{}\"", self.tag)
+        }
+    }
+}
+
+/// Returns `true` if the value of an `If-None-Match` Header Field matches `etag`.
+///
+/// `*` matches any `ETag`, otherwise the value is a comma separated list of `ETag`s
+/// compared with weak comparison. A malformed list matches nothing.
+///
+/// # Params
+///
+/// if_none_match --- The value of the `If-None-Match` Header Field.</br>
+/// etag --- The `ETag` of the current representation.
+pub fn matches(if_none_match: &str, etag: &ETag) -> bool {
+    if if_none_match.trim() == "*" {
+        return true;
+    }
+    match parse_list(if_none_match) {
+        Some(etags) => etags.iter().any(|other| other.weak_eq(etag)),
+        None => false
+    }
+}
+
+/// Returns `response`, or a `304 Not Modified` in its place if `request` is a `GET` or
+/// `HEAD` whose `If-None-Match` matches the response's `ETag`.
+///
+/// The `304` keeps the `ETag`, `Cache-Control`, `Content-Location`, `Date`, `Expires`
+/// and `Vary` Header Fields of the response and has no body.
+///
+/// # Params
+///
+/// request --- The request being responded to.</br>
+/// response --- The full response to the request.
+pub fn if_none_match(request: &MessageHTTP, response: MessageHTTP) -> MessageHTTP {
+    match request.start_line.as_request() {
+        Some(("GET", _, _)) | Some(("HEAD", _, _)) => (),
+        _ => return response
+    }
+    let etag = match response.header(headers::ETAG).map(ETag::from) {
+        Some(Ok(etag)) => etag,
+        _ => return response
+    };
+    let if_none_match = request.headers(headers::IF_NONE_MATCH).collect::<Vec<&str>>().join(",");
+    if request.header(headers::IF_NONE_MATCH).is_none() || !matches(&if_none_match, &etag) {
+        return response;
+    }
+    
+    let version = match response.start_line.as_status() {
+        Some((version, _, _)) => version,
+        None => return response
+    };
+    MessageHTTP::new(
+        StartLine::StatusLine {
+            version,
+            code: StatusCode::NOT_MODIFIED,
+            reason: StatusCode::NOT_MODIFIED.reason_phrase().map(String::from)
+        },
+        response.header_fields.into_iter()
+            .filter(|field| NOT_MODIFIED_FIELDS.iter().any(|name| field.name_eq(name)))
+            .collect(),
+        Vec::new()
+    )
+}
+
+/// Returns the `ETag`s in a comma separated list, or `None` if the list is malformed.
+///
+/// # Params
+///
+/// list --- The list of `ETag`s.
+fn parse_list(list: &str) -> Option<Vec<ETag>> {
+    let mut res = Vec::new();
+    let mut rest = list;
+    
+    loop {
+        // Empty list elements are allowed.
+        rest = rest.trim_start_matches(&[',', ' ', '\t'][..]);
+        if rest.is_empty() {
+            return Some(res);
+        }
+        
+        let weak = rest.starts_with("W/");
+        if weak {
+            rest = &rest[2..];
+        }
+        if !rest.starts_with('"') {
+            return None;
+        }
+        // The opaque tag may contain any visible character except a quote.
+        let end = rest[1..].find('"')? + 1;
+        let tag = &rest[1..end];
+        if tag.bytes().any(|b| b < 0x21 || b == 0x7F) {
+            return None;
+        }
+        res.push(ETag { weak, tag: String::from(tag) });
+        
+        // Each `ETag` must be followed by a separator or the end of the list.
+        rest = rest[end + 1..].trim_start_matches(&[' ', '\t'][..]);
+        if !rest.is_empty() && !rest.starts_with(',') {
+            return None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{ResponseBuilder, HTTP};
+
+    #[test]
+    fn test_etag() {
+        assert_eq!(
+            (ETag::from("\"xyzzy\""), ETag::from(" W/\"xyzzy\" ")),
+            (Ok(ETag::strong("xyzzy")), Ok(ETag::weak("xyzzy"))),
+            "Test ETag::from-1 failed."
+        );
+        
+        for etag in ["xyzzy", "\"xy\"zzy\"", "w/\"xyzzy\"", "\"a\", \"b\"", "\"a b\"", ""].iter() {
+            assert_eq!(
+                ETag::from(etag),
+                Err(ParseError::InvalidETag(String::from(*etag))),
+                "Test ETag::from-2 failed for `{}`.", etag
+            );
+        }
+        
+        assert_eq!(
+            (ETag::strong("a,b").to_string(), ETag::weak("").to_string()),
+            (String::from("\"a,b\""), String::from("W/\"\"")),
+            "Test ETag::fmt-1 failed."
+        );
+        
+        // RFC 7232 section 2.3.2.
+        let pairs = [
+            (ETag::weak("1"), ETag::weak("1"), false, true),
+            (ETag::weak("1"), ETag::weak("2"), false, false),
+            (ETag::weak("1"), ETag::strong("1"), false, true),
+            (ETag::strong("1"), ETag::strong("1"), true, true)
+        ];
+        for &(ref a, ref b, strong, weak) in pairs.iter() {
+            assert_eq!(
+                (a.strong_eq(b), a.weak_eq(b)),
+                (strong, weak),
+                "Test ETag::eq-1 failed for `{}` and `{}`.", a, b
+            );
+        }
+        
+        assert_eq!(
+            (ETag::from_bytes(b"hello"), ETag::from_bytes(b"hello").weak),
+            (ETag::from_bytes(b"hello"), false),
+            "Test ETag::from_bytes-1 failed."
+        );
+        assert!(
+            ETag::from_bytes(b"hello") != ETag::from_bytes(b"hellp") && ETag::from_bytes(b"") != ETag::from_bytes(b"\0"),
+            "Test ETag::from_bytes-2 failed."
+        );
+    }
+    #[test]
+    fn test_matches() {
+        let etag = ETag::strong("xyzzy");
+        
+        assert!(
+            matches("*", &etag) && matches(" * ", &ETag::weak("a")),
+            "Test etag::matches-1 failed."
+        );
+        
+        assert!(
+            matches("\"xyzzy\"", &etag) && matches("W/\"xyzzy\"", &etag) && matches("\"xyzzy\"", &ETag::weak("xyzzy")),
+            "Test etag::matches-2 failed."
+        );
+        
+        assert!(
+            matches("\"r2d2xxxx\", \"c3piozzzz\", \"xyzzy\"", &etag) && matches(",\"a,b\" ,W/\"xyzzy\",", &etag),
+            "Test etag::matches-3 failed."
+        );
+        
+        assert!(
+            !matches("\"r2d2xxxx\", \"c3piozzzz\"", &etag) && !matches("xyzzy", &etag) && !matches("", &etag) && !matches("\"xyzzy\" \"a\"", &etag),
+            "Test etag::matches-4 failed."
+        );
+    }
+    #[test]
+    fn test_if_none_match() {
+        let response = ResponseBuilder::new(200)
+            .header("ETag", "\"xyzzy\"")
+            .header("Cache-Control", "max-age=60")
+            .header("Content-Type", "text/plain")
+            .body("hello")
+            .build()
+            .unwrap();
+        let request = MessageHTTP::from("GET / HTTP/1.1\r\nIf-None-Match: \"a\"\r\nif-none-match: W/\"xyzzy\"\r\n\r\n").unwrap();
+        
+        assert_eq!(
+            if_none_match(&request, response.clone()).to_http().unwrap(),
+            "HTTP/1.1 304 Not Modified\r\nETag: \"xyzzy\"\r\nCache-Control: max-age=60\r\n\r\n",
+            "Test etag::if_none_match-1 failed."
+        );
+        
+        for request in [
+            "GET / HTTP/1.1\r\nIf-None-Match: \"a\"\r\n\r\n", "GET / HTTP/1.1\r\n\r\n",
+            "POST / HTTP/1.1\r\nIf-None-Match: *\r\n\r\n"
+        ].iter() {
+            assert_eq!(
+                if_none_match(&MessageHTTP::from(request).unwrap(), response.clone()),
+                response,
+                "Test etag::if_none_match-2 failed for `{:?}`.", request
+            );
+        }
+    }
+}
@@ -0,0 +1,249 @@
+//! `forwarded` is a module to handle the `Forwarded`, `X-Forwarded-For` and
+//! `X-Forwarded-Proto` Header Fields added by reverse proxies.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::net::IpAddr;
+use std::string::String;
+use super::MessageHTTP;
+use super::headers;
+use super::token;
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+/// A `ForwardedElement` is the information added to a `Forwarded` Header Field by a
+/// single proxy, as in RFC 7239.
+///
+/// Values are unquoted but otherwise kept as they were sent, so a node may be an
+/// address, a bracketed IPv6 address with a port, `unknown` or an obfuscated identifier
+/// such as `_hidden`.
+pub struct ForwardedElement {
+    /// The `for` parameter, the node the proxy received the request from.
+    pub for_: Option<String>,
+    /// The `by` parameter, the node the proxy received the request on.
+    pub by: Option<String>,
+    /// The `host` parameter, the `Host` of the request as the proxy received it.
+    pub host: Option<String>,
+    /// The `proto` parameter, the scheme the proxy received the request with.
+    pub proto: Option<String>
+}
+
+/// Returns the elements of a `Forwarded` Header Field value, in the order the proxies
+/// added them.
+///
+/// Parameter names are case-insensitive and values may be quoted. If a parameter is
+/// repeated within an element only the first is kept, and unknown parameters are
+/// ignored.
+///
+/// # Params
+///
+/// value --- The value of the `Forwarded` Header Field.
+pub fn parse(value: &str) -> Vec<ForwardedElement> {
+    token::split_list(value).into_iter()
+        .map(
+            |element| {
+                let mut res = ForwardedElement::default();
+                
+                // An element has no value before its parameters.
+                for (name, value) in token::parameters(&format!(";{}", element)) {
+                    let parameter = match name.as_str() {
+                        "for" => &mut res.for_,
+                        "by" => &mut res.by,
+                        "host" => &mut res.host,
+                        "proto" => &mut res.proto,
+                        _ => continue
+                    };
+                    if parameter.is_none() {
+                        *parameter = Some(value);
+                    }
+                }
+                res
+            }
+        ).collect()
+}
+
+/// Returns the address of the client which sent the request through the passed proxies,
+/// or `None` if there is no address.
+///
+/// The `for` parameters of the `Forwarded` `HeaderField`s are used if there are any,
+/// otherwise the `X-Forwarded-For` `HeaderField`s. Each proxy appends the address it
+/// received the request from, so the list is walked from right to left and the first
+/// address which is not a trusted proxy is returned; anything to the left of it may have
+/// been sent by the client itself. If every address is a trusted proxy the leftmost
+/// address is returned.</br>
+/// Addresses may have a port, IPv6 addresses may be in brackets, and nodes which are
+/// not an address, e.g. `unknown` or `_hidden`, are skipped.
+///
+/// # Params
+///
+/// req --- The request to get the client address of.</br>
+/// trusted_proxies --- The addresses of the proxies in front of the server.
+pub fn client_ip(req: &MessageHTTP, trusted_proxies: &[IpAddr]) -> Option<IpAddr> {
+    let addresses: Vec<IpAddr> = match req.combined_header(headers::FORWARDED) {
+        Some(header) => parse(&header).into_iter()
+            .filter_map(|element| parse_node(element.for_.as_ref()?))
+            .collect(),
+        None => token::split_list(&req.combined_header(headers::X_FORWARDED_FOR)?).into_iter()
+            .filter_map(parse_node)
+            .collect()
+    };
+    
+    addresses.iter().rev()
+        .find(|address| !trusted_proxies.contains(address))
+        .or_else(|| addresses.first())
+        .cloned()
+}
+
+/// Returns the scheme the request was originally sent with according to the first
+/// `X-Forwarded-Proto` `HeaderField`, e.g. `https`, or `None` if there is no valid scheme.
+///
+/// If the value is a list, as sent by a chain of proxies, the leftmost scheme is the one
+/// the client used. The scheme is returned as it was sent.
+///
+/// # Params
+///
+/// req --- The request to get the original scheme of.
+pub fn original_scheme(req: &MessageHTTP) -> Option<&str> {
+    let scheme = *token::split_list(req.header(headers::X_FORWARDED_PROTO)?).first()?;
+    
+    // A scheme is a letter followed by letters, digits, `+`, `-` or `.`, as in RFC 3986.
+    if scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'-' || b == b'.') {
+        Some(scheme)
+    } else {
+        None
+    }
+}
+
+/// Converts a single node of an `X-Forwarded-For` or `Forwarded` Header Field to an
+/// address, ignoring any port, or `None` if it is not an address.
+///
+/// # Params
+///
+/// node --- The entry to convert, e.g. `192.0.2.1:8080` or `[2001:db8::1]:8080`.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    // A bare address, including an IPv6 address without brackets.
+    if let Ok(address) = node.parse() {
+        return Some(address);
+    }
+    
+    let (address, port) = if node.starts_with('[') {
+        let end = node.find(']')?;
+        let port = &node[end + 1..];
+        
+        if !port.is_empty() && !port.starts_with(':') {
+            return None;
+        }
+        (&node[1..end], port.get(1..).unwrap_or_default())
+    } else {
+        let index = node.find(':')?;
+        
+        (&node[..index], &node[index + 1..])
+    };
+    if !port.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    address.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Returns a request with the passed Header Fields.
+    fn request(fields: &str) -> MessageHTTP {
+        MessageHTTP::from(&format!("GET / HTTP/1.1\r\nHost: example.com\r\n{}\r\n", fields)).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        // RFC 7239 section 4.
+        let element = |for_: Option<&str>, by: Option<&str>, host: Option<&str>, proto: Option<&str>| ForwardedElement {
+            for_: for_.map(String::from),
+            by: by.map(String::from),
+            host: host.map(String::from),
+            proto: proto.map(String::from)
+        };
+        let tests = [
+            ("for=\"_gazonk\"", vec![element(Some("_gazonk"), None, None, None)]),
+            ("For=\"[2001:db8:cafe::17]:4711\"", vec![element(Some("[2001:db8:cafe::17]:4711"), None, None, None)]),
+            ("for=192.0.2.60;proto=http;by=203.0.113.43", vec![element(Some("192.0.2.60"), Some("203.0.113.43"), None, Some("http"))]),
+            ("for=192.0.2.43, for=198.51.100.17", vec![element(Some("192.0.2.43"), None, None, None), element(Some("198.51.100.17"), None, None, None)]),
+            (
+                "for=192.0.2.43,for=\"[2001:db8:cafe::17]\",for=unknown",
+                vec![element(Some("192.0.2.43"), None, None, None), element(Some("[2001:db8:cafe::17]"), None, None, None), element(Some("unknown"), None, None, None)]
+            ),
+            (
+                "for=_hidden; by=\"_o\\\"bf,1\" ;HOST=example.com;proto=https;for=192.0.2.1;ext=1",
+                vec![element(Some("_hidden"), Some("_o\"bf,1"), Some("example.com"), Some("https"))]
+            ),
+            ("", vec![])
+        ];
+        
+        for &(value, ref expected) in tests.iter() {
+            assert_eq!(
+                parse(value),
+                *expected,
+                "Test forwarded::parse-1 failed for `{}`.", value
+            );
+        }
+    }
+    #[test]
+    fn test_client_ip() {
+        let proxies: [IpAddr; 2] = ["10.0.0.1".parse().unwrap(), "2001:db8::2".parse().unwrap()];
+        let tests = [
+            ("X-Forwarded-For: 203.0.113.7\r\n", Some("203.0.113.7")),
+            ("X-Forwarded-For: 198.51.100.1, 203.0.113.7 , 10.0.0.1\r\n", Some("203.0.113.7")),
+            ("X-Forwarded-For: 198.51.100.1\r\nX-Forwarded-For: 203.0.113.7:54321, [2001:db8::2]:443\r\n", Some("203.0.113.7")),
+            ("X-Forwarded-For: 203.0.113.7, [2001:db8::1], unknown, 2001:db8::2\r\n", Some("2001:db8::1")),
+            ("X-Forwarded-For: 203.0.113.7, [2001:db8::1]:80\r\n", Some("2001:db8::1")),
+            ("X-Forwarded-For: 10.0.0.1, 2001:db8::2\r\n", Some("10.0.0.1")),
+            ("X-Forwarded-For: unknown, 203.0.113.7:http, [::1]x\r\n", None),
+            ("", None)
+        ];
+        
+        for &(fields, expected) in tests.iter() {
+            assert_eq!(
+                client_ip(&request(fields), &proxies),
+                expected.map(|address| address.parse().unwrap()),
+                "Test forwarded::client_ip-1 failed for `{}`.", fields.escape_debug()
+            );
+        }
+        
+        let forwarded = request("Forwarded: for=198.51.100.1, for=\"[2001:db8:cafe::17]:4711\";proto=https, for=_hidden, for=10.0.0.1\r\nX-Forwarded-For: 203.0.113.7\r\n");
+        assert_eq!(
+            client_ip(&forwarded, &proxies),
+            Some("2001:db8:cafe::17".parse().unwrap()),
+            "Test forwarded::client_ip-2 failed."
+        );
+        
+        // A client can prepend anything to the list, only the trusted proxies are believed.
+        let spoofed = request("X-Forwarded-For: 127.0.0.1, 203.0.113.7, 10.0.0.1\r\n");
+        assert_eq!(
+            (client_ip(&spoofed, &proxies), client_ip(&spoofed, &[])),
+            (Some("203.0.113.7".parse().unwrap()), Some("10.0.0.1".parse().unwrap())),
+            "Test forwarded::client_ip-3 failed."
+        );
+    }
+    #[test]
+    fn test_original_scheme() {
+        let tests = [
+            ("X-Forwarded-Proto: https\r\n", Some("https")),
+            ("x-forwarded-proto: HTTPS , http\r\n", Some("HTTPS")),
+            ("X-Forwarded-Proto: \r\n", None),
+            ("X-Forwarded-Proto: 1http\r\n", None),
+            ("X-Forwarded-Proto: ht tp\r\n", None),
+            ("", None)
+        ];
+        
+        for &(fields, expected) in tests.iter() {
+            assert_eq!(
+                original_scheme(&request(fields)),
+                expected,
+                "Test forwarded::original_scheme-1 failed for `{}`.", fields.escape_debug()
+            );
+        }
+    }
+}
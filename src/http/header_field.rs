@@ -6,7 +6,11 @@
 //! Date --- 06/09/2017
 
 use std::string::String;
-use super::{HTTP, ErrorToHTTP};
+use std::fmt;
+use std::str::FromStr;
+use super::{HTTP, ErrorToHTTP, ParseError, BuildError, ParseMode};
+use super::token;
+use super::headers;
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 /// A `HeaderField` defines a `name:value` association in the header section of a HTTP message.
@@ -18,29 +22,114 @@ pub struct HeaderField {
 }
 
 impl HeaderField {
-    /// Converts the passed `str` into a `HeaderField`.
+    /// Returns a new `HeaderField` with the passed name and value.
+    ///
+    /// Fails if the name is not a token or the value contains a CR, LF or NUL, any of
+    /// which would let the value inject extra Header fields or a body into the message.
+    ///
+    /// # Params
+    ///
+    /// name --- The name of the `HeaderField`.</br>
+    /// value --- The value of the `HeaderField`.
+    pub fn new(name: &str, value: &str) -> Result<HeaderField, BuildError> {
+        let field = HeaderField {
+            name: String::from(name),
+            value: String::from(value)
+        };
+        
+        field.validate()?;
+        Ok(field)
+    }
+    /// Returns `true` if the name of the `HeaderField` is the passed name, ignoring case.
+    ///
+    /// # Params
+    ///
+    /// name --- The name to compare with.
+    pub fn name_eq(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+    }
+    /// Returns the name of the `HeaderField` in its canonical casing.
+    ///
+    /// The names in `headers::NAMES` use the casing of their RFC, e.g. `ETag` or
+    /// `Content-MD5`, any other name has the first letter of each `-` separated word
+    /// uppercase and the rest lowercase.
+    pub fn canonical_name(&self) -> String {
+        if let Some(name) = headers::NAMES.iter().find(|name| self.name_eq(name)) {
+            return String::from(*name);
+        }
+        
+        self.name.split('-')
+            .map(|word| {
+                let mut chars = word.chars();
+                
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+                    None => String::new()
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("-")
+    }
+    /// Fails if the name is not a token or the value contains a CR, LF or NUL.
+    pub fn validate(&self) -> Result<(), BuildError> {
+        if !token::is_token(&self.name) {
+            Err(BuildError::InvalidHeaderName(self.name.clone()))
+        } else if self.value.contains(&['\r', '\n', '\0'][..]) {
+            Err(BuildError::InvalidHeaderValue(self.value.clone()))
+        } else {
+            Ok(())
+        }
+    }
+    /// Converts the passed `str` into a `HeaderField`, failing with `WhitespaceBeforeColon`
+    /// if there is whitespace between the name and the colon.
+    ///
+    /// [Read more](#method.from_with_mode)
     ///
     /// # Params
     ///
     /// msg --- The `str` to convert.
-    pub fn from(msg: &str) -> Result<HeaderField, String> {
+    pub fn from(msg: &str) -> Result<HeaderField, ParseError> {
+        msg.parse()
+    }
+    /// Converts the passed `str` into a `HeaderField`.
+    ///
+    /// The name must be a token, the value is trimmed and may not contain a CR or LF.
+    /// Whitespace between the name and the colon is dropped unless `mode` is
+    /// `ParseMode::Strict`, as each server a request passes through could read it
+    /// differently.</br>
+    /// Fails with `IllegalCharacter` if the line contains a control character other than a
+    /// horizontal tab, e.g. a NUL or a bare CR, and with `WhitespaceBeforeColon` if a
+    /// `Strict` parse finds whitespace before the colon.
+    ///
+    /// # Params
+    ///
+    /// msg --- The `str` to convert.</br>
+    /// mode --- How strictly the field is parsed.
+    pub fn from_with_mode(msg: &str, mode: ParseMode) -> Result<HeaderField, ParseError> {
+        token::check_line_controls(msg.as_bytes(), mode)?;
         // Split the string on the colon.
         let parts: Vec<&str> = msg.split(":").collect();
+        // Whitespace between the name and the colon is not part of the name.
+        let name = parts[0].trim_end_matches(&[' ', '\t'][..]);
         
-        // Make sure the split worked properly.
-        if parts.len() >= 2 {
+        if parts.len() >= 2 && name.len() < parts[0].len() && token::is_token(name) && mode == ParseMode::Strict {
+            return Err(ParseError::WhitespaceBeforeColon(String::from(msg)));
+        }
+        // Make sure the split worked properly, the name is a token and the value has no
+        // line terminators hidden in it.
+        if parts.len() >= 2 && token::is_token(name) && !msg.contains(&['\r', '\n'][..]) {
             // Return the HeaderField.
             Ok(
                 HeaderField {
                     // The first part is the name of the field.
-                    name: String::from(parts[0].trim()),
+                    name: String::from(name),
                     // All the remaining parts make up the value string.
                     value: String::from(parts.iter().skip(2).fold(
                         // The second part has no colon at the front.
                         String::from(parts[1]),
                         // All other parts have a colon at the front.
                         |mut res, s| {
-                            res.push_str(":");
+                            res.push(':');
                             res.push_str(s);
                             res
                         }
@@ -48,15 +137,54 @@ impl HeaderField {
                 }
             )
         } else {
-            // The split was not successful and it is a bad header field.
-            Err(format!("Bad Header Field: `{}`", msg))
+            // The split was not successful or the name is bad and it is a bad header field.
+            Err(ParseError::MalformedHeader(String::from(msg)))
         }
     }
 }
 
+impl FromStr for HeaderField {
+    type Err = ParseError;
+    
+    /// Converts the passed `str` into a `HeaderField`, see `HeaderField::from`.
+    ///
+    /// # Params
+    ///
+    /// msg --- The `str` to convert.
+    fn from_str(msg: &str) -> Result<HeaderField, ParseError> {
+        // RFC 7230 section 3.2.4 requires a server to reject whitespace before the colon.
+        HeaderField::from_with_mode(msg, ParseMode::Strict)
+    }
+}
+
+impl fmt::Display for HeaderField {
+    /// Writes the `HeaderField` as `name: value` with no line terminator, the name as it
+    /// was received.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.value)
+    }
+}
+
 impl HTTP for HeaderField {
+    /// Converts the `HeaderField` into `name: value` with no line terminator, the name in
+    /// its `canonical_name` casing however it was received.
+    fn to_http(&self) -> Result<String, ErrorToHTTP> {
+        Ok(format!("{}: {}", self.canonical_name(), self.value))
+    }
+}
+
+impl HTTP for [HeaderField] {
+    /// Converts the `HeaderField`s into a header block with each field terminated by `\r\n`.
+    ///
+    /// An empty slice converts to an empty string.
     fn to_http(&self) -> Result<String, ErrorToHTTP> {
-        Ok(format!("{}:{}", self.name, self.value))
+        let mut res = String::new();
+        
+        for field in self.iter() {
+            res.push_str(&field.to_http()?);
+            res.push_str("\r\n");
+        }
+        Ok(res)
     }
 }
 
@@ -76,7 +204,7 @@ mod tests {
         );
         
         assert_eq!(
-            HeaderField::from(" header1:field1 ").unwrap(),
+            HeaderField::from("header1: field1 ").unwrap(),
             HeaderField {
                 name: String::from("header1"),
                 value: String::from("field1")
@@ -85,18 +213,209 @@ mod tests {
         );
         
         assert_eq!(
-            HeaderField::from(" header1 : field1 ").unwrap(),
-            HeaderField {
-                name: String::from("header1"),
-                value: String::from("field1")
-            },
+            (HeaderField::from_with_mode("header1 : field1", ParseMode::Lenient), HeaderField::from("header1 : field1")),
+            (
+                Ok(HeaderField { name: String::from("header1"), value: String::from("field1") }),
+                Err(ParseError::WhitespaceBeforeColon(String::from("header1 : field1")))
+            ),
             "Test HeaderField::from-3 failed."
         );
         
         assert_eq!(
-            HeaderField::from(" header1 : field1 ").unwrap().to_http().unwrap(),
-            "header1:field1",
+            HeaderField::from("header1:   field1 ").unwrap().to_http().unwrap(),
+            "Header1: field1",
             "Test HeaderField::from-4 failed."
         );
+        
+        assert_eq!(
+            HeaderField::from("Host: localhost:8080").unwrap().to_http().unwrap(),
+            "Host: localhost:8080",
+            "Test HeaderField::from-5 failed."
+        );
+        
+        assert_eq!(
+            HeaderField::from("no colon here"),
+            Err(ParseError::MalformedHeader(String::from("no colon here"))),
+            "Test HeaderField::from-6 failed."
+        );
+        
+        assert_eq!(
+            HeaderField::from(": field1"),
+            Err(ParseError::MalformedHeader(String::from(": field1"))),
+            "Test HeaderField::from-7 failed."
+        );
+        
+        assert_eq!(
+            HeaderField::from("X Header: field1"),
+            Err(ParseError::MalformedHeader(String::from("X Header: field1"))),
+            "Test HeaderField::from-8 failed."
+        );
+        
+        assert_eq!(
+            HeaderField::from("X-\u{0}Header: field1"),
+            Err(ParseError::IllegalCharacter { position: 2 }),
+            "Test HeaderField::from-9 failed."
+        );
+        
+        assert_eq!(
+            HeaderField::from("Location: x\rSet-Cookie: evil"),
+            Err(ParseError::IllegalCharacter { position: 11 }),
+            "Test HeaderField::from-10 failed."
+        );
+        
+        assert_eq!(
+            HeaderField::from("Location: x\nSet-Cookie: evil"),
+            Err(ParseError::IllegalCharacter { position: 11 }),
+            "Test HeaderField::from-11 failed."
+        );
+    }
+    #[test]
+    fn test_header_field_whitespace() {
+        let accepted = ["header1:field1", "header1: field1", "header1:\tfield1\t", "header1:  field1  "];
+        for &msg in accepted.iter() {
+            assert_eq!(
+                HeaderField::from(msg),
+                Ok(HeaderField { name: String::from("header1"), value: String::from("field1") }),
+                "Test HeaderField::from whitespace failed for `{:?}`.", msg
+            );
+        }
+        
+        let rejected = [
+            ("header1 :field1", ParseError::WhitespaceBeforeColon(String::from("header1 :field1"))),
+            ("header1\t: field1", ParseError::WhitespaceBeforeColon(String::from("header1\t: field1"))),
+            ("header1  : field1", ParseError::WhitespaceBeforeColon(String::from("header1  : field1"))),
+            (" header1: field1", ParseError::MalformedHeader(String::from(" header1: field1")))
+        ];
+        for &(msg, ref error) in rejected.iter() {
+            assert_eq!(
+                HeaderField::from(msg).as_ref(),
+                Err(error),
+                "Test HeaderField::from whitespace failed for `{:?}`.", msg
+            );
+        }
+    }
+    #[test]
+    fn test_header_field_new() {
+        assert_eq!(
+            HeaderField::new("Location", "/home").unwrap(),
+            HeaderField {
+                name: String::from("Location"),
+                value: String::from("/home")
+            },
+            "Test HeaderField::new-1 failed."
+        );
+        
+        assert_eq!(
+            HeaderField::new("Location", "x\r\nSet-Cookie: evil"),
+            Err(BuildError::InvalidHeaderValue(String::from("x\r\nSet-Cookie: evil"))),
+            "Test HeaderField::new-2 failed."
+        );
+        
+        assert_eq!(
+            HeaderField::new("Location", "x\0"),
+            Err(BuildError::InvalidHeaderValue(String::from("x\0"))),
+            "Test HeaderField::new-3 failed."
+        );
+        
+        assert_eq!(
+            HeaderField::new("Set-Cookie:", "evil"),
+            Err(BuildError::InvalidHeaderName(String::from("Set-Cookie:"))),
+            "Test HeaderField::new-4 failed."
+        );
+    }
+    #[test]
+    fn test_header_block() {
+        let fields = [
+            HeaderField::from("Host: localhost:8080").unwrap(),
+            HeaderField::from("Content-Type: text/html").unwrap(),
+            HeaderField::from("X-Time: 12:30:59").unwrap()
+        ];
+        
+        assert_eq!(
+            fields[..].to_http().unwrap(),
+            "Host: localhost:8080\r\nContent-Type: text/html\r\nX-Time: 12:30:59\r\n",
+            "Test HeaderField block-1 failed."
+        );
+        
+        assert_eq!(
+            fields[..0].to_http().unwrap(),
+            "",
+            "Test HeaderField block-2 failed."
+        );
+    }
+    #[test]
+    fn test_header_field_name() {
+        let field = HeaderField::from("content-TYPE: text/html").unwrap();
+        
+        assert!(
+            field.name_eq("Content-Type") && !field.name_eq("Content-Length"),
+            "Test HeaderField::name_eq-1 failed."
+        );
+        assert_eq!(
+            field.canonical_name(),
+            "Content-Type",
+            "Test HeaderField::canonical_name-1 failed."
+        );
+        assert_eq!(
+            field.to_string(),
+            "content-TYPE: text/html",
+            "Test HeaderField::canonical_name-2 failed."
+        );
+        assert_eq!(
+            HeaderField::from("etag: \"1\"").unwrap().canonical_name(),
+            "ETag",
+            "Test HeaderField::canonical_name-3 failed."
+        );
+        assert_eq!(
+            HeaderField::from("www-authenticate: Basic").unwrap().canonical_name(),
+            "WWW-Authenticate",
+            "Test HeaderField::canonical_name-4 failed."
+        );
+        assert_eq!(
+            HeaderField::from("x-REQUEST-id: 1").unwrap().canonical_name(),
+            "X-Request-Id",
+            "Test HeaderField::canonical_name-5 failed."
+        );
+        assert_eq!(
+            HeaderField::from("content-md5: Q2hlY2sgSW50ZWdyaXR5IQ==").unwrap().canonical_name(),
+            "Content-MD5",
+            "Test HeaderField::canonical_name-6 failed."
+        );
+    }
+    #[test]
+    fn test_header_field_from_str() {
+        assert_eq!(
+            "Host: localhost".parse::<HeaderField>(),
+            Ok(HeaderField {
+                name: String::from("Host"),
+                value: String::from("localhost")
+            }),
+            "Test HeaderField::from_str-1 failed."
+        );
+        assert_eq!(
+            "Host localhost".parse::<HeaderField>(),
+            Err(ParseError::MalformedHeader(String::from("Host localhost"))),
+            "Test HeaderField::from_str-2 failed."
+        );
+        for &(field, position) in [("X-Note: a\rb", 9), ("X-Note: \0", 8), ("X-Note: a\x1Bb", 9), ("X\x7F: a", 1)].iter() {
+            assert_eq!(
+                field.parse::<HeaderField>(),
+                Err(ParseError::IllegalCharacter { position }),
+                "Test HeaderField::from_str-3 failed for `{:?}`.", field
+            );
+        }
+    }
+    #[test]
+    fn test_header_field_display() {
+        assert_eq!(
+            format!("{}", HeaderField::from("Content-Type:text/html ").unwrap()),
+            "Content-Type: text/html",
+            "Test HeaderField display-1 failed."
+        );
+        assert_eq!(
+            HeaderField::from("Host: localhost:8080").unwrap().to_string(),
+            "Host: localhost:8080",
+            "Test HeaderField display-2 failed."
+        );
     }
 }
@@ -0,0 +1,261 @@
+//! `header_map` is a module to handle the indexed storage of the Header Fields of a HTTP message.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::string::String;
+use std::slice;
+use std::collections::HashMap;
+use super::BuildError;
+use super::header_field::HeaderField;
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+/// A `HeaderMap` stores the `HeaderField`s of a message in the order they were added
+/// while indexing them by name, ignoring case.
+///
+/// Looking up a name in a `Vec<HeaderField>` compares it against every field, for a
+/// typical request of ~30 fields that is ~30 case-insensitive string comparisons per
+/// lookup. A `HeaderMap` hashes the lowercase name once and goes straight to the
+/// matching fields, so lookups take the same time however many fields there are.
+pub struct HeaderMap {
+    /// The `HeaderField`s in the order they were added.
+    fields: Vec<HeaderField>,
+    /// The indices into `fields` of each lowercase name, in order.
+    index: HashMap<String, Vec<usize>>
+}
+
+impl HeaderMap {
+    /// Returns a new, empty `HeaderMap`.
+    pub fn new() -> HeaderMap {
+        HeaderMap::default()
+    }
+    /// Returns the value of the first `HeaderField` with the passed name, ignoring case.
+    ///
+    /// # Params
+    ///
+    /// name --- The name of the `HeaderField` to find.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.get_all(name).next()
+    }
+    /// Returns the values of every `HeaderField` with the passed name, ignoring case, in order.
+    ///
+    /// # Params
+    ///
+    /// name --- The name of the `HeaderField`s to find.
+    pub fn get_all<'a>(&'a self, name: &str) -> impl Iterator<Item = &'a str> + 'a {
+        self.index.get(&name.to_ascii_lowercase())
+            .map(|indices| indices.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(move |&index| self.fields[index].value.as_str())
+    }
+    /// Sets the value of the `HeaderField` with the passed name, ignoring case.
+    ///
+    /// The first matching field is updated in place and any other matching fields are
+    /// removed. If there is no matching field a new one is appended.</br>
+    /// Fails, leaving the map unchanged, if the name is not a token or the value
+    /// contains a CR, LF or NUL.
+    ///
+    /// # Params
+    ///
+    /// name --- The name of the `HeaderField` to set.</br>
+    /// value --- The new value of the `HeaderField`.
+    pub fn insert(&mut self, name: &str, value: &str) -> Result<(), BuildError> {
+        let field = HeaderField::new(name, value)?;
+        
+        match self.index.get(&name.to_ascii_lowercase()).map(|indices| indices[0]) {
+            Some(index) => {
+                // No field before `index` matches so removing them all leaves `index` in place.
+                self.remove(name);
+                self.fields.insert(index, field);
+                self.reindex();
+            },
+            None => self.push(field)
+        }
+        Ok(())
+    }
+    /// Appends a new `HeaderField`, regardless of any existing fields with the same name.
+    ///
+    /// Fails if the name is not a token or the value contains a CR, LF or NUL.
+    ///
+    /// # Params
+    ///
+    /// name --- The name of the new `HeaderField`.</br>
+    /// value --- The value of the new `HeaderField`.
+    pub fn append(&mut self, name: &str, value: &str) -> Result<(), BuildError> {
+        self.push(HeaderField::new(name, value)?);
+        Ok(())
+    }
+    /// Removes every `HeaderField` with the passed name, ignoring case.</br>
+    /// Returns the number of fields which were removed.
+    ///
+    /// # Params
+    ///
+    /// name --- The name of the `HeaderField`s to remove.
+    pub fn remove(&mut self, name: &str) -> usize {
+        let len = self.fields.len();
+        
+        if self.index.remove(&name.to_ascii_lowercase()).is_some() {
+            self.fields.retain(|field| !field.name_eq(name));
+            self.reindex();
+        }
+        len - self.fields.len()
+    }
+    /// Returns the number of `HeaderField`s in the map, counting every value.
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+    /// Returns `true` if there are no `HeaderField`s in the map.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+    /// Returns an iterator over the `HeaderField`s in the order they were added.
+    pub fn iter(&self) -> slice::Iter<'_, HeaderField> {
+        self.fields.iter()
+    }
+    /// Appends a `HeaderField` and indexes it.
+    ///
+    /// # Params
+    ///
+    /// field --- The `HeaderField` to append.
+    fn push(&mut self, field: HeaderField) {
+        self.index.entry(field.name.to_ascii_lowercase())
+            .or_default()
+            .push(self.fields.len());
+        self.fields.push(field);
+    }
+    /// Rebuilds the index after fields have been moved.
+    fn reindex(&mut self) {
+        self.index.clear();
+        for (i, field) in self.fields.iter().enumerate() {
+            self.index.entry(field.name.to_ascii_lowercase())
+                .or_default()
+                .push(i);
+        }
+    }
+}
+
+impl From<Vec<HeaderField>> for HeaderMap {
+    /// Returns a `HeaderMap` of the passed `HeaderField`s, in the same order.
+    fn from(fields: Vec<HeaderField>) -> HeaderMap {
+        let mut res = HeaderMap { fields, index: HashMap::new() };
+        
+        res.reindex();
+        res
+    }
+}
+
+impl From<HeaderMap> for Vec<HeaderField> {
+    /// Returns the `HeaderField`s of the `HeaderMap` in order.
+    fn from(map: HeaderMap) -> Vec<HeaderField> {
+        map.fields
+    }
+}
+
+impl<'a> IntoIterator for &'a HeaderMap {
+    type Item = &'a HeaderField;
+    type IntoIter = slice::Iter<'a, HeaderField>;
+    
+    fn into_iter(self) -> slice::Iter<'a, HeaderField> {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_map() {
+        let mut map = HeaderMap::new();
+        
+        map.append("Vary", "Accept").unwrap();
+        map.append("Server", "web_server").unwrap();
+        map.append("vary", "Cookie").unwrap();
+        assert_eq!(
+            map.get("VARY"),
+            Some("Accept"),
+            "Test HeaderMap::get-1 failed."
+        );
+        assert_eq!(
+            map.get_all("Vary").collect::<Vec<&str>>(),
+            vec!["Accept", "Cookie"],
+            "Test HeaderMap::get_all-1 failed."
+        );
+        assert_eq!(
+            map.get("Missing"),
+            None,
+            "Test HeaderMap::get-2 failed."
+        );
+        assert_eq!(
+            map.len(),
+            3,
+            "Test HeaderMap::len-1 failed."
+        );
+        
+        map.insert("VARY", "Origin").unwrap();
+        map.insert("Content-Type", "text/plain").unwrap();
+        assert_eq!(
+            map.iter().map(|field| field.to_string()).collect::<Vec<String>>(),
+            vec!["VARY: Origin", "Server: web_server", "Content-Type: text/plain"],
+            "Test HeaderMap::insert-1 failed."
+        );
+        assert_eq!(
+            map.get("server"),
+            Some("web_server"),
+            "Test HeaderMap::insert-2 failed."
+        );
+        assert_eq!(
+            map.insert("Location", "x\r\nSet-Cookie: evil"),
+            Err(BuildError::InvalidHeaderValue(String::from("x\r\nSet-Cookie: evil"))),
+            "Test HeaderMap::insert-3 failed."
+        );
+        
+        assert_eq!(
+            map.remove("vary"),
+            1,
+            "Test HeaderMap::remove-1 failed."
+        );
+        assert_eq!(
+            (map.get("Vary"), map.get("Content-Type"), map.len()),
+            (None, Some("text/plain"), 2),
+            "Test HeaderMap::remove-2 failed."
+        );
+        assert_eq!(
+            map.remove("Vary"),
+            0,
+            "Test HeaderMap::remove-3 failed."
+        );
+    }
+    #[test]
+    fn test_header_map_from() {
+        let fields = vec![
+            HeaderField::from("Set-Cookie: a=1").unwrap(),
+            HeaderField::from("Host: localhost").unwrap(),
+            HeaderField::from("set-cookie: b=2").unwrap()
+        ];
+        let map = HeaderMap::from(fields.clone());
+        
+        assert_eq!(
+            map.get_all("SET-COOKIE").collect::<Vec<&str>>(),
+            vec!["a=1", "b=2"],
+            "Test HeaderMap::from-1 failed."
+        );
+        assert_eq!(
+            (&map).into_iter().cloned().collect::<Vec<HeaderField>>(),
+            fields,
+            "Test HeaderMap::from-2 failed."
+        );
+        assert_eq!(
+            Vec::from(map),
+            fields,
+            "Test HeaderMap::from-3 failed."
+        );
+        assert!(
+            HeaderMap::from(Vec::new()).is_empty(),
+            "Test HeaderMap::from-4 failed."
+        );
+    }
+}
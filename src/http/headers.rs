@@ -0,0 +1,181 @@
+//! `headers` is a module of constants for the names of well-known Header Fields.
+//!
+//! Header Field names are compared ignoring case, the constants use the casing of the
+//! RFCs which define them.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+/// The `Accept` Header Field.
+pub const ACCEPT: &str = "Accept";
+/// The `Accept-Charset` Header Field.
+pub const ACCEPT_CHARSET: &str = "Accept-Charset";
+/// The `Accept-Encoding` Header Field.
+pub const ACCEPT_ENCODING: &str = "Accept-Encoding";
+/// The `Accept-Language` Header Field.
+pub const ACCEPT_LANGUAGE: &str = "Accept-Language";
+/// The `Accept-Ranges` Header Field.
+pub const ACCEPT_RANGES: &str = "Accept-Ranges";
+/// The `Access-Control-Allow-Credentials` Header Field.
+pub const ACCESS_CONTROL_ALLOW_CREDENTIALS: &str = "Access-Control-Allow-Credentials";
+/// The `Access-Control-Allow-Headers` Header Field.
+pub const ACCESS_CONTROL_ALLOW_HEADERS: &str = "Access-Control-Allow-Headers";
+/// The `Access-Control-Allow-Methods` Header Field.
+pub const ACCESS_CONTROL_ALLOW_METHODS: &str = "Access-Control-Allow-Methods";
+/// The `Access-Control-Allow-Origin` Header Field.
+pub const ACCESS_CONTROL_ALLOW_ORIGIN: &str = "Access-Control-Allow-Origin";
+/// The `Access-Control-Expose-Headers` Header Field.
+pub const ACCESS_CONTROL_EXPOSE_HEADERS: &str = "Access-Control-Expose-Headers";
+/// The `Access-Control-Max-Age` Header Field.
+pub const ACCESS_CONTROL_MAX_AGE: &str = "Access-Control-Max-Age";
+/// The `Access-Control-Request-Headers` Header Field.
+pub const ACCESS_CONTROL_REQUEST_HEADERS: &str = "Access-Control-Request-Headers";
+/// The `Access-Control-Request-Method` Header Field.
+pub const ACCESS_CONTROL_REQUEST_METHOD: &str = "Access-Control-Request-Method";
+/// The `Age` Header Field.
+pub const AGE: &str = "Age";
+/// The `Allow` Header Field.
+pub const ALLOW: &str = "Allow";
+/// The `Authorization` Header Field.
+pub const AUTHORIZATION: &str = "Authorization";
+/// The `Cache-Control` Header Field.
+pub const CACHE_CONTROL: &str = "Cache-Control";
+/// The `Connection` Header Field.
+pub const CONNECTION: &str = "Connection";
+/// The `Content-Disposition` Header Field.
+pub const CONTENT_DISPOSITION: &str = "Content-Disposition";
+/// The `Content-Encoding` Header Field.
+pub const CONTENT_ENCODING: &str = "Content-Encoding";
+/// The `Content-Language` Header Field.
+pub const CONTENT_LANGUAGE: &str = "Content-Language";
+/// The `Content-Length` Header Field.
+pub const CONTENT_LENGTH: &str = "Content-Length";
+/// The `Content-Location` Header Field.
+pub const CONTENT_LOCATION: &str = "Content-Location";
+/// The `Content-MD5` Header Field.
+pub const CONTENT_MD5: &str = "Content-MD5";
+/// The `Content-Range` Header Field.
+pub const CONTENT_RANGE: &str = "Content-Range";
+/// The `Content-Type` Header Field.
+pub const CONTENT_TYPE: &str = "Content-Type";
+/// The `Cookie` Header Field.
+pub const COOKIE: &str = "Cookie";
+/// The `Date` Header Field.
+pub const DATE: &str = "Date";
+/// The `DNT` Header Field.
+pub const DNT: &str = "DNT";
+/// The `ETag` Header Field.
+pub const ETAG: &str = "ETag";
+/// The `Expect` Header Field.
+pub const EXPECT: &str = "Expect";
+/// The `Expires` Header Field.
+pub const EXPIRES: &str = "Expires";
+/// The `Forwarded` Header Field.
+pub const FORWARDED: &str = "Forwarded";
+/// The `Host` Header Field.
+pub const HOST: &str = "Host";
+/// The `If-Match` Header Field.
+pub const IF_MATCH: &str = "If-Match";
+/// The `If-Modified-Since` Header Field.
+pub const IF_MODIFIED_SINCE: &str = "If-Modified-Since";
+/// The `If-None-Match` Header Field.
+pub const IF_NONE_MATCH: &str = "If-None-Match";
+/// The `If-Range` Header Field.
+pub const IF_RANGE: &str = "If-Range";
+/// The `If-Unmodified-Since` Header Field.
+pub const IF_UNMODIFIED_SINCE: &str = "If-Unmodified-Since";
+/// The `Last-Modified` Header Field.
+pub const LAST_MODIFIED: &str = "Last-Modified";
+/// The `Location` Header Field.
+pub const LOCATION: &str = "Location";
+/// The `Max-Forwards` Header Field.
+pub const MAX_FORWARDS: &str = "Max-Forwards";
+/// The `Origin` Header Field.
+pub const ORIGIN: &str = "Origin";
+/// The `Pragma` Header Field.
+pub const PRAGMA: &str = "Pragma";
+/// The `Proxy-Authenticate` Header Field.
+pub const PROXY_AUTHENTICATE: &str = "Proxy-Authenticate";
+/// The `Proxy-Authorization` Header Field.
+pub const PROXY_AUTHORIZATION: &str = "Proxy-Authorization";
+/// The `Range` Header Field.
+pub const RANGE: &str = "Range";
+/// The `Referer` Header Field.
+pub const REFERER: &str = "Referer";
+/// The `Retry-After` Header Field.
+pub const RETRY_AFTER: &str = "Retry-After";
+/// The `Sec-WebSocket-Accept` Header Field.
+pub const SEC_WEBSOCKET_ACCEPT: &str = "Sec-WebSocket-Accept";
+/// The `Sec-WebSocket-Extensions` Header Field.
+pub const SEC_WEBSOCKET_EXTENSIONS: &str = "Sec-WebSocket-Extensions";
+/// The `Sec-WebSocket-Key` Header Field.
+pub const SEC_WEBSOCKET_KEY: &str = "Sec-WebSocket-Key";
+/// The `Sec-WebSocket-Protocol` Header Field.
+pub const SEC_WEBSOCKET_PROTOCOL: &str = "Sec-WebSocket-Protocol";
+/// The `Sec-WebSocket-Version` Header Field.
+pub const SEC_WEBSOCKET_VERSION: &str = "Sec-WebSocket-Version";
+/// The `Server` Header Field.
+pub const SERVER: &str = "Server";
+/// The `Set-Cookie` Header Field.
+pub const SET_COOKIE: &str = "Set-Cookie";
+/// The `TE` Header Field.
+pub const TE: &str = "TE";
+/// The `Trailer` Header Field.
+pub const TRAILER: &str = "Trailer";
+/// The `Transfer-Encoding` Header Field.
+pub const TRANSFER_ENCODING: &str = "Transfer-Encoding";
+/// The `Upgrade` Header Field.
+pub const UPGRADE: &str = "Upgrade";
+/// The `User-Agent` Header Field.
+pub const USER_AGENT: &str = "User-Agent";
+/// The `Vary` Header Field.
+pub const VARY: &str = "Vary";
+/// The `Via` Header Field.
+pub const VIA: &str = "Via";
+/// The `Warning` Header Field.
+pub const WARNING: &str = "Warning";
+/// The `WWW-Authenticate` Header Field.
+pub const WWW_AUTHENTICATE: &str = "WWW-Authenticate";
+/// The `X-Forwarded-For` Header Field, the de facto standard for the addresses a request
+/// was forwarded from.
+pub const X_FORWARDED_FOR: &str = "X-Forwarded-For";
+/// The `X-Forwarded-Proto` Header Field, the de facto standard for the scheme a request
+/// was originally sent with.
+pub const X_FORWARDED_PROTO: &str = "X-Forwarded-Proto";
+/// The `X-XSS-Protection` Header Field, the de facto standard for enabling a browser's
+/// cross-site scripting filter.
+pub const X_XSS_PROTECTION: &str = "X-XSS-Protection";
+
+/// Every Header Field name defined in this module.
+pub static NAMES: [&str; 68] = [
+    ACCEPT, ACCEPT_CHARSET, ACCEPT_ENCODING, ACCEPT_LANGUAGE, ACCEPT_RANGES,
+    ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS,
+    ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_MAX_AGE,
+    ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD, AGE, ALLOW,
+    AUTHORIZATION, CACHE_CONTROL, CONNECTION, CONTENT_DISPOSITION, CONTENT_ENCODING,
+    CONTENT_LANGUAGE, CONTENT_LENGTH, CONTENT_LOCATION, CONTENT_MD5, CONTENT_RANGE, CONTENT_TYPE,
+    COOKIE, DATE, DNT, ETAG, EXPECT, EXPIRES, FORWARDED, HOST, IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH,
+    IF_RANGE, IF_UNMODIFIED_SINCE, LAST_MODIFIED, LOCATION, MAX_FORWARDS, ORIGIN, PRAGMA,
+    PROXY_AUTHENTICATE, PROXY_AUTHORIZATION, RANGE, REFERER, RETRY_AFTER, SEC_WEBSOCKET_ACCEPT,
+    SEC_WEBSOCKET_EXTENSIONS, SEC_WEBSOCKET_KEY, SEC_WEBSOCKET_PROTOCOL, SEC_WEBSOCKET_VERSION, SERVER,
+    SET_COOKIE, TE, TRAILER, TRANSFER_ENCODING, UPGRADE, USER_AGENT, VARY, VIA, WARNING,
+    WWW_AUTHENTICATE, X_FORWARDED_FOR, X_FORWARDED_PROTO, X_XSS_PROTECTION
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::header_field::HeaderField;
+
+    #[test]
+    fn test_headers() {
+        for name in NAMES.iter() {
+            assert!(
+                HeaderField::new(name, "value").is_ok(),
+                "Test headers-1 failed for `{}`.", name
+            );
+        }
+    }
+}
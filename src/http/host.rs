@@ -0,0 +1,115 @@
+//! `host` is a module to handle the `Host` Header Field and the authority of request targets.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::string::String;
+use super::ParseError;
+
+/// Converts the value of a `Host` Header Field to its lowercase host and optional port.
+///
+/// An IPv6 literal keeps its brackets, e.g. `[::1]`. An empty port, as in `host:`, is
+/// the same as no port.</br>
+/// Fails if the value is empty, contains whitespace or other characters which cannot
+/// appear in a host, has more than one colon outside of brackets, or has a port which
+/// is not a number from 0 to 65535.
+///
+/// # Params
+///
+/// value --- The value of the `Host` Header Field.
+pub fn parse(value: &str) -> Result<(String, Option<u16>), ParseError> {
+    let invalid = || ParseError::InvalidHost(String::from(value));
+    
+    let (host, port) = if value.starts_with('[') {
+        // An IPv6 literal contains colons so the port follows the closing bracket.
+        let end = value.find(']').ok_or_else(invalid)? + 1;
+        let literal = &value[1..end - 1];
+        if literal.is_empty() || !literal.bytes().all(|b| b.is_ascii_hexdigit() || b == b':' || b == b'.') {
+            return Err(invalid());
+        }
+        
+        match &value[end..] {
+            "" => (&value[..end], None),
+            rest if rest.starts_with(':') => (&value[..end], Some(&rest[1..])),
+            _ => return Err(invalid())
+        }
+    } else {
+        let mut parts = value.split(':');
+        let host = parts.next().unwrap_or_default();
+        let port = parts.next();
+        if parts.next().is_some() || host.is_empty() || !host.bytes().all(is_host_char) {
+            return Err(invalid());
+        }
+        (host, port)
+    };
+    
+    let port = match port {
+        Some(port) if !port.is_empty() => {
+            // Only plain digits are valid, `parse` would also accept a sign.
+            if !port.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            Some(port.parse().map_err(|_| invalid())?)
+        },
+        _ => None
+    };
+    Ok((host.to_lowercase(), port))
+}
+
+/// Returns `true` if the byte may appear in a registered name or IPv4 address.
+///
+/// # Params
+///
+/// byte --- The byte to check.
+fn is_host_char(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"-._~%!$&'()*+,;=".contains(&byte)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("Example.COM:8080"),
+            Ok((String::from("example.com"), Some(8080))),
+            "Test host::parse-1 failed."
+        );
+        
+        assert_eq!(
+            (parse("example.com"), parse("example.com:")),
+            (Ok((String::from("example.com"), None)), Ok((String::from("example.com"), None))),
+            "Test host::parse-2 failed."
+        );
+        
+        assert_eq!(
+            (parse("[::1]:8080"), parse("[2001:DB8::ff00:42:8329]"), parse("[::ffff:192.0.2.128]:0")),
+            (
+                Ok((String::from("[::1]"), Some(8080))),
+                Ok((String::from("[2001:db8::ff00:42:8329]"), None)),
+                Ok((String::from("[::ffff:192.0.2.128]"), Some(0)))
+            ),
+            "Test host::parse-3 failed."
+        );
+        
+        assert_eq!(
+            parse("192.168.0.1:65535"),
+            Ok((String::from("192.168.0.1"), Some(65535))),
+            "Test host::parse-4 failed."
+        );
+        
+        for value in [
+            "", ":80", "example com", "example.com:80:80", "::1", "[::1", "[::1]80", "[]", "[::g]",
+            "example.com:65536", "example.com:+80", "example.com:http", "user@example.com", "example.com/path"
+        ].iter() {
+            assert_eq!(
+                parse(value),
+                Err(ParseError::InvalidHost(String::from(*value))),
+                "Test host::parse-5 failed for `{}`.", value
+            );
+        }
+    }
+}
@@ -7,10 +7,62 @@
 //! Date --- 06/09/2017
 
 use std::string::String;
-use super::{HTTP, ErrorToHTTP};
+use std::str;
+use std::str::FromStr;
+use std::fmt;
+use std::io;
+use std::io::prelude::*;
+use std::borrow::Cow;
+use std::time::SystemTime;
+use super::{HTTP, ErrorToHTTP, ParseError, BuildError, Version, Method, HeaderMap, ParseLimits};
+use super::headers;
+use super::token;
+use super::chunked::{self, Encoder};
+use super::date::{self, RetryAfter};
+use super::host;
+use super::target::RequestTarget;
+use super::cookie::{self, Cookie, SetCookie};
+use super::percent;
+use super::negotiate;
+#[cfg(feature = "json")]
+use serde::de::DeserializeOwned;
+#[cfg(feature = "compression")]
+use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
 use super::header_field::*;
 use super::start_line::*;
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+/// How strictly a `MessageHTTP` is parsed.
+///
+/// A folded line begins with a space or tab and continues the value of the Header field
+/// before it. A bare LF ends a line when parsing `Lenient`ly, as RFC 7230 allows, but is a
+/// `BareLineFeed` when parsing `Strict`ly.
+pub enum ParseMode {
+    #[default]
+    /// Folded lines are joined onto the value of the preceding Header field with a single
+    /// space, recognised methods and the `HTTP` of the version are matched ignoring case and
+    /// whitespace between a Header field name and its colon is dropped.
+    Lenient,
+    /// Folded lines, whitespace before the colon of a Header field, recognised methods
+    /// which are not uppercase and versions which are not exactly `HTTP/x.y` are rejected,
+    /// e.g. for a public-facing server.
+    Strict
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// How the end of a message body is found, as in RFC 7230 section 3.3.3.
+pub enum BodyFraming {
+    /// The body is exactly this many bytes long.
+    ContentLength(u64),
+    /// The body is chunked and ends with the last chunk.
+    Chunked,
+    /// The body ends when the connection is closed.
+    Close,
+    /// The framing is ambiguous or invalid, the message must be rejected and the
+    /// connection closed.
+    Error
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 /// A `MessageHTTP` is a representation of a HTTP message.
 pub struct MessageHTTP {
@@ -33,238 +85,2365 @@ impl MessageHTTP {
     pub fn new(start_line: StartLine, header_fields: Vec<HeaderField>, message_body: Vec<u8>) -> MessageHTTP {
         MessageHTTP { start_line, header_fields, message_body }
     }
-    /// Returns a new `MessageHTTP` from the passed `str`.
+    /// Returns a new `MessageHTTP` from the passed `str`, joining any folded Header field lines.
     ///
     /// # Params
     ///
     /// msg --- The message string to convert.
-    pub fn from(msg: &str) -> Result<MessageHTTP, String> {
-        // Split the message based on the line termination for HTTP messages.
-        let mut lines = msg.split("\r\n");
-        
-        // Get the start_line as the first line in the message.
-        let start_line = if let Some(line) = lines.next() {
-            // Convert the first line to a `StartLine`.
-            match StartLine::from(line) {
-                Ok(line) => line,
-                Err(e) => return Err(e)
-            }
-        } else {
-            // There was no first line in lines.
-            return Err(format!("Bad Message string, no Start line: `{}`", msg).to_owned());
+    pub fn from(msg: &str) -> Result<MessageHTTP, ParseError> {
+        msg.parse()
+    }
+    /// Returns a new `MessageHTTP` from the passed `str`, treating folded lines according to `mode`.
+    ///
+    /// # Params
+    ///
+    /// msg --- The message string to convert.</br>
+    /// mode --- How folded Header field lines are treated.
+    pub fn from_with_mode(msg: &str, mode: ParseMode) -> Result<MessageHTTP, ParseError> {
+        // Everything after the first empty line is the body, even if it has empty lines
+        // of its own.
+        MessageHTTP::from_bytes_with_mode(msg.as_bytes(), mode)
+    }
+    /// Returns a new `MessageHTTP` from the passed bytes, joining any folded Header field lines.
+    ///
+    /// Only the start line and header fields must be valid utf8, everything after the
+    /// first empty line is copied into the message body untouched.
+    ///
+    /// # Params
+    ///
+    /// msg --- The message bytes to convert.
+    pub fn from_bytes(msg: &[u8]) -> Result<MessageHTTP, ParseError> {
+        MessageHTTP::from_bytes_with_mode(msg, ParseMode::Lenient)
+    }
+    /// Returns a new `MessageHTTP` from the passed bytes, treating folded lines according to `mode`.
+    ///
+    /// # Params
+    ///
+    /// msg --- The message bytes to convert.</br>
+    /// mode --- How folded Header field lines are treated.
+    pub fn from_bytes_with_mode(msg: &[u8], mode: ParseMode) -> Result<MessageHTTP, ParseError> {
+        // The header section ends at the first empty line.
+        let (head, body) = match token::head_len(msg) {
+            Some(index) => msg.split_at(index),
+            None => (msg, &msg[msg.len()..])
         };
+        let message = MessageHTTP::from_head(head, mode)?;
         
-        // Get all the header fields from the message and convert them all.
-        let fields = lines
-            .clone()
-            .take_while(
-                |s| {
-                    *s != ""
-                }
-            ).map(HeaderField::from);
-        
-        // The `Vec` of Header fields for the message.
-        let mut header_fields = Vec::new();
-        // Read in each of the fields.
-        for field in fields {
-            // If the field raised an error when getting passed raise it again.
-            match field {
-                Ok(hf) => header_fields.push(hf),
-                Err(e) => return Err(e)
-            }
-        }
+        MessageHTTP::with_body(message.start_line, message.header_fields, body.to_vec())
+    }
+    /// Returns a new `MessageHTTP` from the passed `str` if it is within `limits`.
+    ///
+    /// Fails with `UriTooLong`, `TooManyHeaderFields`, `HeaderTooLarge` or `BodyTooLarge` if
+    /// a limit is exceeded, so a server can answer with a `414`, `431` or `413`.
+    ///
+    /// # Params
+    ///
+    /// msg --- The message string to convert.</br>
+    /// limits --- The largest message to accept.
+    pub fn from_with_limits(msg: &str, limits: ParseLimits) -> Result<MessageHTTP, ParseError> {
+        limits.check_message(msg.as_bytes())?;
+        MessageHTTP::from(msg)
+    }
+    /// Returns a new `MessageHTTP` from the passed bytes if they are within `limits`.
+    ///
+    /// [Read more](#method.from_with_limits)
+    ///
+    /// # Params
+    ///
+    /// msg --- The message bytes to convert.</br>
+    /// limits --- The largest message to accept.
+    pub fn from_bytes_with_limits(msg: &[u8], limits: ParseLimits) -> Result<MessageHTTP, ParseError> {
+        limits.check_message(msg)?;
+        MessageHTTP::from_bytes(msg)
+    }
+    /// Returns a new `MessageHTTP` with an empty body from the bytes of a header section,
+    /// for readers which frame the body themselves.
+    ///
+    /// The body is not checked against the `Content-Length`.</br>
+    /// Fails with `IllegalCharacter` if the head contains a control character other than a
+    /// horizontal tab or a line terminator, or with `BareLineFeed` if a `Strict` parse finds
+    /// a line ending in a bare LF, at its position in the head.
+    ///
+    /// # Params
+    ///
+    /// head --- The start line and header fields, with or without the final empty line.</br>
+    /// mode --- How folded Header field lines are treated.
+    pub fn from_head(head: &[u8], mode: ParseMode) -> Result<MessageHTTP, ParseError> {
+        MessageHTTP::from_head_with_http09(head, mode, false)
+    }
+    /// Returns a new `MessageHTTP` with an empty body from the bytes of a header section as
+    /// by `from_head`, accepting an HTTP/0.9 simple-request if `http09` is `true`.
+    ///
+    /// A simple-request has no Header fields, its head is the Request line alone.
+    ///
+    /// # Params
+    ///
+    /// head --- The start line and header fields, with or without the final empty line.</br>
+    /// mode --- How folded Header field lines are treated.</br>
+    /// http09 --- If a simple-request is accepted.
+    pub fn from_head_with_http09(head: &[u8], mode: ParseMode, http09: bool) -> Result<MessageHTTP, ParseError> {
+        // Checked before the lines are split so the position is within the whole head.
+        token::check_controls(head, mode)?;
+        let head = match str::from_utf8(head) {
+            Ok(head) => head,
+            Err(_) => return Err(ParseError::InvalidUtf8)
+        };
+        let mut lines = head.split('\n').map(|line| line.trim_end_matches('\r'));
         
-        // Skip the lines which where used for the Header fields.
-        let mut lines = lines.skip(header_fields.len() + 1);
-        // The `init_string` is the first part of the message body, following lines need to be appended again.
-        let init_string = String::from(
-            // If there is no next line then there is no message body.
-            match lines.next() {
-                Some(line) => line,
-                None => ""
-            }
-        );
-        // If there is no next line then there is no message body.
-        let message_body = if init_string != "" {
-            // Append each of the remaining lines with there seperators restored as the bytes are part of the message.
-            lines.fold(
-                init_string,
-                |mut res, s| {
-                    res.push_str("\r\n");
-                    res.push_str(s);
-                    res
-                }
-            ).into_bytes()
-        } else {
-            // There is no body and therefore there is no bytes.
-            init_string.into_bytes()
+        let start_line = match lines.next() {
+            Some(line) if !line.trim().is_empty() => StartLine::from_with_http09(line, mode, http09)?,
+            _ => return Err(ParseError::MissingStartLine)
         };
+        // Every remaining line of the head is a Header field, an empty line can only
+        // be a trailing line terminator.
+        let header_fields = parse_header_fields(lines.filter(|s| !s.is_empty()), mode)?;
         
-        Ok(MessageHTTP::new(start_line, header_fields, message_body))
+        Ok(MessageHTTP::new(start_line, header_fields, Vec::new()))
     }
     /// Returns a new `MessageHTTP` from the passed bytes.
     ///
     /// # Params
     ///
-    /// msg --- The message string to convert.
-    pub fn from_utf8(msg: Vec<u8>) -> Result<MessageHTTP, String> {
-        match String::from_utf8(msg) {
-            Ok(s) => MessageHTTP::from(s.as_str()),
-            Err(_) => Err(String::from("Bad bytes for utf8 encoded message."))
+    /// msg --- The message bytes to convert.
+    pub fn from_utf8(msg: Vec<u8>) -> Result<MessageHTTP, ParseError> {
+        MessageHTTP::from_bytes(&msg)
+    }
+    /// Returns a new `MessageHTTP` built from parsed parts, framing the body by its `Content-Length`.
+    ///
+    /// # Params
+    ///
+    /// start_line --- The `StartLine` for the message.</br>
+    /// header_fields --- The `HeaderField`s of the message.</br>
+    /// message_body --- All of the bytes following the header section.
+    fn with_body(start_line: StartLine, header_fields: Vec<HeaderField>, message_body: Vec<u8>) -> Result<MessageHTTP, ParseError> {
+        let mut message = MessageHTTP::new(start_line, header_fields, message_body);
+        
+        // A message which could be framed more than one way could smuggle another inside it.
+        message.check_framing()?;
+        // If the length of the body is declared only that many bytes belong to the message.
+        if let Some(length) = message.content_length()? {
+            if message.message_body.len() < length {
+                return Err(ParseError::IncompleteBody { expected: length, received: message.message_body.len() });
+            }
+            message.message_body.truncate(length);
         }
+        Ok(message)
     }
-}
-
-impl HTTP for MessageHTTP {
-    fn to_http(&self) -> Result<String, ErrorToHTTP> {
-        let mut res = format!("{}\r\n", self.start_line.to_http().unwrap()).to_owned();
+    /// Returns the value of the first `HeaderField` with the passed name, ignoring case.
+    ///
+    /// # Params
+    ///
+    /// name --- The name of the `HeaderField` to find.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.header_fields.iter()
+            .find(|field| field.name_eq(name))
+            .map(|field| field.value.as_str())
+    }
+    /// Returns the values of every `HeaderField` with the passed name, ignoring case, in order.
+    ///
+    /// # Params
+    ///
+    /// name --- The name of the `HeaderField`s to find.
+    pub fn headers<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.header_fields.iter()
+            .filter(move |field| field.name_eq(name))
+            .map(|field| field.value.as_str())
+    }
+    /// Returns the elements of every `HeaderField` with the passed name, ignoring case, in
+    /// order, as if every comma separated element had been sent in a `HeaderField` of its own.
+    ///
+    /// Values are only split on commas outside of quoted-strings and each element is
+    /// trimmed. `Set-Cookie` values may themselves contain commas and are never split.
+    ///
+    /// # Params
+    ///
+    /// name --- The name of the `HeaderField`s to find.
+    pub fn header_values<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        let split = !name.eq_ignore_ascii_case(headers::SET_COOKIE);
         
-        for field in self.header_fields.iter() {
-            res.push_str(&format!("{}\r\n", field.to_http().unwrap()));
+        self.headers(name)
+            .flat_map(move |value| if split { token::split_list(value) } else { vec![value] })
+    }
+    /// Returns `true` if the passed message is equal to this one, ignoring the case of
+    /// the `HeaderField` names.
+    ///
+    /// The derived `PartialEq` compares names exactly, this compares them as a server would.
+    ///
+    /// # Params
+    ///
+    /// other --- The message to compare with.
+    pub fn eq_ignore_header_case(&self, other: &MessageHTTP) -> bool {
+        self.start_line == other.start_line
+            && self.message_body == other.message_body
+            && self.header_fields.len() == other.header_fields.len()
+            && self.header_fields.iter()
+                .zip(other.header_fields.iter())
+                .all(|(a, b)| a.name_eq(&b.name) && a.value == b.value)
+    }
+    /// Returns a `HeaderMap` of the `HeaderField`s of the message, for repeated lookups.
+    pub fn headers_map(&self) -> HeaderMap {
+        HeaderMap::from(self.header_fields.clone())
+    }
+    /// Returns the values of every `HeaderField` with the passed name, ignoring case,
+    /// combined into one comma separated list, or `None` if there are no such `HeaderField`s.
+    ///
+    /// `Set-Cookie` values may themselves contain commas and can not be combined, so
+    /// `None` is also returned if there is more than one `Set-Cookie`; use `headers` instead.
+    ///
+    /// # Params
+    ///
+    /// name --- The name of the `HeaderField`s to combine.
+    pub fn combined_header(&self, name: &str) -> Option<String> {
+        let values = self.headers(name).collect::<Vec<&str>>();
+        
+        if values.is_empty()
+            || (values.len() > 1 && name.eq_ignore_ascii_case(headers::SET_COOKIE)) {
+            return None;
         }
+        Some(values.join(", "))
+    }
+    /// Returns the `Retry-After` of the message, or `None` if there is no `Retry-After` or it
+    /// is neither a number of seconds nor an HTTP-date.
+    pub fn retry_after(&self) -> Option<RetryAfter> {
+        self.header(headers::RETRY_AFTER).and_then(RetryAfter::parse)
+    }
+    /// Returns the lowercase transfer codings listed by the `Transfer-Encoding` `HeaderField`s,
+    /// in the order they were applied.
+    pub fn transfer_encodings(&self) -> Vec<String> {
+        self.header_values(headers::TRANSFER_ENCODING)
+            .map(str::to_lowercase)
+            .collect()
+    }
+    /// Returns how the end of the message body is found.
+    ///
+    /// A message with both a `Transfer-Encoding` and a `Content-Length` is an `Error`, as
+    /// the two could be read differently by each server a request passes through and
+    /// be used to smuggle a request. A `Transfer-Encoding` whose final coding is not
+    /// `chunked` is an `Error` for a request and reads until `Close` for a response.</br>
+    /// Without either a request has no body and a response reads until `Close`.
+    pub fn body_framing(&self) -> BodyFraming {
+        let encodings = self.transfer_encodings();
+        let is_request = self.start_line.as_request().is_some();
         
-        if self.message_body.is_empty() {
-            return Ok(res);
-        } else {
-            res.push_str("\r\n");
+        match (encodings.last(), self.content_length()) {
+            (Some(_), Ok(Some(_))) | (_, Err(_)) => BodyFraming::Error,
+            (Some(coding), _) if coding == "chunked" => BodyFraming::Chunked,
+            (Some(_), _) if is_request => BodyFraming::Error,
+            (Some(_), _) => BodyFraming::Close,
+            (None, Ok(Some(length))) => BodyFraming::ContentLength(length as u64),
+            (None, Ok(None)) if is_request => BodyFraming::ContentLength(0),
+            (None, Ok(None)) => BodyFraming::Close
+        }
+    }
+    /// Returns how the end of the message body is found, as by `body_framing`, or why the
+    /// message must be rejected in place of `BodyFraming::Error`.
+    ///
+    /// Fails with `AmbiguousFraming` if there is both a `Transfer-Encoding` and a
+    /// `Content-Length`, with `InvalidContentLength` or `ConflictingContentLength` if the
+    /// `Content-Length` is bad and with `InvalidFraming` if the final transfer coding of a
+    /// request is not `chunked`.
+    pub fn check_framing(&self) -> Result<BodyFraming, ParseError> {
+        match self.body_framing() {
+            BodyFraming::Error => {
+                if !self.transfer_encodings().is_empty() && self.header(headers::CONTENT_LENGTH).is_some() {
+                    return Err(ParseError::AmbiguousFraming);
+                }
+                self.content_length()?;
+                Err(ParseError::InvalidFraming)
+            },
+            framing => Ok(framing)
         }
+    }
+    /// Returns the length of the message body declared by the `Content-Length` `HeaderField`,
+    /// or `None` if there is no `Content-Length`.
+    ///
+    /// Repeated `Content-Length`s, or a list of lengths in one, are accepted only if
+    /// every length is the same. Lengths which disagree make the framing of the body
+    /// ambiguous and could be used to smuggle a request, so they are an error.
+    pub fn content_length(&self) -> Result<Option<usize>, ParseError> {
+        let mut res = None;
         
-        match String::from_utf8(self.message_body.clone()) {
-            Ok(s) => res.push_str(&s),
-            Err(_) => return Err(ErrorToHTTP)
+        for value in self.headers(headers::CONTENT_LENGTH).flat_map(|value| value.split(',')) {
+            let value = value.trim();
+            
+            // Only plain digits are valid, `parse` would also accept a sign.
+            if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(ParseError::InvalidContentLength(String::from(value)));
+            }
+            let length = match value.parse::<usize>() {
+                Ok(length) => length,
+                Err(_) => return Err(ParseError::InvalidContentLength(String::from(value)))
+            };
+            match res {
+                Some(res) if res != length => return Err(ParseError::ConflictingContentLength),
+                _ => res = Some(length)
+            }
         }
         Ok(res)
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    
-    #[test]
-    fn test_message_http() {
-        assert_eq!(
-            MessageHTTP::from("http/1.1 200 OK\r\n name : value \r\n taste : smell \r\n\r\n The red fox jumped\r\nover the lazy dog").unwrap(),
-            MessageHTTP {
-                start_line: StartLine::StatusLine {
-                    version: String::from("HTTP/1.1"),
-                    code: 200,
-                    reason: Some(String::from("OK"))
-                },
-                header_fields: vec![
-                    HeaderField {
-                        name: String::from("name"),
-                        value: String::from("value")
-                    },
-                    HeaderField {
-                        name: String::from("taste"),
-                        value: String::from("smell")
-                    }
-                ],
-                message_body: String::from(" The red fox jumped\r\nover the lazy dog").into_bytes()
-            },
-            "Test MessageHTTP::from-1 failed."
-        );
+    /// Returns the lowercase host and optional port of the `Host` `HeaderField`, or
+    /// `None` if there is no `Host`.
+    ///
+    /// The authority of an absolute-form target replaces the `Host`, as in RFC 7230
+    /// section 5.4.</br>
+    /// Fails if there is more than one `Host` or it is not valid.
+    pub fn host(&self) -> Result<Option<(String, Option<u16>)>, ParseError> {
+        let mut hosts = self.headers(headers::HOST);
+        let host = match (hosts.next(), hosts.next()) {
+            (Some(_), Some(_)) => return Err(ParseError::DuplicateHost),
+            (host, _) => host
+        };
         
-        assert_eq!(
-            MessageHTTP::from("http/1.1 200 OK\r\n name : value \r\n taste : smell \r\n\r\n").unwrap(),
-            MessageHTTP {
-                start_line: StartLine::StatusLine {
-                    version: String::from("HTTP/1.1"),
-                    code: 200,
-                    reason: Some(String::from("OK"))
-                },
-                header_fields: vec![
-                    HeaderField {
-                        name: String::from("name"),
-                        value: String::from("value")
-                    },
-                    HeaderField {
-                        name: String::from("taste"),
-                        value: String::from("smell")
-                    }
-                ],
-                message_body: String::from("").into_bytes()
+        match (self.start_line.target_form(), host) {
+            (Some(Ok(RequestTarget::AbsoluteForm { ref authority, .. })), _) => host::parse(authority).map(Some),
+            (_, Some(value)) => host::parse(value).map(Some),
+            (_, None) => Ok(None)
+        }
+    }
+    /// Checks that a HTTP/1.1 or later request has exactly one valid `Host` `HeaderField`,
+    /// a server must respond `400 Bad Request` if it does not.
+    ///
+    /// An earlier request may omit the `Host` and a response is never checked.
+    pub fn validate_host(&self) -> Result<(), ParseError> {
+        match self.start_line.as_request() {
+            Some((_, _, version)) => match self.host()? {
+                None if version >= Version::HTTP_11 => Err(ParseError::MissingHost),
+                _ => Ok(())
             },
-            "Test MessageHTTP::from-2 failed."
-        );
+            None => Ok(())
+        }
+    }
+    /// Returns every `Cookie` sent in the `Cookie` `HeaderField`s of the message, in order.
+    pub fn cookies(&self) -> Vec<Cookie> {
+        self.headers(headers::COOKIE)
+            .flat_map(cookie::parse)
+            .collect()
+    }
+    /// Returns the message body with any `Content-Encoding` removed, decoding no more than
+    /// the `max_body_bytes` of the default `ParseLimits`.
+    ///
+    /// [Read more](#method.decoded_body_with_limits)
+    pub fn decoded_body(&self) -> Result<Cow<'_, [u8]>, ParseError> {
+        self.decoded_body_with_limits(ParseLimits::default())
+    }
+    /// Returns the message body with any `Content-Encoding` removed.
+    ///
+    /// The body is borrowed for `identity` or when there is no `Content-Encoding`.
+    /// `gzip` and `deflate` are only supported with the `compression` feature, any other
+    /// coding is an `UnsupportedContentEncoding` error.</br>
+    /// Fails with `BodyTooLarge` as soon as a coding decodes to more than the
+    /// `max_body_bytes` of `limits`, so a small compressed body can not exhaust memory.
+    ///
+    /// # Params
+    ///
+    /// limits --- The largest decoded body to accept.
+    pub fn decoded_body_with_limits(&self, limits: ParseLimits) -> Result<Cow<'_, [u8]>, ParseError> {
+        let mut body = Cow::Borrowed(&self.message_body[..]);
+        let header = self.headers(headers::CONTENT_ENCODING).collect::<Vec<&str>>().join(",");
         
-        assert_eq!(
-            MessageHTTP::from("http/1.1 200\r\n name : value \r\n taste : smell \r\n\r\n").unwrap(),
-            MessageHTTP {
-                start_line: StartLine::StatusLine {
-                    version: String::from("HTTP/1.1"),
-                    code: 200,
-                    reason: None
-                },
-                header_fields: vec![
-                    HeaderField {
-                        name: String::from("name"),
-                        value: String::from("value")
-                    },
-                    HeaderField {
-                        name: String::from("taste"),
-                        value: String::from("smell")
-                    }
-                ],
-                message_body: String::from("").into_bytes()
-            },
-            "Test MessageHTTP::from-3 failed."
-        );
+        // Codings are listed in the order they were applied so they are removed in reverse.
+        for encoding in header.rsplit(',').map(str::trim).filter(|encoding| !encoding.is_empty()) {
+            body = match encoding.to_lowercase().as_str() {
+                "identity" => body,
+                #[cfg(feature = "compression")]
+                "gzip" | "x-gzip" => Cow::Owned(inflate(GzDecoder::new(&body[..]), limits.max_body_bytes)?),
+                #[cfg(feature = "compression")]
+                // `deflate` should be zlib wrapped but some clients send raw deflate data.
+                "deflate" => Cow::Owned(
+                    inflate(ZlibDecoder::new(&body[..]), limits.max_body_bytes)
+                        .or_else(|_| inflate(DeflateDecoder::new(&body[..]), limits.max_body_bytes))?
+                ),
+                _ => return Err(ParseError::UnsupportedContentEncoding(String::from(encoding)))
+            };
+            if let Cow::Owned(ref decoded) = body {
+                if decoded.len() > limits.max_body_bytes {
+                    return Err(ParseError::BodyTooLarge);
+                }
+            }
+        }
+        Ok(body)
+    }
+    /// Returns `true` if the `Accept-Encoding` `HeaderField`s allow a body with the passed
+    /// content coding, ignoring case.
+    ///
+    /// Without an `Accept-Encoding` `HeaderField` only `identity` is acceptable.
+    ///
+    /// # Params
+    ///
+    /// encoding --- The content coding to check, e.g. `gzip`.
+    pub fn accepts_encoding(&self, encoding: &str) -> bool {
+        let header = self.headers(headers::ACCEPT_ENCODING).collect::<Vec<&str>>().join(",");
         
-        assert_eq!(
-            MessageHTTP::from("get / http/1.1\r\n name : value \r\n taste : smell \r\n\r\n").unwrap(),
-            MessageHTTP {
-                start_line: StartLine::RequestLine {
-                    method: "GET",
-                    target: String::from("/"),
-                    version: String::from("HTTP/1.1")
-                },
-                header_fields: vec![
-                    HeaderField {
-                        name: String::from("name"),
-                        value: String::from("value")
-                    },
-                    HeaderField {
-                        name: String::from("taste"),
-                        value: String::from("smell")
-                    }
-                ],
-                message_body: String::from("").into_bytes()
-            },
-            "Test MessageHTTP::from-4 failed."
-        );
+        negotiate::accepts_encoding(&header, encoding)
+    }
+    /// Returns the most preferred of the `available` language tags according to the
+    /// `Accept-Language` `HeaderField`s, or `None` if none of them are acceptable.
+    ///
+    /// Without an `Accept-Language` `HeaderField` the first available tag is returned.
+    /// [Read more](negotiate/fn.preferred_language.html)
+    ///
+    /// # Params
+    ///
+    /// available --- The language tags which can be sent, in order of the server's preference.
+    pub fn preferred_language(&self, available: &[&str]) -> Option<String> {
+        let header = self.headers(headers::ACCEPT_LANGUAGE).collect::<Vec<&str>>().join(",");
         
-        assert_eq!(
-            MessageHTTP::from("get / http/1.1\r\n name : value \r\n taste : smell \r\n\r\n The quick brown fox\r\njumped over the lazy dog.").unwrap(),
-            MessageHTTP {
-                start_line: StartLine::RequestLine {
-                    method: "GET",
-                    target: String::from("/"),
-                    version: String::from("HTTP/1.1")
-                },
-                header_fields: vec![
-                    HeaderField {
-                        name: String::from("name"),
-                        value: String::from("value")
-                    },
-                    HeaderField {
-                        name: String::from("taste"),
-                        value: String::from("smell")
-                    }
-                ],
-                message_body: String::from(" The quick brown fox\r\njumped over the lazy dog.").into_bytes()
+        negotiate::preferred_language(&header, available)
+    }
+    /// Returns the decoded `key=value` pairs of an `application/x-www-form-urlencoded` body in order.
+    ///
+    /// `+` is decoded as a space and percent-encoded bytes are decoded, keys without a
+    /// value are given an empty value and repeated keys are all kept.</br>
+    /// Fails if the `Content-Type` is not `application/x-www-form-urlencoded` or the
+    /// body does not decode to utf8.
+    pub fn form_data(&self) -> Result<Vec<(String, String)>, ParseError> {
+        let content_type = self.header(headers::CONTENT_TYPE).unwrap_or_default();
+        // Parameters such as `charset` follow the media type.
+        if !content_type.split(';').next().unwrap_or_default().trim().eq_ignore_ascii_case("application/x-www-form-urlencoded") {
+            return Err(ParseError::UnexpectedContentType(String::from(content_type)));
+        }
+        let body = match str::from_utf8(&self.message_body) {
+            Ok(body) => body,
+            Err(_) => return Err(ParseError::InvalidUtf8)
+        };
+        
+        body.split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(
+                |pair| {
+                    let mut parts = pair.splitn(2, '=');
+                    
+                    Ok((
+                        percent::decode_form(parts.next().unwrap_or_default())?,
+                        percent::decode_form(parts.next().unwrap_or_default())?
+                    ))
+                }
+            ).collect()
+    }
+    /// Returns the message body decoded as text using the `charset` of the `Content-Type`.
+    ///
+    /// `utf-8`, `us-ascii` and `iso-8859-1` are supported, the body is taken to be `utf-8`
+    /// if there is no `charset`.</br>
+    /// Fails if the `charset` is not supported or the body is not valid in it.
+    pub fn body_as_string(&self) -> Result<String, ParseError> {
+        let charset = self.charset();
+        
+        match charset.as_str() {
+            "utf-8" | "utf8" => match String::from_utf8(self.message_body.clone()) {
+                Ok(body) => Ok(body),
+                Err(_) => Err(ParseError::InvalidUtf8)
             },
-            "Test MessageHTTP::from-5 failed."
-        );
+            "us-ascii" | "ascii" => if self.message_body.is_ascii() {
+                Ok(self.message_body.iter().map(|&b| char::from(b)).collect())
+            } else {
+                Err(ParseError::InvalidText(charset))
+            },
+            // Every byte of latin-1 is the code point of the same value.
+            "iso-8859-1" | "latin1" | "latin-1" => Ok(self.message_body.iter().map(|&b| char::from(b)).collect()),
+            _ => Err(ParseError::UnsupportedCharset(charset))
+        }
+    }
+    /// Returns the message body decoded as text like `body_as_string`, replacing anything
+    /// which can not be decoded with `U+FFFD`.
+    ///
+    /// A body with an unsupported `charset` is decoded as `utf-8`.
+    pub fn body_as_string_lossy(&self) -> String {
+        match self.charset().as_str() {
+            "us-ascii" | "ascii" => self.message_body.iter()
+                .map(|&b| if b.is_ascii() { char::from(b) } else { '\u{FFFD}' })
+                .collect(),
+            "iso-8859-1" | "latin1" | "latin-1" => self.message_body.iter().map(|&b| char::from(b)).collect(),
+            _ => String::from_utf8_lossy(&self.message_body).into_owned()
+        }
+    }
+    /// Returns the lowercase `charset` parameter of the `Content-Type`, or `utf-8` if there is none.
+    fn charset(&self) -> String {
+        token::parameters(self.header(headers::CONTENT_TYPE).unwrap_or_default())
+            .into_iter()
+            .find(|param| param.0 == "charset")
+            .map(|param| param.1.to_lowercase())
+            .unwrap_or_else(|| String::from("utf-8"))
+    }
+    #[cfg(feature = "json")]
+    /// Returns the JSON message body deserialized as a `T`.
+    ///
+    /// Fails with `UnexpectedContentType` if the `Content-Type` is not `application/json`
+    /// or a `+json` type, or with `InvalidJson` if the body cannot be deserialized.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, ParseError> {
+        let content_type = self.header(headers::CONTENT_TYPE).unwrap_or_default();
+        let media_type = content_type.split(';').next().unwrap_or_default().trim().to_lowercase();
+        if media_type != "application/json" && !(media_type.contains('/') && media_type.ends_with("+json")) {
+            return Err(ParseError::UnexpectedContentType(String::from(content_type)));
+        }
         
-        assert_eq!(
-            MessageHTTP::from("get / http/1.1\r\n name : value \r\n taste : smell \r\n\r\n The quick brown fox\r\njumped over the lazy dog.")
-                .unwrap()
-                .to_http()
-                .unwrap(),
-            "GET \"/\" HTTP/1.1\r\nname:value\r\ntaste:smell\r\n\r\n The quick brown fox\r\njumped over the lazy dog.",
-            "Test MessageHTTP::from-6 failed."
-        );
+        match ::serde_json::from_slice(&self.message_body) {
+            Ok(value) => Ok(value),
+            Err(e) => Err(ParseError::InvalidJson(e.to_string()))
+        }
+    }
+    /// Appends a new `HeaderField` to the message, regardless of any existing fields with the same name.
+    ///
+    /// Fails if the name is not a token or the value contains a CR, LF or NUL.
+    ///
+    /// # Params
+    ///
+    /// name --- The name of the new `HeaderField`.</br>
+    /// value --- The value of the new `HeaderField`.
+    pub fn add_header(&mut self, name: &str, value: &str) -> Result<(), BuildError> {
+        self.header_fields.push(HeaderField::new(name, value)?);
+        Ok(())
+    }
+    /// Sets the value of the `HeaderField` with the passed name, ignoring case.
+    ///
+    /// The first matching field is updated in place and any other matching fields are
+    /// removed. If there is no matching field a new one is appended.</br>
+    /// Fails, leaving the message unchanged, if the name is not a token or the value
+    /// contains a CR, LF or NUL.
+    ///
+    /// # Params
+    ///
+    /// name --- The name of the `HeaderField` to set.</br>
+    /// value --- The new value of the `HeaderField`.
+    pub fn set_header(&mut self, name: &str, value: &str) -> Result<(), BuildError> {
+        let field = HeaderField::new(name, value)?;
+        
+        match self.header_fields.iter().position(|field| field.name_eq(name)) {
+            Some(index) => {
+                // No field before `index` matches so removing them all leaves `index` in place.
+                self.remove_header(name);
+                self.header_fields.insert(index, field);
+            },
+            None => self.header_fields.push(field)
+        }
+        Ok(())
+    }
+    /// Sets the `Date` `HeaderField` to the current time.
+    pub fn set_date_now(&mut self) {
+        self.set_header(headers::DATE, &date::format(SystemTime::now()))
+            .expect("A HTTP-date is always a valid Header Field value.");
+    }
+    /// Sets the `Connection` `HeaderField` to `close`, telling the peer the connection
+    /// will be closed after this message.
+    pub fn set_connection_close(&mut self) {
+        self.set_header(headers::CONNECTION, "close")
+            .expect("`close` is always a valid Header Field value.");
+    }
+    /// Sets the `Allow` `HeaderField` to the passed methods, e.g. for a
+    /// `405 Method Not Allowed` or a response to an `OPTIONS` request.
+    ///
+    /// Methods are uppercased and only the first of any duplicates is kept, otherwise the
+    /// order they were passed in is kept.</br>
+    /// Fails, leaving the message unchanged, if a method is not a token.
+    ///
+    /// # Params
+    ///
+    /// methods --- The methods the target resource supports.
+    pub fn with_allow(&mut self, methods: &[&str]) -> Result<(), BuildError> {
+        let mut allowed: Vec<String> = Vec::new();
+        
+        for &method in methods {
+            let normalized = method.trim().to_uppercase();
+            
+            if !token::is_token(&normalized) {
+                return Err(BuildError::InvalidMethod(String::from(method)));
+            }
+            if !allowed.contains(&normalized) {
+                allowed.push(normalized);
+            }
+        }
+        self.set_header(headers::ALLOW, &allowed.join(", "))
+    }
+    /// Returns `true` if the connection should be kept open after this message.
+    ///
+    /// HTTP/1.1 and later keep the connection open unless the `Connection` `HeaderField`s
+    /// list `close`, earlier versions close it unless they list `keep-alive`. The options
+    /// of every `Connection` are compared ignoring case.
+    pub fn wants_keep_alive(&self) -> bool {
+        let has_option = |option: &str| self.headers(headers::CONNECTION)
+            .flat_map(token::split_list)
+            .any(|value| value.eq_ignore_ascii_case(option));
+        
+        if has_option("close") {
+            false
+        } else {
+            self.start_line.version() >= Version::HTTP_11 || has_option("keep-alive")
+        }
+    }
+    /// Appends a new `Set-Cookie` `HeaderField` for the passed cookie.
+    ///
+    /// Every cookie gets its own field, they are never merged.
+    ///
+    /// # Params
+    ///
+    /// cookie --- The cookie to set.
+    pub fn add_cookie(&mut self, cookie: SetCookie) -> Result<(), BuildError> {
+        let value = cookie.to_header_value()?;
+        
+        self.add_header(headers::SET_COOKIE, &value)
+    }
+    /// Removes every `HeaderField` with the passed name, ignoring case.</br>
+    /// Returns the number of fields which were removed.
+    ///
+    /// # Params
+    ///
+    /// name --- The name of the `HeaderField`s to remove.
+    pub fn remove_header(&mut self, name: &str) -> usize {
+        let len = self.header_fields.len();
+        
+        self.header_fields.retain(|field| !field.name_eq(name));
+        len - self.header_fields.len()
+    }
+    /// Returns the bytes of this `MessageHTTP` as they would be sent on the wire.
+    ///
+    /// The start line and header fields are written exactly as by `to_http` and the
+    /// message body is appended untouched, so the body does not need to be valid utf8.</br>
+    /// A `Content-Length` is added for a non-empty body without a `Content-Length` or
+    /// `Transfer-Encoding`.</br>
+    /// Fails as `to_http` does, e.g. if the `Content-Length` does not match the length of
+    /// the body.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ErrorToHTTP> {
+        let mut res = self.head()?.into_bytes();
+        
+        res.extend_from_slice(&self.message_body);
+        Ok(res)
+    }
+    /// Writes this `MessageHTTP` to `writer` exactly as `to_bytes` would return it, without
+    /// building the whole message in memory first, then flushes `writer`.
+    ///
+    /// A body which should not be held in memory at all can be sent as a `Response`.</br>
+    /// Fails with `InvalidData`, before anything is written, if the `Content-Length` does
+    /// not match the length of the body or if a `1xx`, `204 No Content` or
+    /// `304 Not Modified` response has a body, `Content-Length` or `Transfer-Encoding`.
+    ///
+    /// # Params
+    ///
+    /// writer --- The writer to send the message to.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.write_parts_to(writer, self.status_allows_body(), true)
+    }
+    /// Writes this response to `writer` as `write_to` would, without the body if a
+    /// response to the passed `Method` must not have one, e.g. for a `HEAD` request.
+    ///
+    /// The Header Fields are written as they are and a `Content-Length` is added as by
+    /// `write_to`, so a response to a `HEAD` request has the `Content-Length` of the body it
+    /// would have had. A response whose body was already left out keeps its `Content-Length`.
+    ///
+    /// # Params
+    ///
+    /// writer --- The writer to send the message to.</br>
+    /// method --- The method of the request being responded to.
+    pub fn write_response_to<W: Write>(&self, writer: &mut W, method: &Method) -> io::Result<()> {
+        let body = self.start_line.as_status().is_none_or(|(_, code, _)| method.response_body_allowed(code));
+        
+        self.write_parts_to(writer, body, true)
+    }
+    /// Writes this response to `writer` with its Header Fields and body exactly as they
+    /// are, without adding or checking a `Content-Length`.
+    ///
+    /// This is only for a response whose body is read until the connection is closed,
+    /// the connection must be closed once the response is written.
+    ///
+    /// # Params
+    ///
+    /// writer --- The writer to send the message to.
+    pub fn write_unframed_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.write_parts_to(writer, true, false)
+    }
+    /// Writes this `MessageHTTP` to `writer`, then flushes `writer`.
+    ///
+    /// # Params
+    ///
+    /// writer --- The writer to send the message to.</br>
+    /// body --- If the message body is written.</br>
+    /// framed --- If a `Content-Length` is added or checked for a message which may have a
+    /// body.
+    fn write_parts_to<W: Write>(&self, writer: &mut W, body: bool, framed: bool) -> io::Result<()> {
+        if self.is_simple_response() {
+            writer.write_all(&self.message_body)?;
+            return writer.flush();
+        }
+        
+        // The framing is checked before anything is written.
+        let length = match self.missing_content_length() {
+            _ if !framed => None,
+            Ok(length) => length,
+            // The body of a response to a `HEAD` request may already have been left out.
+            Err(_) if !body && self.message_body.is_empty() && self.status_allows_body() => None,
+            Err(_) => return Err(io::Error::new(io::ErrorKind::InvalidData, "The message body is not allowed or does not match the Content-Length."))
+        };
+        match self.start_line.to_http() {
+            Ok(line) => write!(writer, "{}\r\n", line)?,
+            Err(_) => return Err(io::Error::new(io::ErrorKind::InvalidData, "Failed to convert the Start line to HTTP."))
+        }
+        for field in self.header_fields.iter() {
+            match field.to_http() {
+                Ok(field) => write!(writer, "{}\r\n", field)?,
+                Err(_) => return Err(io::Error::new(io::ErrorKind::InvalidData, "Failed to convert a Header field to HTTP."))
+            }
+        }
+        if let Some(length) = length {
+            write!(writer, "{}: {}\r\n", headers::CONTENT_LENGTH, length)?;
+        }
+        // The header section is always terminated by an empty line.
+        writer.write_all(b"\r\n")?;
+        if body {
+            writer.write_all(&self.message_body)?;
+        }
+        writer.flush()
+    }
+    /// Writes this `MessageHTTP` to `writer` using the chunked transfer coding for the body.
+    ///
+    /// A `Transfer-Encoding: chunked` field is written in place of any `Content-Length`
+    /// or `Transfer-Encoding` fields on the message.
+    ///
+    /// # Params
+    ///
+    /// writer --- The writer to send the message to.
+    pub fn write_chunked_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.write_chunked_with_trailers_to(writer, &[])
+    }
+    /// Writes this `MessageHTTP` to `writer` using the chunked transfer coding for the body,
+    /// followed by the passed trailer fields.
+    ///
+    /// Fails, before anything is written, if any of the trailers is one of the
+    /// `chunked::FORBIDDEN_TRAILERS`.
+    ///
+    /// # Params
+    ///
+    /// writer --- The writer to send the message to.</br>
+    /// trailers --- The `HeaderField`s to send after the body.
+    pub fn write_chunked_with_trailers_to<W: Write>(&self, writer: &mut W, trailers: &[HeaderField]) -> io::Result<()> {
+        chunked::check_trailers(trailers)?;
+        let mut head = match self.start_line.to_http() {
+            Ok(line) => format!("{}\r\n", line),
+            Err(_) => return Err(io::Error::new(io::ErrorKind::InvalidData, "Failed to convert the Start line to HTTP."))
+        };
+        for field in self.header_fields.iter() {
+            if field.name_eq(headers::CONTENT_LENGTH) || field.name_eq(headers::TRANSFER_ENCODING) {
+                continue;
+            }
+            match field.to_http() {
+                Ok(field) => head.push_str(&format!("{}\r\n", field)),
+                Err(_) => return Err(io::Error::new(io::ErrorKind::InvalidData, "Failed to convert a Header field to HTTP."))
+            }
+        }
+        head.push_str(&format!("{}: chunked\r\n\r\n", headers::TRANSFER_ENCODING));
+        writer.write_all(head.as_bytes())?;
+        
+        let mut encoder = Encoder::new(writer);
+        encoder.write_all(&self.message_body)?;
+        encoder.finish_with_trailers(trailers)?;
+        Ok(())
+    }
+    /// Decodes a chunked message body in place, as in RFC 7230 section 4.1.3.
+    ///
+    /// The trailer fields are appended to the `HeaderField`s, the `Transfer-Encoding` and
+    /// `Trailer` fields are removed and the `Content-Length` is set to the length of the
+    /// decoded body. Any transfer codings applied before `chunked`, e.g. `gzip`, are moved
+    /// to the end of the `Content-Encoding` so the body can still be decoded as by
+    /// `decoded_body`. A message whose body is not chunked is left unchanged.
+    pub fn decode_chunked(&mut self) -> Result<(), ParseError> {
+        if self.body_framing() != BodyFraming::Chunked {
+            return Ok(());
+        }
+        
+        let (body, trailers, _) = chunked::decode_with_trailers(&self.message_body)?;
+        let mut encodings = self.transfer_encodings();
+        
+        encodings.pop();
+        self.remove_header(headers::TRANSFER_ENCODING);
+        if !encodings.is_empty() {
+            // The transfer codings were applied after any content codings.
+            let content_encodings = self.combined_header(headers::CONTENT_ENCODING)
+                .into_iter()
+                .chain(encodings)
+                .collect::<Vec<String>>()
+                .join(", ");
+            
+            self.set_header(headers::CONTENT_ENCODING, &content_encodings)
+                .expect("Codings are always a valid Header Field value.");
+        }
+        self.remove_header(headers::TRAILER);
+        self.header_fields.extend(trailers);
+        self.set_header(headers::CONTENT_LENGTH, &body.len().to_string())
+            .expect("A `Content-Length` is always a valid Header Field value.");
+        self.message_body = body;
+        Ok(())
+    }
+    /// Returns `true` if this is the response to an HTTP/0.9 simple-request, which is
+    /// sent as its body alone.
+    pub fn is_simple_response(&self) -> bool {
+        self.start_line.as_status().is_some_and(|status| status.0 == Version::HTTP_09)
+    }
+    /// Removes the body, `Content-Length` and `Transfer-Encoding` of a `1xx`,
+    /// `204 No Content` or `304 Not Modified` response, which must not have a body, so it
+    /// can be sent. Validators such as an `ETag` are kept.</br>
+    /// Any other message is left unchanged.
+    pub fn strip_body_for_status(&mut self) {
+        if !self.status_allows_body() {
+            self.message_body.clear();
+            self.remove_header(headers::CONTENT_LENGTH);
+            self.remove_header(headers::TRANSFER_ENCODING);
+        }
+    }
+    /// Returns `false` if this is a `1xx`, `204 No Content` or `304 Not Modified` response,
+    /// which can not have a body.
+    fn status_allows_body(&self) -> bool {
+        self.start_line.as_status().is_none_or(|(_, code, _)| code.allows_body())
+    }
+    /// Returns the length to send in a `Content-Length` so the end of the message body can
+    /// be found, or `None` if the body is empty or already framed.
+    ///
+    /// Fails if the `Content-Length` is invalid or does not match the length of the body,
+    /// as the receiver would read the wrong bytes as the body or as the next message, or if
+    /// the status code does not allow a body and there is a body or any framing.
+    fn missing_content_length(&self) -> Result<Option<usize>, ErrorToHTTP> {
+        if !self.status_allows_body() {
+            let framed = self.header_fields.iter()
+                .any(|field| field.name_eq(headers::CONTENT_LENGTH) || field.name_eq(headers::TRANSFER_ENCODING));
+            
+            return if framed || !self.message_body.is_empty() { Err(ErrorToHTTP) } else { Ok(None) };
+        }
+        match self.content_length() {
+            Ok(Some(length)) if length == self.message_body.len() => Ok(None),
+            Ok(None) if self.message_body.is_empty() || !self.transfer_encodings().is_empty() => Ok(None),
+            Ok(None) => Ok(Some(self.message_body.len())),
+            _ => Err(ErrorToHTTP)
+        }
+    }
+    /// Returns the start line and header fields of the message, terminated by an empty line.
+    ///
+    /// A `Content-Length` is added if the message body is not framed. A simple response
+    /// has no head at all.
+    fn head(&self) -> Result<String, ErrorToHTTP> {
+        if self.is_simple_response() {
+            return Ok(String::new());
+        }
+        
+        let mut res = format!("{}\r\n", self.start_line.to_http()?);
+        
+        res.push_str(&self.header_fields.to_http()?);
+        if let Some(length) = self.missing_content_length()? {
+            res.push_str(&format!("{}: {}\r\n", headers::CONTENT_LENGTH, length));
+        }
+        // The header section is always terminated by an empty line.
+        res.push_str("\r\n");
+        Ok(res)
+    }
+}
+
+impl FromStr for MessageHTTP {
+    type Err = ParseError;
+    
+    /// Converts the passed `str` into a `MessageHTTP`, joining any folded Header field lines.
+    ///
+    /// # Params
+    ///
+    /// msg --- The message string to convert.
+    fn from_str(msg: &str) -> Result<MessageHTTP, ParseError> {
+        MessageHTTP::from_with_mode(msg, ParseMode::Lenient)
+    }
+}
+
+/// Returns the `HeaderField`s parsed from the lines of a header section.
+///
+/// A line beginning with a space or tab is folded onto the line before it, a fold
+/// before the first Header field would continue the start line and is always rejected.
+///
+/// # Params
+///
+/// lines --- The lines of the header section, not including the start line.</br>
+/// mode --- How folded lines are treated.
+fn parse_header_fields<'a, I: Iterator<Item = &'a str>>(lines: I, mode: ParseMode) -> Result<Vec<HeaderField>, ParseError> {
+    let mut unfolded: Vec<String> = Vec::new();
+    
+    for line in lines {
+        if line.starts_with(&[' ', '\t'][..]) {
+            match unfolded.last_mut() {
+                Some(field) if mode != ParseMode::Strict => {
+                    // Replace the fold and the whitespace around it with a single space.
+                    let len = field.trim_end().len();
+                    
+                    field.truncate(len);
+                    field.push(' ');
+                    field.push_str(line.trim());
+                },
+                _ => return Err(ParseError::ObsoleteFold(String::from(line)))
+            }
+        } else {
+            unfolded.push(String::from(line));
+        }
+    }
+    unfolded.iter()
+        .map(|line| HeaderField::from_with_mode(line, mode))
+        .collect()
+}
+
+#[cfg(feature = "compression")]
+/// Returns the bytes read from a decompressing reader, stopping one byte past `max_bytes`
+/// so the caller can tell the body was too large.
+///
+/// # Params
+///
+/// decoder --- The reader to decompress with.</br>
+/// max_bytes --- The most decompressed bytes to accept.
+fn inflate<R: Read>(decoder: R, max_bytes: usize) -> Result<Vec<u8>, ParseError> {
+    let mut res = Vec::new();
+    
+    match decoder.take(max_bytes as u64 + 1).read_to_end(&mut res) {
+        Ok(_) => Ok(res),
+        Err(e) => Err(ParseError::InvalidCompressedBody(e.to_string()))
+    }
+}
+
+impl fmt::Display for MessageHTTP {
+    /// Writes the start line and header fields of the message, each terminated by `\r\n`,
+    /// followed by an empty line and the length of the body, e.g. `<11 bytes>`.
+    ///
+    /// The body itself is never written so binary bodies can not corrupt a terminal or log.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\r\n", self.start_line)?;
+        for field in self.header_fields.iter() {
+            write!(f, "{}\r\n", field)?;
+        }
+        match self.message_body.len() {
+            1 => write!(f, "\r\n<1 byte>"),
+            len => write!(f, "\r\n<{} bytes>", len)
+        }
+    }
+}
+
+impl HTTP for MessageHTTP {
+    /// Converts the `MessageHTTP` into the string which would be sent on the wire.
+    ///
+    /// The start line and each header field are terminated by `\r\n`, followed by
+    /// an empty line and then the message body.</br>
+    /// A `Content-Length` is added as by `to_bytes`. An `ErrorToHTTP` is returned if any
+    /// component fails to convert, if the `Content-Length` does not match the body or if
+    /// the message body is not valid utf8.
+    fn to_http(&self) -> Result<String, ErrorToHTTP> {
+        let mut res = self.head()?;
+        
+        match String::from_utf8(self.message_body.clone()) {
+            Ok(s) => res.push_str(&s),
+            Err(_) => return Err(ErrorToHTTP)
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{StatusCode, Version, Method};
+    
+    #[test]
+    fn test_message_http() {
+        assert_eq!(
+            MessageHTTP::from("http/1.1 200 OK\r\nname: value \r\ntaste: smell \r\n\r\n The red fox jumped\r\nover the lazy dog").unwrap(),
+            MessageHTTP {
+                start_line: StartLine::StatusLine {
+                    version: Version::HTTP_11,
+                    code: StatusCode::OK,
+                    reason: Some(String::from("OK"))
+                },
+                header_fields: vec![
+                    HeaderField {
+                        name: String::from("name"),
+                        value: String::from("value")
+                    },
+                    HeaderField {
+                        name: String::from("taste"),
+                        value: String::from("smell")
+                    }
+                ],
+                message_body: String::from(" The red fox jumped\r\nover the lazy dog").into_bytes()
+            },
+            "Test MessageHTTP::from-1 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("http/1.1 200 OK\r\nname: value \r\ntaste: smell \r\n\r\n").unwrap(),
+            MessageHTTP {
+                start_line: StartLine::StatusLine {
+                    version: Version::HTTP_11,
+                    code: StatusCode::OK,
+                    reason: Some(String::from("OK"))
+                },
+                header_fields: vec![
+                    HeaderField {
+                        name: String::from("name"),
+                        value: String::from("value")
+                    },
+                    HeaderField {
+                        name: String::from("taste"),
+                        value: String::from("smell")
+                    }
+                ],
+                message_body: String::from("").into_bytes()
+            },
+            "Test MessageHTTP::from-2 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("http/1.1 200\r\nname: value \r\ntaste: smell \r\n\r\n").unwrap(),
+            MessageHTTP {
+                start_line: StartLine::StatusLine {
+                    version: Version::HTTP_11,
+                    code: StatusCode::OK,
+                    reason: None
+                },
+                header_fields: vec![
+                    HeaderField {
+                        name: String::from("name"),
+                        value: String::from("value")
+                    },
+                    HeaderField {
+                        name: String::from("taste"),
+                        value: String::from("smell")
+                    }
+                ],
+                message_body: String::from("").into_bytes()
+            },
+            "Test MessageHTTP::from-3 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("get / http/1.1\r\nname: value \r\ntaste: smell \r\n\r\n").unwrap(),
+            MessageHTTP {
+                start_line: StartLine::RequestLine {
+                    method: Method::Get,
+                    target: String::from("/"),
+                    version: Version::HTTP_11
+                },
+                header_fields: vec![
+                    HeaderField {
+                        name: String::from("name"),
+                        value: String::from("value")
+                    },
+                    HeaderField {
+                        name: String::from("taste"),
+                        value: String::from("smell")
+                    }
+                ],
+                message_body: String::from("").into_bytes()
+            },
+            "Test MessageHTTP::from-4 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("get / http/1.1\r\nname: value \r\ntaste: smell \r\n\r\n The quick brown fox\r\njumped over the lazy dog.").unwrap(),
+            MessageHTTP {
+                start_line: StartLine::RequestLine {
+                    method: Method::Get,
+                    target: String::from("/"),
+                    version: Version::HTTP_11
+                },
+                header_fields: vec![
+                    HeaderField {
+                        name: String::from("name"),
+                        value: String::from("value")
+                    },
+                    HeaderField {
+                        name: String::from("taste"),
+                        value: String::from("smell")
+                    }
+                ],
+                message_body: String::from(" The quick brown fox\r\njumped over the lazy dog.").into_bytes()
+            },
+            "Test MessageHTTP::from-5 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("get / http/1.1\r\nname: value \r\ntaste: smell \r\n\r\n The quick brown fox\r\njumped over the lazy dog.")
+                .unwrap()
+                .to_http()
+                .unwrap(),
+            "GET / HTTP/1.1\r\nName: value\r\nTaste: smell\r\nContent-Length: 47\r\n\r\n The quick brown fox\r\njumped over the lazy dog.",
+            "Test MessageHTTP::from-6 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("\r\nname: value\r\n\r\n"),
+            Err(ParseError::MissingStartLine),
+            "Test MessageHTTP::from-7 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("GET / HTTP/1.1\r\nname value\r\n\r\n"),
+            Err(ParseError::MalformedHeader(String::from("name value"))),
+            "Test MessageHTTP::from-8 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from_utf8(vec![b'G', b'E', b'T', 0xFF]),
+            Err(ParseError::InvalidUtf8),
+            "Test MessageHTTP::from-9 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_to_http() {
+        let messages = [
+            "GET / HTTP/1.1\r\nName: value\r\nTaste: smell\r\nContent-Length: 47\r\n\r\n The quick brown fox\r\njumped over the lazy dog.",
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: 17\r\n\r\n<p>\r\n\r\nline\r\n</p>",
+            // Only an unregistered code is written without a reason.
+            "HTTP/1.1 299\r\n\r\n"
+        ];
+        for (i, msg) in messages.iter().enumerate() {
+            let parsed = MessageHTTP::from(msg).unwrap();
+            
+            assert_eq!(
+                parsed.to_http().unwrap(),
+                *msg,
+                "Test MessageHTTP::to_http-{} failed.", i + 1
+            );
+            assert_eq!(
+                MessageHTTP::from(&parsed.to_http().unwrap()).unwrap(),
+                parsed,
+                "Test MessageHTTP::to_http-{} failed to round-trip.", i + 1
+            );
+        }
+        
+        assert_eq!(
+            MessageHTTP::new(
+                StartLine::from("HTTP/1.1 204 No Content").unwrap(),
+                Vec::new(),
+                Vec::new()
+            ).to_http().unwrap(),
+            "HTTP/1.1 204 No Content\r\n\r\n",
+            "Test MessageHTTP::to_http-4 failed."
+        );
+        
+        assert!(
+            MessageHTTP::new(
+                StartLine::from("HTTP/1.1 200 OK").unwrap(),
+                Vec::new(),
+                vec![0xFF, 0xFE]
+            ).to_http().is_err(),
+            "Test MessageHTTP::to_http-5 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_from_str() {
+        let message: MessageHTTP = "GET / HTTP/1.1\r\nHost: localhost\r\n\r\n".parse().unwrap();
+        
+        assert_eq!(
+            message,
+            MessageHTTP::from("GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap(),
+            "Test MessageHTTP::from_str-1 failed."
+        );
+        assert_eq!(
+            "".parse::<MessageHTTP>(),
+            Err(ParseError::MissingStartLine),
+            "Test MessageHTTP::from_str-2 failed."
+        );
+        assert_eq!(
+            "GET / HTTP/1.1\r\nHost localhost\r\n\r\n".parse::<MessageHTTP>(),
+            Err(ParseError::MalformedHeader(String::from("Host localhost"))),
+            "Test MessageHTTP::from_str-3 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_illegal_character() {
+        let tests = [
+            ("GET /a\0b HTTP/1.1\r\nHost: localhost\r\n\r\n", 6),
+            ("GET / HTTP/1.1\r\nX-Note: a\rb\r\n\r\n", 25),
+            ("HTTP/1.1 200 O\x01K\r\n\r\n", 14),
+            ("GET / HTTP/1.1\r\nX-Note: \x7F\r\n\r\n", 24)
+        ];
+        
+        for &(msg, position) in tests.iter() {
+            let error = MessageHTTP::from(msg).unwrap_err();
+            
+            assert_eq!(
+                (error.clone(), error.to_string()),
+                (ParseError::IllegalCharacter { position }, format!("Bad message, illegal control character at byte {}.", position)),
+                "Test MessageHTTP::from illegal-character failed for `{:?}`.", msg
+            );
+        }
+        
+        // Control characters in the body are not checked.
+        assert_eq!(
+            MessageHTTP::from("POST / HTTP/1.1\r\nX-Note: a\tb\r\n\r\n\0\x01\r").map(|message| message.message_body),
+            Ok(b"\0\x01\r".to_vec()),
+            "Test MessageHTTP::from illegal-character failed for a body."
+        );
+    }
+    #[test]
+    fn test_message_http_from_blank_lines() {
+        let tests: [(&str, &[u8]); 4] = [
+            ("\r\nhello", b"\r\nhello"),
+            ("--a\r\n\r\nhello\r\n--a--", b"--a\r\n\r\nhello\r\n--a--"),
+            ("\r\n", b"\r\n"),
+            ("", b"")
+        ];
+        
+        for &(body, expected) in tests.iter() {
+            let message = MessageHTTP::from(&format!("POST / HTTP/1.1\r\nHost: localhost\r\n\r\n{}", body)).unwrap();
+            
+            assert_eq!(
+                (message.header_fields.len(), message.message_body.as_slice()),
+                (1, expected),
+                "Test MessageHTTP::from_blank_lines failed for `{}`.", body.escape_debug()
+            );
+        }
+    }
+    #[test]
+    fn test_message_http_display() {
+        assert_eq!(
+            format!("{}", MessageHTTP::from("POST /upload HTTP/1.1\r\nHost: localhost\r\nContent-Length: 11\r\n\r\nhello world").unwrap()),
+            "POST /upload HTTP/1.1\r\nHost: localhost\r\nContent-Length: 11\r\n\r\n<11 bytes>",
+            "Test MessageHTTP display-1 failed."
+        );
+        assert_eq!(
+            MessageHTTP::new(
+                StartLine::from("HTTP/1.1 200 OK").unwrap(),
+                vec![HeaderField::from("Content-Type: application/octet-stream").unwrap()],
+                vec![0x1B]
+            ).to_string(),
+            "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\n\r\n<1 byte>",
+            "Test MessageHTTP display-2 failed."
+        );
+        assert_eq!(
+            MessageHTTP::from("HTTP/1.1 204 No Content\r\n\r\n").unwrap().to_string(),
+            "HTTP/1.1 204 No Content\r\n\r\n<0 bytes>",
+            "Test MessageHTTP display-3 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_content_length() {
+        assert_eq!(
+            MessageHTTP::from("POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello").unwrap().message_body,
+            b"hello".to_vec(),
+            "Test MessageHTTP::from-Content-Length-1 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\nhello"),
+            Err(ParseError::IncompleteBody { expected: 10, received: 5 }),
+            "Test MessageHTTP::from-Content-Length-2 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello\r\nGET / HTTP/1.1").unwrap().message_body,
+            b"hello".to_vec(),
+            "Test MessageHTTP::from-Content-Length-3 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("POST / HTTP/1.1\r\nContent-Length: 0\r\n\r\nGET / HTTP/1.1").unwrap().message_body,
+            Vec::<u8>::new(),
+            "Test MessageHTTP::from-Content-Length-4 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("POST / HTTP/1.1\r\nHost: localhost\r\n\r\nhello\r\nworld").unwrap().message_body,
+            b"hello\r\nworld".to_vec(),
+            "Test MessageHTTP::from-Content-Length-5 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("POST / HTTP/1.1\r\nContent-Length: +5\r\n\r\nhello"),
+            Err(ParseError::InvalidContentLength(String::from("+5"))),
+            "Test MessageHTTP::from-Content-Length-6 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("POST / HTTP/1.1\r\nContent-Length: 5\r\ncontent-length: 5, 5\r\n\r\nhello").unwrap().content_length(),
+            Ok(Some(5)),
+            "Test MessageHTTP::from-Content-Length-7 failed."
+        );
+        assert_eq!(
+            MessageHTTP::from("POST / HTTP/1.1\r\nContent-Length: 5\r\nContent-Length: 10\r\n\r\nhelloGET / HTTP/1.1\r\n\r\n"),
+            Err(ParseError::ConflictingContentLength),
+            "Test MessageHTTP::from-Content-Length-8 failed."
+        );
+        assert_eq!(
+            MessageHTTP::from("POST / HTTP/1.1\r\nContent-Length: 5, 10\r\n\r\nhelloGET / HTTP/1.1\r\n\r\n"),
+            Err(ParseError::ConflictingContentLength),
+            "Test MessageHTTP::from-Content-Length-9 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_body_framing() {
+        let message = MessageHTTP::from("POST / HTTP/1.1\r\nTransfer-Encoding: GZIP\r\ntransfer-encoding: , Chunked\r\n\r\n").unwrap();
+        assert_eq!(
+            message.transfer_encodings(),
+            vec![String::from("gzip"), String::from("chunked")],
+            "Test MessageHTTP::transfer_encodings-1 failed."
+        );
+        assert_eq!(
+            message.body_framing(),
+            BodyFraming::Chunked,
+            "Test MessageHTTP::body_framing-1 failed."
+        );
+        
+        let cases = [
+            ("POST / HTTP/1.1\r\nTransfer-Encoding: gzip, chunked\r\n\r\n", BodyFraming::Chunked),
+            ("POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n", BodyFraming::Chunked),
+            ("POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\nContent-Length: 5\r\n\r\n", BodyFraming::Error),
+            ("POST / HTTP/1.1\r\nContent-Length: 5\r\nTransfer-Encoding: identity\r\n\r\n", BodyFraming::Error),
+            ("POST / HTTP/1.1\r\nTransfer-Encoding: chunked, gzip\r\n\r\n", BodyFraming::Error),
+            ("HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked, gzip\r\n\r\n", BodyFraming::Close),
+            ("POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello", BodyFraming::ContentLength(5)),
+            ("POST / HTTP/1.1\r\nHost: localhost\r\n\r\n", BodyFraming::ContentLength(0)),
+            ("HTTP/1.1 200 OK\r\nServer: web_server\r\n\r\n", BodyFraming::Close)
+        ];
+        for (i, &(msg, framing)) in cases.iter().enumerate() {
+            assert_eq!(
+                MessageHTTP::from_head(msg.split("\r\n\r\n").next().unwrap().as_bytes(), ParseMode::Lenient).unwrap().body_framing(),
+                framing,
+                "Test MessageHTTP::body_framing-{} failed.", i + 2
+            );
+        }
+    }
+    #[test]
+    fn test_message_http_smuggling() {
+        let tests = [
+            // CL.TE, a front-end using the `Content-Length` passes `SMUGGLED` along as the body.
+            ("POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 13\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nSMUGGLED", ParseError::AmbiguousFraming),
+            // TE.CL, a back-end using the `Content-Length` reads `8\r\n` and leaves the rest.
+            ("POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 3\r\nTransfer-Encoding: chunked\r\n\r\n8\r\nSMUGGLED\r\n0\r\n\r\n", ParseError::AmbiguousFraming),
+            ("POST / HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: chunked\r\nTransfer-Encoding: x\r\nContent-Length: 4\r\n\r\n1\r\nZ\r\n", ParseError::AmbiguousFraming),
+            ("POST / HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: chunked\r\nTransfer-Encoding: x\r\n\r\n1\r\nZ\r\n0\r\n\r\n", ParseError::InvalidFraming),
+            ("POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 3\r\nContent-Length: 8\r\n\r\nSMUGGLED", ParseError::ConflictingContentLength)
+        ];
+        for &(msg, ref error) in tests.iter() {
+            assert_eq!(
+                MessageHTTP::from(msg),
+                Err(error.clone()),
+                "Test MessageHTTP::from-smuggling failed for `{:?}`.", msg
+            );
+            assert_eq!(
+                MessageHTTP::from_head(msg.split("\r\n\r\n").next().unwrap().as_bytes(), ParseMode::Lenient).map(|message| message.body_framing()),
+                Ok(BodyFraming::Error),
+                "Test MessageHTTP::check_framing failed for `{:?}`.", msg
+            );
+        }
+    }
+    #[test]
+    fn test_message_http_from_bytes() {
+        let mut msg = b"POST /upload HTTP/1.1\r\nContent-Type: application/octet-stream\r\n\r\n".to_vec();
+        msg.extend_from_slice(&[0x00, 0xFF, 0xFE]);
+        
+        let message = MessageHTTP::from_bytes(&msg).unwrap();
+        assert_eq!(
+            message.message_body,
+            vec![0x00, 0xFF, 0xFE],
+            "Test MessageHTTP::from_bytes-1 failed."
+        );
+        assert_eq!(
+            message.header("Content-Type"),
+            Some("application/octet-stream"),
+            "Test MessageHTTP::from_bytes-2 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from_utf8(msg).unwrap(),
+            message,
+            "Test MessageHTTP::from_bytes-3 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from_bytes(b"GET / HTTP/1.1\r\nHost: localhost\r\n").unwrap(),
+            MessageHTTP::from("GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap(),
+            "Test MessageHTTP::from_bytes-4 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from_bytes(b"GET / HTTP/1.1\r\nHost: \xFF\r\n\r\n"),
+            Err(ParseError::InvalidUtf8),
+            "Test MessageHTTP::from_bytes-5 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_folding() {
+        let msg = "GET / HTTP/1.1\r\nUser-Agent: Mozilla/5.0 \r\n\t (X11; Linux x86_64)\r\nHost: localhost\r\n\r\n";
+        
+        let message = MessageHTTP::from(msg).unwrap();
+        assert_eq!(
+            message.header("User-Agent"),
+            Some("Mozilla/5.0 (X11; Linux x86_64)"),
+            "Test MessageHTTP::from_with_mode-1 failed."
+        );
+        assert_eq!(
+            message.header("Host"),
+            Some("localhost"),
+            "Test MessageHTTP::from_with_mode-2 failed."
+        );
+        assert_eq!(
+            MessageHTTP::from_bytes(msg.as_bytes()).unwrap(),
+            message,
+            "Test MessageHTTP::from_with_mode-3 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from_with_mode(msg, ParseMode::Strict),
+            Err(ParseError::ObsoleteFold(String::from("\t (X11; Linux x86_64)"))),
+            "Test MessageHTTP::from_with_mode-4 failed."
+        );
+        assert_eq!(
+            MessageHTTP::from_bytes_with_mode(msg.as_bytes(), ParseMode::Strict),
+            Err(ParseError::ObsoleteFold(String::from("\t (X11; Linux x86_64)"))),
+            "Test MessageHTTP::from_with_mode-5 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("GET / HTTP/1.1\r\n Host: localhost\r\n\r\n"),
+            Err(ParseError::ObsoleteFold(String::from(" Host: localhost"))),
+            "Test MessageHTTP::from_with_mode-6 failed."
+        );
+        assert_eq!(
+            MessageHTTP::from_bytes(b"GET / HTTP/1.1\r\n Host: localhost\r\n\r\n"),
+            Err(ParseError::ObsoleteFold(String::from(" Host: localhost"))),
+            "Test MessageHTTP::from_with_mode-7 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_strict() {
+        let tests = [
+            ("get / HTTP/1.1\r\nHost: a\r\n\r\n", ParseError::NonCanonicalMethod(String::from("get"))),
+            ("Post / HTTP/1.1\r\nHost: a\r\n\r\n", ParseError::NonCanonicalMethod(String::from("Post"))),
+            ("GET / http/1.1\r\nHost: a\r\n\r\n", ParseError::NonCanonicalVersion(String::from("http/1.1"))),
+            ("GET / HTTP/01.1\r\nHost: a\r\n\r\n", ParseError::NonCanonicalVersion(String::from("HTTP/01.1"))),
+            ("http/1.1 200 OK\r\n\r\n", ParseError::NonCanonicalVersion(String::from("http/1.1"))),
+            ("GET / HTTP/1.1\r\nHost : a\r\n\r\n", ParseError::WhitespaceBeforeColon(String::from("Host : a"))),
+            ("GET / HTTP/1.1\r\nHost\t: a\r\n\r\n", ParseError::WhitespaceBeforeColon(String::from("Host\t: a")))
+        ];
+        for &(msg, ref error) in tests.iter() {
+            let lenient = MessageHTTP::from_with_mode(msg, ParseMode::Lenient);
+            
+            assert!(
+                lenient.as_ref().is_ok_and(|message| message.start_line.method().is_none_or(|method| method.is_recognized())),
+                "Test MessageHTTP::from_with_mode-strict-1 failed for `{:?}`.", msg
+            );
+            assert_eq!(
+                MessageHTTP::from_with_mode(msg, ParseMode::Strict),
+                Err(error.clone()),
+                "Test MessageHTTP::from_with_mode-strict-2 failed for `{:?}`.", msg
+            );
+        }
+        assert_eq!(
+            MessageHTTP::from("GET / HTTP/1.1\r\nHost : a\r\n\r\n").unwrap().header("Host"),
+            Some("a"),
+            "Test MessageHTTP::from_with_mode-strict-3 failed."
+        );
+        
+        let msg = "GET / HTTP/1.1\nHost: a\r\n\nbody";
+        assert_eq!(
+            (
+                MessageHTTP::from_with_mode(msg, ParseMode::Lenient).map(|message| (message.header("Host").map(String::from), message.message_body)),
+                MessageHTTP::from_with_mode(msg, ParseMode::Strict)
+            ),
+            (Ok((Some(String::from("a")), b"body".to_vec())), Err(ParseError::BareLineFeed { position: 14 })),
+            "Test MessageHTTP::from_with_mode-strict-4 failed."
+        );
+        let msg = "GET / HTTP/1.1\r\nX Host: a\r\n\r\n";
+        assert_eq!(
+            (MessageHTTP::from_with_mode(msg, ParseMode::Lenient), MessageHTTP::from_with_mode(msg, ParseMode::Strict)),
+            (Err(ParseError::MalformedHeader(String::from("X Host: a"))), Err(ParseError::MalformedHeader(String::from("X Host: a")))),
+            "Test MessageHTTP::from_with_mode-strict-5 failed."
+        );
+        assert_eq!(
+            MessageHTTP::from_with_mode("BREW /pot HTTP/1.1\r\n\r\n", ParseMode::Strict).map(|message| message.start_line.method().cloned()),
+            Ok(Some(Method::Extension(String::from("BREW")))),
+            "Test MessageHTTP::from_with_mode-strict-6 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_header_values() {
+        let one = MessageHTTP::from("HTTP/1.1 200 OK\r\nVia: 1.0 fred, 1.1 p.example.net (\"a, b\") ,1.1 web\r\n\r\n").unwrap();
+        let three = MessageHTTP::from("HTTP/1.1 200 OK\r\nVia: 1.0 fred\r\nServer: web_server\r\nvia: 1.1 p.example.net (\"a, b\")\r\nVIA: 1.1 web\r\n\r\n").unwrap();
+        
+        assert_eq!(
+            one.header_values("via").collect::<Vec<&str>>(),
+            vec!["1.0 fred", "1.1 p.example.net (\"a, b\")", "1.1 web"],
+            "Test MessageHTTP::header_values-1 failed."
+        );
+        assert_eq!(
+            one.header_values("Via").collect::<Vec<&str>>(),
+            three.header_values("Via").collect::<Vec<&str>>(),
+            "Test MessageHTTP::header_values-2 failed."
+        );
+        
+        let message = MessageHTTP::from("HTTP/1.1 200 OK\r\nSet-Cookie: a=1; Expires=Wed, 21 Oct 2015 07:28:00 GMT\r\nset-cookie: b=2\r\n\r\n").unwrap();
+        assert_eq!(
+            (message.header_values("Set-Cookie").collect::<Vec<&str>>(), message.header_values("Missing").count()),
+            (vec!["a=1; Expires=Wed, 21 Oct 2015 07:28:00 GMT", "b=2"], 0),
+            "Test MessageHTTP::header_values-3 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_retry_after() {
+        use std::time::{Duration, UNIX_EPOCH};
+        
+        let tests = [
+            ("Retry-After: 120\r\n", Some(RetryAfter::Delay(Duration::from_secs(120)))),
+            ("Retry-After: Fri, 31 Dec 1999 23:59:59 GMT\r\n", Some(RetryAfter::Date(UNIX_EPOCH + Duration::from_secs(946684799)))),
+            ("Retry-After: soon\r\n", None),
+            ("Retry-After: -5\r\n", None),
+            ("Retry-After: 99999999999999999999\r\n", None),
+            ("", None)
+        ];
+        for &(field, expected) in tests.iter() {
+            assert_eq!(
+                MessageHTTP::from(&format!("HTTP/1.1 503 Service Unavailable\r\n{}\r\n", field)).unwrap().retry_after(),
+                expected,
+                "Test MessageHTTP::retry_after failed for `{:?}`.", field
+            );
+        }
+        assert_eq!(
+            (RetryAfter::Delay(Duration::from_millis(1500)).to_string(), RetryAfter::Date(UNIX_EPOCH).to_string()),
+            (String::from("1"), String::from("Thu, 01 Jan 1970 00:00:00 GMT")),
+            "Test RetryAfter::fmt-1 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_headers() {
+        let mut message = MessageHTTP::from("HTTP/1.1 200 OK\r\nVary: Accept\r\nServer: web_server\r\nvary: Cookie\r\n\r\n").unwrap();
+        
+        assert_eq!(
+            message.header("VARY"),
+            Some("Accept"),
+            "Test MessageHTTP::header-1 failed."
+        );
+        assert_eq!(
+            message.headers("vary").collect::<Vec<&str>>(),
+            vec!["Accept", "Cookie"],
+            "Test MessageHTTP::headers-1 failed."
+        );
+        assert_eq!(
+            message.header("Missing"),
+            None,
+            "Test MessageHTTP::header-2 failed."
+        );
+        
+        message.add_header("Set-Cookie", "a=1").unwrap();
+        message.add_header("set-cookie", "b=2").unwrap();
+        assert_eq!(
+            message.headers("Set-Cookie").collect::<Vec<&str>>(),
+            vec!["a=1", "b=2"],
+            "Test MessageHTTP::add_header-1 failed."
+        );
+        assert_eq!(
+            message.combined_header("Vary"),
+            Some(String::from("Accept, Cookie")),
+            "Test MessageHTTP::combined_header-1 failed."
+        );
+        assert_eq!(
+            message.combined_header("Server"),
+            Some(String::from("web_server")),
+            "Test MessageHTTP::combined_header-2 failed."
+        );
+        assert_eq!(
+            message.combined_header("Set-Cookie"),
+            None,
+            "Test MessageHTTP::combined_header-3 failed."
+        );
+        assert_eq!(
+            message.combined_header("Missing"),
+            None,
+            "Test MessageHTTP::combined_header-4 failed."
+        );
+        assert_eq!(
+            message.headers_map().get_all("SET-COOKIE").collect::<Vec<&str>>(),
+            vec!["a=1", "b=2"],
+            "Test MessageHTTP::headers_map-1 failed."
+        );
+        
+        let lower = MessageHTTP::from("HTTP/1.1 200 OK\r\nvary: Accept\r\nserver: web_server\r\n\r\n").unwrap();
+        let upper = MessageHTTP::from("HTTP/1.1 200 OK\r\nVary: Accept\r\nSERVER: web_server\r\n\r\n").unwrap();
+        assert!(
+            lower != upper && lower.eq_ignore_header_case(&upper),
+            "Test MessageHTTP::eq_ignore_header_case-1 failed."
+        );
+        assert!(
+            !lower.eq_ignore_header_case(&MessageHTTP::from("HTTP/1.1 200 OK\r\nvary: accept\r\nserver: web_server\r\n\r\n").unwrap()),
+            "Test MessageHTTP::eq_ignore_header_case-2 failed."
+        );
+        assert!(
+            !lower.eq_ignore_header_case(&MessageHTTP::from("HTTP/1.1 200 OK\r\nvary: Accept\r\n\r\n").unwrap()),
+            "Test MessageHTTP::eq_ignore_header_case-3 failed."
+        );
+        
+        message.set_header("VARY", "Origin").unwrap();
+        message.set_header("Content-Type", "text/plain").unwrap();
+        assert_eq!(
+            message.to_http().unwrap(),
+            "HTTP/1.1 200 OK\r\nVary: Origin\r\nServer: web_server\r\nSet-Cookie: a=1\r\nSet-Cookie: b=2\r\nContent-Type: text/plain\r\n\r\n",
+            "Test MessageHTTP::set_header-1 failed."
+        );
+        
+        assert_eq!(
+            message.remove_header("SET-COOKIE"),
+            2,
+            "Test MessageHTTP::remove_header-1 failed."
+        );
+        assert_eq!(
+            message.remove_header("Set-Cookie"),
+            0,
+            "Test MessageHTTP::remove_header-2 failed."
+        );
+        assert_eq!(
+            message.to_http().unwrap(),
+            "HTTP/1.1 200 OK\r\nVary: Origin\r\nServer: web_server\r\nContent-Type: text/plain\r\n\r\n",
+            "Test MessageHTTP::remove_header-3 failed."
+        );
+        
+        assert_eq!(
+            message.set_header("Location", "x\r\nSet-Cookie: evil"),
+            Err(BuildError::InvalidHeaderValue(String::from("x\r\nSet-Cookie: evil"))),
+            "Test MessageHTTP::set_header-2 failed."
+        );
+        assert_eq!(
+            message.add_header("Location", "x\r\nSet-Cookie: evil"),
+            Err(BuildError::InvalidHeaderValue(String::from("x\r\nSet-Cookie: evil"))),
+            "Test MessageHTTP::add_header-2 failed."
+        );
+        assert_eq!(
+            message.set_header("Content-Type", "text/html\0"),
+            Err(BuildError::InvalidHeaderValue(String::from("text/html\0"))),
+            "Test MessageHTTP::set_header-3 failed."
+        );
+        assert_eq!(
+            message.to_http().unwrap(),
+            "HTTP/1.1 200 OK\r\nVary: Origin\r\nServer: web_server\r\nContent-Type: text/plain\r\n\r\n",
+            "Test MessageHTTP::set_header-4 failed."
+        );
+        
+        // A bare LF ends the line when parsing leniently, so it cannot hide inside a value.
+        let msg = "HTTP/1.1 302 Found\r\nLocation: x\nSet-Cookie: evil\r\n\r\n";
+        assert_eq!(
+            (
+                MessageHTTP::from(msg).map(|message| message.header_fields),
+                MessageHTTP::from_with_mode(msg, ParseMode::Strict)
+            ),
+            (
+                Ok(vec![HeaderField::new("Location", "x").unwrap(), HeaderField::new("Set-Cookie", "evil").unwrap()]),
+                Err(ParseError::BareLineFeed { position: 31 })
+            ),
+            "Test MessageHTTP::from-bare-LF-1 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_write_chunked_to() {
+        let message = MessageHTTP::from("HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 11\r\n\r\nhello world").unwrap();
+        let mut res = Vec::new();
+        
+        message.write_chunked_to(&mut res).unwrap();
+        assert_eq!(
+            res,
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nTransfer-Encoding: chunked\r\n\r\nB\r\nhello world\r\n0\r\n\r\n".to_vec(),
+            "Test MessageHTTP::write_chunked_to-1 failed."
+        );
+        
+        let head_len = res.windows(4).position(|w| w == b"\r\n\r\n").unwrap() + 4;
+        assert_eq!(
+            ::http::chunked::decode(&res[head_len..]).unwrap().0,
+            message.message_body,
+            "Test MessageHTTP::write_chunked_to-2 failed."
+        );
+        
+        let trailers = [
+            HeaderField::from("Content-MD5: XrY7u+Ae7tCTyyK7j1rNww==").unwrap(),
+            HeaderField::from("Grpc-Status: 0").unwrap()
+        ];
+        let mut res = Vec::new();
+        
+        message.write_chunked_with_trailers_to(&mut res, &trailers).unwrap();
+        let mut decoded = MessageHTTP::from_bytes(&res).unwrap();
+        assert_eq!(
+            decoded.body_framing(),
+            BodyFraming::Chunked,
+            "Test MessageHTTP::write_chunked_with_trailers_to-1 failed."
+        );
+        decoded.decode_chunked().unwrap();
+        assert_eq!(
+            decoded.to_http().unwrap(),
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-MD5: XrY7u+Ae7tCTyyK7j1rNww==\r\nGrpc-Status: 0\r\nContent-Length: 11\r\n\r\nhello world",
+            "Test MessageHTTP::decode_chunked-1 failed."
+        );
+        
+        let mut res = Vec::new();
+        assert_eq!(
+            (message.write_chunked_with_trailers_to(&mut res, &[HeaderField::from("Host: evil").unwrap()]).is_err(), res),
+            (true, Vec::new()),
+            "Test MessageHTTP::write_chunked_with_trailers_to-2 failed."
+        );
+        
+        let mut gzipped = MessageHTTP::from("HTTP/1.1 200 OK\r\nTrailer: X-Sum\r\nTransfer-Encoding: gzip, chunked\r\n\r\n2\r\nhi\r\n0\r\nX-Sum: 1\r\n\r\n").unwrap();
+        gzipped.decode_chunked().unwrap();
+        assert_eq!(
+            gzipped.to_http().unwrap(),
+            "HTTP/1.1 200 OK\r\nContent-Encoding: gzip\r\nX-Sum: 1\r\nContent-Length: 2\r\n\r\nhi",
+            "Test MessageHTTP::decode_chunked-2 failed."
+        );
+        assert_eq!(
+            MessageHTTP::from(&gzipped.to_http().unwrap()).map(|message| message.body_framing()),
+            Ok(BodyFraming::ContentLength(2)),
+            "Test MessageHTTP::decode_chunked-3 failed."
+        );
+        
+        let mut gzipped = MessageHTTP::from("HTTP/1.1 200 OK\r\nContent-Encoding: br\r\nTransfer-Encoding: gzip, chunked\r\n\r\n2\r\nhi\r\n0\r\n\r\n").unwrap();
+        gzipped.decode_chunked().unwrap();
+        assert_eq!(
+            gzipped.combined_header(headers::CONTENT_ENCODING),
+            Some(String::from("br, gzip")),
+            "Test MessageHTTP::decode_chunked-4 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_body_as_string() {
+        let mut message = MessageHTTP::from("HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=ISO-8859-1\r\n\r\n").unwrap();
+        
+        message.message_body = b"caf\xE9 \xA9 \xFF".to_vec();
+        assert_eq!(
+            message.body_as_string(),
+            Ok(String::from("café © ÿ")),
+            "Test MessageHTTP::body_as_string-1 failed."
+        );
+        assert_eq!(
+            message.body_as_string_lossy(),
+            "café © ÿ",
+            "Test MessageHTTP::body_as_string_lossy-1 failed."
+        );
+        
+        message.set_header("Content-Type", "text/plain").unwrap();
+        assert_eq!(
+            message.body_as_string(),
+            Err(ParseError::InvalidUtf8),
+            "Test MessageHTTP::body_as_string-2 failed."
+        );
+        assert_eq!(
+            message.body_as_string_lossy(),
+            "caf\u{FFFD} \u{FFFD} \u{FFFD}",
+            "Test MessageHTTP::body_as_string_lossy-2 failed."
+        );
+        
+        message.set_header("Content-Type", "text/plain; charset=\"us-ascii\"").unwrap();
+        assert_eq!(
+            message.body_as_string(),
+            Err(ParseError::InvalidText(String::from("us-ascii"))),
+            "Test MessageHTTP::body_as_string-3 failed."
+        );
+        message.message_body = b"plain".to_vec();
+        assert_eq!(
+            message.body_as_string(),
+            Ok(String::from("plain")),
+            "Test MessageHTTP::body_as_string-4 failed."
+        );
+        
+        message.set_header("Content-Type", "text/html; charset=UTF-8").unwrap();
+        message.message_body = "café".as_bytes().to_vec();
+        assert_eq!(
+            message.body_as_string(),
+            Ok(String::from("café")),
+            "Test MessageHTTP::body_as_string-5 failed."
+        );
+        
+        message.set_header("Content-Type", "text/plain; charset=Shift_JIS").unwrap();
+        assert_eq!(
+            message.body_as_string(),
+            Err(ParseError::UnsupportedCharset(String::from("shift_jis"))),
+            "Test MessageHTTP::body_as_string-6 failed."
+        );
+        assert_eq!(
+            message.body_as_string_lossy(),
+            "café",
+            "Test MessageHTTP::body_as_string_lossy-3 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_form_data() {
+        let message = MessageHTTP::from("POST /submit HTTP/1.1\r\nContent-Type: application/x-www-form-urlencoded\r\n\r\nname=Dan&msg=hello+world%21&flag&&name=caf%C3%A9").unwrap();
+        
+        assert_eq!(
+            message.form_data().unwrap(),
+            vec![
+                (String::from("name"), String::from("Dan")),
+                (String::from("msg"), String::from("hello world!")),
+                (String::from("flag"), String::new()),
+                (String::from("name"), String::from("caf\u{e9}"))
+            ],
+            "Test MessageHTTP::form_data-1 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("POST / HTTP/1.1\r\ncontent-type: Application/X-WWW-Form-Urlencoded; charset=utf-8\r\n\r\na=1").unwrap().form_data().unwrap(),
+            vec![(String::from("a"), String::from("1"))],
+            "Test MessageHTTP::form_data-2 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("POST / HTTP/1.1\r\nContent-Type: application/json\r\n\r\n{\"a\": 1}").unwrap().form_data(),
+            Err(ParseError::UnexpectedContentType(String::from("application/json"))),
+            "Test MessageHTTP::form_data-3 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("POST / HTTP/1.1\r\n\r\na=1").unwrap().form_data(),
+            Err(ParseError::UnexpectedContentType(String::new())),
+            "Test MessageHTTP::form_data-4 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("POST / HTTP/1.1\r\nContent-Type: application/x-www-form-urlencoded\r\n\r\na=%C3").unwrap().form_data(),
+            Err(ParseError::InvalidUtf8),
+            "Test MessageHTTP::form_data-5 failed."
+        );
+    }
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_message_http_json() {
+        use std::collections::BTreeMap;
+        
+        let message = MessageHTTP::from("POST / HTTP/1.1\r\nContent-Type: application/json; charset=utf-8\r\n\r\n{\"a\": [1, 2], \"b\": []}").unwrap();
+        let mut expected = BTreeMap::new();
+        expected.insert(String::from("a"), vec![1, 2]);
+        expected.insert(String::from("b"), Vec::new());
+        assert_eq!(
+            message.json::<BTreeMap<String, Vec<u32>>>().unwrap(),
+            expected,
+            "Test MessageHTTP::json-1 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("POST / HTTP/1.1\r\nContent-Type: application/problem+json\r\n\r\n\"text\"").unwrap().json::<String>().unwrap(),
+            "text",
+            "Test MessageHTTP::json-2 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("POST / HTTP/1.1\r\nContent-Type: text/plain\r\n\r\n1").unwrap().json::<u32>(),
+            Err(ParseError::UnexpectedContentType(String::from("text/plain"))),
+            "Test MessageHTTP::json-3 failed."
+        );
+        
+        match MessageHTTP::from("POST / HTTP/1.1\r\nContent-Type: application/json\r\n\r\n{\"a\":").unwrap().json::<u32>() {
+            Err(ParseError::InvalidJson(_)) => (),
+            res => panic!("Test MessageHTTP::json-4 failed, got `{:?}`.", res)
+        }
+    }
+    #[test]
+    fn test_message_http_accepts_encoding() {
+        let message = MessageHTTP::from("GET / HTTP/1.1\r\nAccept-Encoding: gzip, br;q=0\r\naccept-encoding: deflate;q=0.5\r\n\r\n").unwrap();
+        
+        assert_eq!(
+            ["gzip", "GZIP", "deflate", "br", "identity", "zstd"].iter().map(|encoding| message.accepts_encoding(encoding)).collect::<Vec<bool>>(),
+            vec![true, true, true, false, true, false],
+            "Test MessageHTTP::accepts_encoding-1 failed."
+        );
+        
+        let message = MessageHTTP::from("GET / HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(
+            (message.accepts_encoding("gzip"), message.accepts_encoding("identity")),
+            (false, true),
+            "Test MessageHTTP::accepts_encoding-2 failed."
+        );
+        
+        // A coding preferred less than `identity` is still acceptable.
+        let message = MessageHTTP::from("GET / HTTP/1.1\r\nAccept-Encoding: gzip;q=0.1, identity;q=0.5\r\n\r\n").unwrap();
+        assert_eq!(
+            ["gzip", "identity", "br"].iter().map(|encoding| message.accepts_encoding(encoding)).collect::<Vec<bool>>(),
+            vec![true, true, false],
+            "Test MessageHTTP::accepts_encoding-3 failed."
+        );
+        
+        let message = MessageHTTP::from("GET / HTTP/1.1\r\nAccept-Encoding: br, *;q=0\r\n\r\n").unwrap();
+        assert_eq!(
+            ["br", "gzip", "identity"].iter().map(|encoding| message.accepts_encoding(encoding)).collect::<Vec<bool>>(),
+            vec![true, false, false],
+            "Test MessageHTTP::accepts_encoding-4 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_preferred_language() {
+        let message = MessageHTTP::from("GET / HTTP/1.1\r\nAccept-Language: fr-CH, fr;q=0.9\r\naccept-language: en;q=0.8, *;q=0\r\n\r\n").unwrap();
+        
+        assert_eq!(
+            (message.preferred_language(&["en-AU", "fr-FR"]), message.preferred_language(&["de", "en-AU"]), message.preferred_language(&["de"])),
+            (Some(String::from("fr-FR")), Some(String::from("en-AU")), None),
+            "Test MessageHTTP::preferred_language-1 failed."
+        );
+        
+        let message = MessageHTTP::from("GET / HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(
+            (message.preferred_language(&["de", "en"]), message.preferred_language(&[])),
+            (Some(String::from("de")), None),
+            "Test MessageHTTP::preferred_language-2 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_decoded_body() {
+        let mut message = MessageHTTP::from("POST / HTTP/1.1\r\nContent-Encoding: identity\r\n\r\nhello").unwrap();
+        
+        assert_eq!(
+            message.decoded_body().unwrap(),
+            Cow::Borrowed(&b"hello"[..]),
+            "Test MessageHTTP::decoded_body-1 failed."
+        );
+        
+        message.remove_header("Content-Encoding");
+        assert_eq!(
+            message.decoded_body().unwrap(),
+            Cow::Borrowed(&b"hello"[..]),
+            "Test MessageHTTP::decoded_body-2 failed."
+        );
+        
+        message.add_header("Content-Encoding", "br").unwrap();
+        assert_eq!(
+            message.decoded_body(),
+            Err(ParseError::UnsupportedContentEncoding(String::from("br"))),
+            "Test MessageHTTP::decoded_body-3 failed."
+        );
+    }
+    #[test]
+    #[cfg(feature = "compression")]
+    fn test_message_http_decoded_body_compressed() {
+        use flate2::Compression;
+        use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
+        
+        let data = b"The quick brown fox jumped over the lazy dog. The quick brown fox.".to_vec();
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(&data).unwrap();
+        let gzip = gzip.finish().unwrap();
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(&data).unwrap();
+        let zlib = zlib.finish().unwrap();
+        let mut deflate = DeflateEncoder::new(Vec::new(), Compression::default());
+        deflate.write_all(&data).unwrap();
+        let deflate = deflate.finish().unwrap();
+        // Gzip applied to a deflated body.
+        let mut both = GzEncoder::new(Vec::new(), Compression::default());
+        both.write_all(&zlib).unwrap();
+        let both = both.finish().unwrap();
+        
+        for &(encoding, body) in [("gzip", &gzip), ("X-GZIP", &gzip), ("deflate", &zlib), ("Deflate", &deflate), ("deflate, identity, gzip", &both)].iter() {
+            let mut message = MessageHTTP::from("POST / HTTP/1.1\r\n\r\n").unwrap();
+            message.add_header("Content-Encoding", encoding).unwrap();
+            message.message_body = body.to_vec();
+            
+            assert_eq!(
+                message.decoded_body().unwrap().into_owned(),
+                data,
+                "Test MessageHTTP::decoded_body-4 failed for `{}`.", encoding
+            );
+        }
+        
+        let mut message = MessageHTTP::from("POST / HTTP/1.1\r\nContent-Encoding: gzip\r\n\r\nnot gzip").unwrap();
+        match message.decoded_body() {
+            Err(ParseError::InvalidCompressedBody(_)) => (),
+            res => panic!("Test MessageHTTP::decoded_body-5 failed, got `{:?}`.", res)
+        }
+        
+        message.set_header("Content-Encoding", "gzip, compress").unwrap();
+        assert_eq!(
+            message.decoded_body(),
+            Err(ParseError::UnsupportedContentEncoding(String::from("compress"))),
+            "Test MessageHTTP::decoded_body-6 failed."
+        );
+        
+        // A small body which inflates past the limit is cut off.
+        let mut bomb = GzEncoder::new(Vec::new(), Compression::best());
+        bomb.write_all(&[0; 64 * 1024]).unwrap();
+        message.set_header("Content-Encoding", "gzip").unwrap();
+        message.message_body = bomb.finish().unwrap();
+        let limits = ParseLimits { max_body_bytes: 1024, ..ParseLimits::default() };
+        assert_eq!(
+            (message.decoded_body_with_limits(limits), message.decoded_body().map(|body| body.len())),
+            (Err(ParseError::BodyTooLarge), Ok(64 * 1024)),
+            "Test MessageHTTP::decoded_body-7 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_host() {
+        let message = MessageHTTP::from("GET / HTTP/1.1\r\nHost: [::1]:8080\r\n\r\n").unwrap();
+        assert_eq!(
+            (message.host(), message.validate_host()),
+            (Ok(Some((String::from("[::1]"), Some(8080)))), Ok(())),
+            "Test MessageHTTP::host-1 failed."
+        );
+        
+        let message = MessageHTTP::from("GET / HTTP/1.1\r\nhost: Example.com\r\n\r\n").unwrap();
+        assert_eq!(
+            (message.host(), message.validate_host()),
+            (Ok(Some((String::from("example.com"), None))), Ok(())),
+            "Test MessageHTTP::host-2 failed."
+        );
+        
+        let message = MessageHTTP::from("GET / HTTP/1.1\r\nHost: a.com\r\nHost: b.com\r\n\r\n").unwrap();
+        assert_eq!(
+            (message.host(), message.validate_host()),
+            (Err(ParseError::DuplicateHost), Err(ParseError::DuplicateHost)),
+            "Test MessageHTTP::host-3 failed."
+        );
+        
+        let message = MessageHTTP::from("GET / HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(
+            (message.host(), message.validate_host()),
+            (Ok(None), Err(ParseError::MissingHost)),
+            "Test MessageHTTP::host-4 failed."
+        );
+        
+        let message = MessageHTTP::from("GET / HTTP/1.1\r\nHost: a b\r\n\r\n").unwrap();
+        assert_eq!(
+            message.validate_host(),
+            Err(ParseError::InvalidHost(String::from("a b"))),
+            "Test MessageHTTP::host-5 failed."
+        );
+        
+        assert_eq!(
+            (MessageHTTP::from("GET / HTTP/1.0\r\n\r\n").unwrap().validate_host(), MessageHTTP::from("HTTP/1.1 200 OK\r\n\r\n").unwrap().validate_host()),
+            (Ok(()), Ok(())),
+            "Test MessageHTTP::host-6 failed."
+        );
+        
+        let message = MessageHTTP::from("GET http://Proxy.example:81/a HTTP/1.1\r\nHost: ignored.com\r\n\r\n").unwrap();
+        assert_eq!(
+            message.host(),
+            Ok(Some((String::from("proxy.example"), Some(81)))),
+            "Test MessageHTTP::host-7 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_set_date_now() {
+        let mut message = MessageHTTP::from("HTTP/1.1 200 OK\r\nDate: Sun, 06 Nov 1994 08:49:37 GMT\r\n\r\n").unwrap();
+        
+        message.set_date_now();
+        let time = ::http::date::parse(message.header("Date").unwrap()).unwrap();
+        assert!(
+            SystemTime::now().duration_since(time).unwrap().as_secs() < 60 && message.headers("Date").count() == 1,
+            "Test MessageHTTP::set_date_now-1 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_wants_keep_alive() {
+        let cases = [
+            ("GET / HTTP/1.1\r\n\r\n", true),
+            ("GET / HTTP/1.1\r\nConnection: close\r\n\r\n", false),
+            ("GET / HTTP/1.1\r\nConnection: Upgrade, CLOSE\r\n\r\n", false),
+            ("GET / HTTP/1.1\r\nConnection: keep-alive, Upgrade\r\n\r\n", true),
+            ("GET / HTTP/1.0\r\n\r\n", false),
+            ("GET / HTTP/1.0\r\nConnection: Keep-Alive\r\n\r\n", true),
+            ("GET / HTTP/1.0\r\nConnection: keep-alive, Upgrade\r\n\r\n", true),
+            ("GET / HTTP/1.0\r\nConnection: Upgrade\r\nConnection: keep-alive\r\n\r\n", true),
+            ("GET / HTTP/1.0\r\nConnection: keep-alive, close\r\n\r\n", false),
+            ("HTTP/1.0 200 OK\r\n\r\n", false)
+        ];
+        for (i, &(msg, keep_alive)) in cases.iter().enumerate() {
+            assert_eq!(
+                MessageHTTP::from(msg).unwrap().wants_keep_alive(),
+                keep_alive,
+                "Test MessageHTTP::wants_keep_alive-{} failed.", i + 1
+            );
+        }
+        
+        let mut response = MessageHTTP::from("HTTP/1.1 200 OK\r\nConnection: keep-alive\r\n\r\n").unwrap();
+        response.set_connection_close();
+        assert_eq!(
+            response.headers("Connection").collect::<Vec<&str>>(),
+            vec!["close"],
+            "Test MessageHTTP::set_connection_close-1 failed."
+        );
+        assert!(
+            !response.wants_keep_alive(),
+            "Test MessageHTTP::set_connection_close-2 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_cookies() {
+        let message = MessageHTTP::from("GET / HTTP/1.1\r\nCookie: a=1; theme=dark\r\nHost: localhost\r\ncookie: a=2\r\n\r\n").unwrap();
+        
+        assert_eq!(
+            message.cookies(),
+            vec![
+                Cookie { name: String::from("a"), value: String::from("1") },
+                Cookie { name: String::from("theme"), value: String::from("dark") },
+                Cookie { name: String::from("a"), value: String::from("2") }
+            ],
+            "Test MessageHTTP::cookies-1 failed."
+        );
+        
+        assert_eq!(
+            MessageHTTP::from("GET / HTTP/1.1\r\n\r\n").unwrap().cookies(),
+            Vec::new(),
+            "Test MessageHTTP::cookies-2 failed."
+        );
+        
+        let mut message = MessageHTTP::from("HTTP/1.1 200 OK\r\n\r\n").unwrap();
+        message.add_cookie(SetCookie::new("a", "1")).unwrap();
+        message.add_cookie(SetCookie::new("b", "2").http_only(true)).unwrap();
+        assert_eq!(
+            message.headers("Set-Cookie").collect::<Vec<&str>>(),
+            vec!["a=1", "b=2; HttpOnly"],
+            "Test MessageHTTP::add_cookie-1 failed."
+        );
+        assert!(
+            message.add_cookie(SetCookie::new("c", "\r\nLocation: evil")).is_err(),
+            "Test MessageHTTP::add_cookie-2 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_to_bytes() {
+        let mut message = MessageHTTP::from("HTTP/1.1 200 OK\r\nContent-Type: image/png\r\n\r\n").unwrap();
+        let head = "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: 6\r\n\r\n";
+        
+        message.message_body = vec![0x89, 0xFF, 0x00, 0xFF, b'\r', b'\n'];
+        let bytes = message.to_bytes().unwrap();
+        
+        assert_eq!(
+            &bytes[..head.len()],
+            head.as_bytes(),
+            "Test MessageHTTP::to_bytes-1 failed."
+        );
+        assert_eq!(
+            &bytes[head.len()..],
+            &[0x89, 0xFF, 0x00, 0xFF, b'\r', b'\n'],
+            "Test MessageHTTP::to_bytes-2 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_to_bytes_mismatched_length() {
+        let mut message = MessageHTTP::from("HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello").unwrap();
+        
+        message.message_body.truncate(4);
+        assert!(
+            message.to_bytes().is_err(),
+            "Test MessageHTTP::to_bytes-3 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_strip_body_for_status() {
+        for &code in [100, 101, 204, 304].iter() {
+            let head = format!("HTTP/1.1 {}\r\nETag: \"a\"\r\n\r\n", code);
+            let mut message = MessageHTTP::from(&head).unwrap();
+            
+            message.add_header("Content-Length", "5").unwrap();
+            message.add_header("Transfer-Encoding", "chunked").unwrap();
+            message.message_body = b"hello".to_vec();
+            assert!(
+                message.to_http().is_err(),
+                "Test MessageHTTP::to_http-no-body failed for `{}`.", code
+            );
+            message.strip_body_for_status();
+            assert_eq!(
+                message,
+                MessageHTTP::from(&head).unwrap(),
+                "Test MessageHTTP::strip_body_for_status-1 failed for `{}`.", code
+            );
+        }
+        
+        let mut message = MessageHTTP::from("HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello").unwrap();
+        let original = message.clone();
+        message.strip_body_for_status();
+        assert_eq!(message, original, "Test MessageHTTP::strip_body_for_status-2 failed.");
+    }
+    #[test]
+    fn test_message_http_framing() {
+        let mut message = MessageHTTP::from("HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello").unwrap();
+        let mut res = Vec::new();
+        
+        message.message_body.truncate(4);
+        assert!(message.to_http().is_err(), "Test MessageHTTP::to_http-framing-1 failed.");
+        assert_eq!(
+            message.write_to(&mut res).map_err(|e| e.kind()),
+            Err(io::ErrorKind::InvalidData),
+            "Test MessageHTTP::write_to-framing-1 failed."
+        );
+        assert!(res.is_empty(), "Test MessageHTTP::write_to-framing-2 failed.");
+        
+        message.write_unframed_to(&mut res).unwrap();
+        assert_eq!(
+            res,
+            b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhell".to_vec(),
+            "Test MessageHTTP::write_unframed_to-1 failed."
+        );
+        
+        let message = MessageHTTP::from("HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n0\r\n\r\n").unwrap();
+        assert_eq!(
+            message.to_http().unwrap(),
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n0\r\n\r\n",
+            "Test MessageHTTP::to_http-framing-1 failed."
+        );
+        
+        let mut message = MessageHTTP::from("POST /upload HTTP/1.1\r\nHost: example.com\r\n\r\n").unwrap();
+        let mut res = Vec::new();
+        
+        message.message_body = b"abc".to_vec();
+        message.write_to(&mut res).unwrap();
+        assert_eq!(
+            res,
+            b"POST /upload HTTP/1.1\r\nHost: example.com\r\nContent-Length: 3\r\n\r\nabc".to_vec(),
+            "Test MessageHTTP::write_to-framing-3 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_from_with_limits() {
+        let limits = ParseLimits {
+            max_start_line: 20,
+            max_header_count: 2,
+            max_header_bytes: 64,
+            max_body_bytes: 5
+        };
+        // The start line is exactly 20 bytes, the header section exactly 64 bytes.
+        let msg = "POST /abcde HTTP/1.1\r\nHost: example.co.uk\r\nContent-Length: 5\r\n\r\nhello";
+        
+        assert_eq!(
+            MessageHTTP::from_with_limits(msg, limits),
+            MessageHTTP::from(msg),
+            "Test MessageHTTP::from_with_limits-1 failed."
+        );
+        assert!(
+            MessageHTTP::from_bytes_with_limits(msg.as_bytes(), limits).is_ok(),
+            "Test MessageHTTP::from_with_limits-2 failed."
+        );
+        
+        // Each message passes one limit by a single byte or Header field.
+        let tests = [
+            (msg.replacen("/abcde", "/abcdef", 1), limits, ParseError::UriTooLong { limit: 20, size: 21 }),
+            (msg.replacen("\r\n\r\n", "\r\nA: b\r\n\r\n", 1), ParseLimits { max_header_bytes: 70, ..limits }, ParseError::TooManyHeaderFields { limit: 2, count: 3 }),
+            (msg.replacen("example.co.uk", "example.com.au", 1), limits, ParseError::HeaderTooLarge { limit: 64, size: 65 }),
+            (msg.replacen("Length: 5", "Length: 6", 1).replacen("hello", "hello!", 1), limits, ParseError::BodyTooLarge)
+        ];
+        for &(ref msg, limits, ref error) in tests.iter() {
+            assert_eq!(
+                MessageHTTP::from_with_limits(msg, limits).as_ref(),
+                Err(error),
+                "Test MessageHTTP::from_with_limits-3 failed for `{}`.", msg
+            );
+            assert_eq!(
+                MessageHTTP::from_bytes_with_limits(msg.as_bytes(), limits).as_ref(),
+                Err(error),
+                "Test MessageHTTP::from_bytes_with_limits-1 failed for `{}`.", msg
+            );
+        }
+    }
+    #[test]
+    fn test_message_http_http2_preface() {
+        assert_eq!(
+            (MessageHTTP::from_bytes(HTTP2_PREFACE), MessageHTTP::from("PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n")),
+            (Err(ParseError::Http2Preface), Err(ParseError::Http2Preface)),
+            "Test MessageHTTP HTTP/2 preface-1 failed."
+        );
+        assert_eq!(
+            MessageHTTP::from("PRI /other HTTP/1.1\r\n\r\n").map(|message| message.start_line.method().cloned()),
+            Ok(Some(Method::Extension(String::from("PRI")))),
+            "Test MessageHTTP HTTP/2 preface-2 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_simple_request() {
+        let request = MessageHTTP::from_head_with_http09(b"GET /index.html\r\n", ParseMode::Strict, true).unwrap();
+        assert_eq!(
+            (request.start_line.version(), request.header_fields.len()),
+            (Version::HTTP_09, 0),
+            "Test MessageHTTP simple-request-1 failed."
+        );
+        assert_eq!(
+            (MessageHTTP::from("GET /index.html\r\n"), MessageHTTP::from_with_mode("GET /index.html\r\n", ParseMode::Strict)),
+            (Err(ParseError::MissingVersion), Err(ParseError::MissingVersion)),
+            "Test MessageHTTP simple-request-2 failed."
+        );
+        
+        let mut response = MessageHTTP::from("HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n\r\n<p>hi</p>").unwrap();
+        response.start_line = StartLine::StatusLine { version: Version::HTTP_09, code: StatusCode::OK, reason: None };
+        let mut bytes = Vec::new();
+        response.write_to(&mut bytes).unwrap();
+        assert_eq!(
+            (response.is_simple_response(), response.to_bytes().unwrap(), bytes),
+            (true, b"<p>hi</p>".to_vec(), b"<p>hi</p>".to_vec()),
+            "Test MessageHTTP simple-request-3 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_write_to() {
+        /// A writer which accepts at most three bytes per call.
+        struct ThreeBytes(Vec<u8>);
+        
+        impl Write for ThreeBytes {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                let len = buf.len().min(3);
+                
+                self.0.extend_from_slice(&buf[..len]);
+                Ok(len)
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        
+        let mut message = MessageHTTP::from("HTTP/1.1 200 OK\r\nContent-Type: image/png\r\n\r\n").unwrap();
+        message.message_body = vec![0x89, 0xFF, 0x00, 0xFF, b'\r', b'\n'];
+        
+        let mut res = Vec::new();
+        message.write_to(&mut res).unwrap();
+        assert_eq!(
+            res,
+            message.to_bytes().unwrap(),
+            "Test MessageHTTP::write_to-1 failed."
+        );
+        
+        let mut res = ThreeBytes(Vec::new());
+        message.write_to(&mut res).unwrap();
+        assert_eq!(
+            res.0,
+            message.to_bytes().unwrap(),
+            "Test MessageHTTP::write_to-2 failed."
+        );
+        
+        let mut res = Vec::new();
+        MessageHTTP::from("GET / HTTP/1.1\r\nHost: example.com\r\n\r\n").unwrap().write_to(&mut res).unwrap();
+        assert_eq!(
+            res,
+            b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n".to_vec(),
+            "Test MessageHTTP::write_to-3 failed."
+        );
+    }
+    #[test]
+    fn test_message_http_write_response_to() {
+        let response = MessageHTTP::from("HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello").unwrap();
+        let head = b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\n";
+        
+        for &(ref method, body) in [(Method::Get, true), (Method::Post, true), (Method::Head, false), (Method::Connect, false)].iter() {
+            let mut res = Vec::new();
+            
+            response.write_response_to(&mut res, method).unwrap();
+            assert_eq!(
+                res,
+                if body { response.to_bytes().unwrap() } else { head.to_vec() },
+                "Test MessageHTTP::write_response_to-1 failed for `{}`.", method
+            );
+        }
+        
+        let mut response = MessageHTTP::from("HTTP/1.1 304 Not Modified\r\nETag: \"a\"\r\n\r\n").unwrap();
+        response.message_body = b"stale".to_vec();
+        let (mut written, mut responded) = (Vec::new(), Vec::new());
+        assert_eq!(
+            response.write_to(&mut written).map_err(|e| e.kind()),
+            Err(io::ErrorKind::InvalidData),
+            "Test MessageHTTP::write_response_to-stale-1 failed."
+        );
+        response.strip_body_for_status();
+        response.write_to(&mut written).unwrap();
+        response.write_response_to(&mut responded, &Method::Get).unwrap();
+        assert_eq!(
+            (written.clone(), responded),
+            (b"HTTP/1.1 304 Not Modified\r\nETag: \"a\"\r\n\r\n".to_vec(), written),
+            "Test MessageHTTP::write_response_to-2 failed."
+        );
+        
+        let mut response = MessageHTTP::from("HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\n\r\n").unwrap();
+        let head = b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 5\r\n\r\n";
+        let mut res = Vec::new();
+        
+        response.message_body = b"hello".to_vec();
+        response.write_response_to(&mut res, &Method::Head).unwrap();
+        assert_eq!(res, head.to_vec(), "Test MessageHTTP::write_response_to-3 failed.");
+        
+        let mut res = Vec::new();
+        response.message_body.clear();
+        response.add_header("Content-Length", "5").unwrap();
+        response.write_response_to(&mut res, &Method::Head).unwrap();
+        assert_eq!(res, head.to_vec(), "Test MessageHTTP::write_response_to-4 failed.");
     }
 }
@@ -0,0 +1,280 @@
+//! `method` is a module to handle the method of a HTTP request.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::fmt;
+use std::string::String;
+use std::str::FromStr;
+use super::{ParseError, StatusCode};
+use super::token;
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+/// A `Method` is the action a request asks the recipient to take.
+///
+/// Methods which are not recognised are kept as an `Extension` so that a server can
+/// answer `501 Not Implemented` rather than failing to parse the whole request.
+pub enum Method {
+    /// `GET`
+    Get,
+    /// `HEAD`
+    Head,
+    /// `POST`
+    Post,
+    /// `PUT`
+    Put,
+    /// `DELETE`
+    Delete,
+    /// `OPTIONS`
+    Options,
+    /// `PATCH`
+    Patch,
+    /// `TRACE`
+    Trace,
+    /// `CONNECT`
+    Connect,
+    /// Any other method, as it was sent.
+    Extension(String)
+}
+
+impl Method {
+    /// Every `Method` recognised by the crate, the methods defined by RFC 7231 along with
+    /// `PATCH` from RFC 5789.
+    pub const ALL: [Method; 9] = [
+        Method::Get, Method::Head, Method::Post, Method::Put, Method::Delete,
+        Method::Connect, Method::Options, Method::Trace, Method::Patch
+    ];
+    /// Converts the passed `str` to a `Method`.
+    ///
+    /// # Params
+    ///
+    /// method --- The `str` to convert to a `Method`.
+    pub fn from(method: &str) -> Result<Method, ParseError> {
+        method.parse()
+    }
+    /// Returns the name of the `Method`, e.g. `GET`.
+    pub fn as_str(&self) -> &str {
+        match *self {
+            Method::Get => "GET",
+            Method::Head => "HEAD",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Delete => "DELETE",
+            Method::Options => "OPTIONS",
+            Method::Patch => "PATCH",
+            Method::Trace => "TRACE",
+            Method::Connect => "CONNECT",
+            Method::Extension(ref method) => method
+        }
+    }
+    /// Returns `true` if the `Method` is one of those recognised by the crate rather than
+    /// an `Extension`, e.g. to answer `501 Not Implemented` otherwise.
+    pub fn is_recognized(&self) -> bool {
+        !matches!(*self, Method::Extension(_))
+    }
+    /// Returns `true` if the `Method` is safe, as in RFC 7231 section 4.2.1, so a request
+    /// with it only retrieves and never changes anything on the server.
+    ///
+    /// An `Extension` is never assumed to be safe.
+    pub fn is_safe(&self) -> bool {
+        matches!(*self, Method::Get | Method::Head | Method::Options | Method::Trace)
+    }
+    /// Returns `true` if the `Method` is idempotent, as in RFC 7231 section 4.2.2, so a
+    /// request with it can be repeated, e.g. after a connection fails.
+    ///
+    /// Every safe `Method` is idempotent, an `Extension` is never assumed to be.
+    pub fn is_idempotent(&self) -> bool {
+        self.is_safe() || matches!(*self, Method::Put | Method::Delete)
+    }
+    /// Returns `true` if a response to the `Method` may be stored by a cache, as in RFC
+    /// 7231 section 4.2.3.
+    ///
+    /// A response to a `POST` is only cacheable with explicit freshness information and a
+    /// `Content-Location`, which the caller must check.
+    pub fn is_cacheable(&self) -> bool {
+        matches!(*self, Method::Get | Method::Head | Method::Post)
+    }
+    /// Returns `true` if a request with the `Method` may have a body.
+    ///
+    /// Only a `TRACE` request must not have a body, as in RFC 7231 section 4.3.8.
+    pub fn request_body_allowed(&self) -> bool {
+        *self != Method::Trace
+    }
+    /// Returns `true` if a response with the passed status code to a request with the
+    /// `Method` may have a body.
+    ///
+    /// A response to a `HEAD` request, a `2xx` response to a `CONNECT` request and a `1xx`,
+    /// `204 No Content` or `304 Not Modified` response never have a body, as in RFC 7230
+    /// section 3.3.
+    ///
+    /// # Params
+    ///
+    /// status --- The status code of the response.
+    pub fn response_body_allowed(&self, status: StatusCode) -> bool {
+        status.allows_body()
+            && *self != Method::Head
+            && !(*self == Method::Connect && status.is_success())
+    }
+}
+
+impl FromStr for Method {
+    type Err = ParseError;
+    
+    /// Converts the passed `str` to a `Method`.
+    ///
+    /// The methods of RFC 7231 and RFC 5789 are recognised regardless of case, any other
+    /// token becomes an `Extension`.</br>
+    /// Fails if the `str` is not a token.
+    ///
+    /// # Params
+    ///
+    /// method --- The `str` to convert to a `Method`.
+    fn from_str(method: &str) -> Result<Method, ParseError> {
+        if !token::is_token(method) {
+            return Err(ParseError::InvalidMethod(String::from(method)));
+        }
+        
+        Ok(
+            match method.to_uppercase().as_str() {
+                "GET" => Method::Get,
+                "HEAD" => Method::Head,
+                "POST" => Method::Post,
+                "PUT" => Method::Put,
+                "DELETE" => Method::Delete,
+                "OPTIONS" => Method::Options,
+                "PATCH" => Method::Patch,
+                "TRACE" => Method::Trace,
+                "CONNECT" => Method::Connect,
+                _ => Method::Extension(String::from(method))
+            }
+        )
+    }
+}
+
+impl fmt::Display for Method {
+    /// Writes the name of the `Method`, e.g. `GET`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_method_properties() {
+        // The safe, idempotent, cacheable and request body properties of each method.
+        let tests = [
+            ("GET", [true, true, true, true]),
+            ("HEAD", [true, true, true, true]),
+            ("POST", [false, false, true, true]),
+            ("PUT", [false, true, false, true]),
+            ("DELETE", [false, true, false, true]),
+            ("CONNECT", [false, false, false, true]),
+            ("OPTIONS", [true, true, false, true]),
+            ("TRACE", [true, true, false, false]),
+            ("PATCH", [false, false, false, true]),
+            ("BREW", [false, false, false, true])
+        ];
+        
+        for &(name, expected) in tests.iter() {
+            let method = Method::from(name).unwrap();
+            
+            assert_eq!(
+                [method.is_safe(), method.is_idempotent(), method.is_cacheable(), method.request_body_allowed()],
+                expected,
+                "Test Method properties-1 failed for `{}`.", name
+            );
+        }
+        assert_eq!(
+            tests.len(),
+            Method::ALL.len() + 1,
+            "Test Method properties-2 failed, not every method is tested."
+        );
+    }
+    #[test]
+    fn test_method_response_body_allowed() {
+        let statuses = [100, 101, 200, 204, 206, 304, 404, 500];
+        let tests = [
+            ("GET", [false, false, true, false, true, false, true, true]),
+            ("HEAD", [false; 8]),
+            ("POST", [false, false, true, false, true, false, true, true]),
+            ("PUT", [false, false, true, false, true, false, true, true]),
+            ("DELETE", [false, false, true, false, true, false, true, true]),
+            ("CONNECT", [false, false, false, false, false, false, true, true]),
+            ("OPTIONS", [false, false, true, false, true, false, true, true]),
+            ("TRACE", [false, false, true, false, true, false, true, true]),
+            ("PATCH", [false, false, true, false, true, false, true, true]),
+            ("BREW", [false, false, true, false, true, false, true, true])
+        ];
+        
+        for &(name, expected) in tests.iter() {
+            let method = Method::from(name).unwrap();
+            let mut allowed = [false; 8];
+            
+            for (allowed, &status) in allowed.iter_mut().zip(statuses.iter()) {
+                *allowed = method.response_body_allowed(StatusCode(status));
+            }
+            assert_eq!(
+                allowed,
+                expected,
+                "Test Method::response_body_allowed-1 failed for `{}`.", name
+            );
+        }
+    }
+    #[test]
+    #[allow(deprecated)]
+    fn test_method_all() {
+        assert_eq!(
+            Method::ALL.iter().map(Method::as_str).collect::<Vec<_>>(),
+            super::super::HTTP_METHOD.to_vec(),
+            "Test Method::ALL-1 failed."
+        );
+    }
+    #[test]
+    fn test_method() {
+        for method in Method::ALL.iter() {
+            assert_eq!(
+                Method::from(&method.as_str().to_lowercase()).as_ref(),
+                Ok(method),
+                "Test Method::from-1 failed for `{}`.", method
+            );
+        }
+        assert_eq!(
+            Method::from("get"),
+            Ok(Method::Get),
+            "Test Method::from-2 failed."
+        );
+        assert_eq!(
+            "BREW".parse::<Method>(),
+            Ok(Method::Extension(String::from("BREW"))),
+            "Test Method::from-3 failed."
+        );
+        for &method in ["", "GE T", "GET/", "(GET)"].iter() {
+            assert_eq!(
+                Method::from(method),
+                Err(ParseError::InvalidMethod(String::from(method))),
+                "Test Method::from-4 failed for `{}`.", method
+            );
+        }
+        
+        assert!(
+            Method::Patch.is_recognized() && !Method::from("BREW").unwrap().is_recognized(),
+            "Test Method::is_recognized-1 failed."
+        );
+        
+        assert_eq!(
+            Method::Extension(String::from("PROPFIND")).to_string(),
+            "PROPFIND",
+            "Test Method::fmt-1 failed."
+        );
+        assert_eq!(
+            Method::Options.to_string(),
+            "OPTIONS",
+            "Test Method::fmt-2 failed."
+        );
+    }
+}
@@ -0,0 +1,103 @@
+//! `mime` is a module to guess the media type of a file from its extension.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::path::Path;
+
+/// The media type of a file whose extension is not recognised.
+pub static OCTET_STREAM: &str = "application/octet-stream";
+
+/// The media types of the file extensions commonly served by a web server.
+static TYPES: [(&str, &str); 22] = [
+    ("html", "text/html; charset=utf-8"),
+    ("htm", "text/html; charset=utf-8"),
+    ("css", "text/css; charset=utf-8"),
+    ("js", "text/javascript; charset=utf-8"),
+    ("mjs", "text/javascript; charset=utf-8"),
+    ("txt", "text/plain; charset=utf-8"),
+    ("csv", "text/csv; charset=utf-8"),
+    ("json", "application/json"),
+    ("xml", "application/xml"),
+    ("pdf", "application/pdf"),
+    ("wasm", "application/wasm"),
+    ("zip", "application/zip"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("svg", "image/svg+xml"),
+    ("ico", "image/x-icon"),
+    ("webp", "image/webp"),
+    ("woff", "font/woff"),
+    ("woff2", "font/woff2"),
+    ("mp4", "video/mp4")
+];
+
+/// Returns the media type of the passed file extension, compared ignoring case.</br>
+/// Returns `None` if the extension is not recognised.
+///
+/// # Params
+///
+/// extension --- The extension without its `.`, e.g. `html`.
+pub fn from_extension(extension: &str) -> Option<&'static str> {
+    TYPES.iter()
+        .find(|&&(ext, _)| ext.eq_ignore_ascii_case(extension))
+        .map(|&(_, media_type)| media_type)
+}
+
+/// Returns the media type of the file at the passed path from its extension, or
+/// `application/octet-stream` if it has none or it is not recognised.
+///
+/// # Params
+///
+/// path --- The path of the file.
+pub fn from_path<P: AsRef<Path>>(path: P) -> &'static str {
+    path.as_ref().extension()
+        .and_then(|extension| extension.to_str())
+        .and_then(from_extension)
+        .unwrap_or(OCTET_STREAM)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_extension() {
+        let tests = [
+            ("html", Some("text/html; charset=utf-8")),
+            ("CSS", Some("text/css; charset=utf-8")),
+            ("Png", Some("image/png")),
+            ("exe", None),
+            ("", None)
+        ];
+        for &(extension, media_type) in tests.iter() {
+            assert_eq!(
+                from_extension(extension),
+                media_type,
+                "Test mime::from_extension failed for `{}`.", extension
+            );
+        }
+    }
+    #[test]
+    fn test_from_path() {
+        let tests = [
+            ("index.html", "text/html; charset=utf-8"),
+            ("css/site.min.css", "text/css; charset=utf-8"),
+            ("logo.PNG", "image/png"),
+            ("Makefile", OCTET_STREAM),
+            (".html", OCTET_STREAM),
+            ("archive.tar.gz", OCTET_STREAM)
+        ];
+        for &(path, media_type) in tests.iter() {
+            assert_eq!(
+                from_path(path),
+                media_type,
+                "Test mime::from_path failed for `{}`.", path
+            );
+        }
+    }
+}
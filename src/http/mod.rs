@@ -6,14 +6,61 @@
 //! Date --- 06/09/2017
 
 mod message;
+mod error;
+mod builder;
+mod status;
+mod version;
+mod method;
+mod header_map;
+mod body;
+mod read;
+mod base64;
+#[cfg(feature = "serde")]
+mod serialize;
 pub mod start_line;
 pub mod header_field;
+pub mod headers;
+pub mod chunked;
+pub mod percent;
+pub mod cookie;
+pub mod date;
+pub mod multipart;
+pub mod negotiate;
+pub mod range;
+pub mod etag;
+pub mod conditional;
+pub mod auth;
+pub mod host;
+pub mod target;
+pub mod responses;
+pub mod path;
+pub mod uri;
+pub mod cache;
+pub mod disposition;
+pub mod cors;
+pub mod forwarded;
+pub mod token;
+pub mod parser;
+pub mod mime;
 
 pub use std::string::String;
 pub use self::message::*;
+pub use self::error::*;
+pub use self::builder::*;
+pub use self::status::*;
+pub use self::version::*;
+pub use self::method::*;
+pub use self::header_map::*;
+pub use self::body::*;
+pub use self::read::*;
 
 /// The methods recognised by a [`MessageHTTP`](struct.MessageHTTP.html).
-pub static HTTP_METHOD: [&'static str; 1] = ["GET"];
+///
+/// These are the methods defined by RFC 7231 along with `PATCH` from RFC 5789.
+#[deprecated(since = "0.2.0", note = "use `Method::ALL` instead")]
+pub static HTTP_METHOD: [&str; 9] = [
+    "GET", "HEAD", "POST", "PUT", "DELETE", "CONNECT", "OPTIONS", "TRACE", "PATCH"
+];
 
 #[derive(Debug)]
 /// Denotes that there was an error when converting an object to its HTTP string.
@@ -0,0 +1,257 @@
+//! `multipart` is a module to handle `multipart/form-data` message bodies, such as file uploads.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::string::String;
+use std::str;
+use super::{MessageHTTP, ParseError};
+use super::headers;
+use super::header_field::HeaderField;
+use super::disposition::ContentDisposition;
+use super::token;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// A `Part` is a single field of a `multipart/form-data` body.
+pub struct Part {
+    /// The `name` of the form field, from the `Content-Disposition`.
+    pub name: String,
+    /// The `filename` of an uploaded file, from the `Content-Disposition`.
+    pub filename: Option<String>,
+    /// The `Content-Disposition` of the part, or `None` if it could not be parsed.
+    pub disposition: Option<ContentDisposition>,
+    /// The `Content-Type` of the part, if one was sent.
+    pub content_type: Option<String>,
+    /// The raw bytes of the part.
+    pub data: Vec<u8>
+}
+
+/// Returns the `Part`s of a `multipart/form-data` message body in order.
+///
+/// Any preamble before the first boundary and epilogue after the closing boundary is
+/// ignored.</br>
+/// Fails if the `Content-Type` is not `multipart/form-data`, it has no `boundary`
+/// parameter, or the body is malformed.
+///
+/// # Params
+///
+/// message --- The message to get the `Part`s of.
+pub fn parse(message: &MessageHTTP) -> Result<Vec<Part>, ParseError> {
+    let content_type = message.header(headers::CONTENT_TYPE).unwrap_or_default();
+    if !content_type.split(';').next().unwrap_or_default().trim().eq_ignore_ascii_case("multipart/form-data") {
+        return Err(ParseError::UnexpectedContentType(String::from(content_type)));
+    }
+    let boundary = match token::parameters(content_type).into_iter().find(|param| param.0 == "boundary") {
+        Some((_, ref boundary)) if !boundary.is_empty() => format!("--{}", boundary),
+        _ => return Err(ParseError::MissingBoundary)
+    };
+    let body = &message.message_body[..];
+    // Every delimiter after the first begins on a new line, the line break belongs to the delimiter.
+    let delimiter = format!("\r\n{}", boundary).into_bytes();
+    
+    // The first boundary is either at the start of the body or follows the preamble.
+    let mut position = if body.starts_with(boundary.as_bytes()) {
+        boundary.len()
+    } else {
+        match find(body, &delimiter) {
+            Some(index) => index + delimiter.len(),
+            None => return Err(ParseError::InvalidMultipart(String::from("missing boundary")))
+        }
+    };
+    let mut parts = Vec::new();
+    
+    loop {
+        // The closing boundary is followed by `--` and then the ignored epilogue.
+        if body[position..].starts_with(b"--") {
+            return Ok(parts);
+        }
+        // A boundary may be followed by whitespace before the line break.
+        while body.get(position) == Some(&b' ') || body.get(position) == Some(&b'\t') {
+            position += 1;
+        }
+        if !body[position..].starts_with(b"\r\n") {
+            return Err(ParseError::InvalidMultipart(String::from("missing CRLF after boundary")));
+        }
+        position += 2;
+        
+        let end = match find(&body[position..], &delimiter) {
+            Some(index) => position + index,
+            None => return Err(ParseError::InvalidMultipart(String::from("missing closing boundary")))
+        };
+        parts.push(parse_part(&body[position..end])?);
+        position = end + delimiter.len();
+    }
+}
+
+/// Converts the bytes between two boundaries to a `Part`.
+///
+/// # Params
+///
+/// part --- The headers and data of the part.
+fn parse_part(part: &[u8]) -> Result<Part, ParseError> {
+    // The headers end at the first empty line, a part without headers starts with it.
+    let (head, data) = if part.starts_with(b"\r\n") {
+        (&part[..0], &part[2..])
+    } else {
+        match find(part, b"\r\n\r\n") {
+            Some(index) => (&part[..index], &part[index + 4..]),
+            None => return Err(ParseError::InvalidMultipart(String::from("part missing empty line after headers")))
+        }
+    };
+    let head = match str::from_utf8(head) {
+        Ok(head) => head,
+        Err(_) => return Err(ParseError::InvalidUtf8)
+    };
+    
+    let mut name = None;
+    let mut filename = None;
+    let mut disposition = None;
+    let mut content_type = None;
+    for field in head.split("\r\n").filter(|line| !line.is_empty()).map(HeaderField::from) {
+        let field = field?;
+        
+        if field.name_eq(headers::CONTENT_DISPOSITION) {
+            disposition = ContentDisposition::from(&field.value).ok();
+            match disposition {
+                Some(ref disposition) => {
+                    name = disposition.name.clone();
+                    filename = disposition.filename.clone();
+                },
+                // A malformed disposition type does not cost the part its parameters.
+                None => for (param, value) in token::parameters(&field.value) {
+                    match param.as_str() {
+                        "name" => name = Some(value),
+                        "filename" => filename = Some(value),
+                        _ => ()
+                    }
+                }
+            }
+        } else if field.name_eq(headers::CONTENT_TYPE) {
+            content_type = Some(field.value);
+        }
+    }
+    
+    match name {
+        Some(name) => Ok(
+            Part {
+                name,
+                filename,
+                disposition,
+                content_type,
+                data: data.to_vec()
+            }
+        ),
+        None => Err(ParseError::InvalidMultipart(format!("part missing name: `{}`", head)))
+    }
+}
+
+/// Returns the index of the first occurrence of `needle` in `haystack`.
+///
+/// # Params
+///
+/// haystack --- The bytes to search.</br>
+/// needle --- The bytes to search for.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Returns a message with the passed `Content-Type` and body.
+    fn message(content_type: &str, body: &[u8]) -> MessageHTTP {
+        let mut message = MessageHTTP::from_bytes(b"POST /upload HTTP/1.1\r\n\r\n").unwrap();
+        
+        message.add_header("Content-Type", content_type).unwrap();
+        message.message_body = body.to_vec();
+        message
+    }
+
+    #[test]
+    fn test_parse() {
+        let mut body = b"This is the preamble.\r\n--AaB03x\r\n\
+            Content-Disposition: form-data; name=\"title\"\r\n\r\n\
+            Holiday\r\n\
+            --AaB03x  \r\n\
+            Content-Disposition: form-data; name=\"photo\"; filename=\"a \\\"b\\\";c.png\"\r\n\
+            Content-Type: image/png\r\n\r\n".to_vec();
+        body.extend_from_slice(&[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x00, 0xFF, b'-', b'-']);
+        body.extend_from_slice(b"\r\n--AaB03x--\r\nThis is the epilogue.");
+        
+        assert_eq!(
+            parse(&message("multipart/form-data; boundary=AaB03x", &body)).unwrap(),
+            vec![
+                Part {
+                    name: String::from("title"),
+                    filename: None,
+                    disposition: Some(ContentDisposition::new("form-data").name("title")),
+                    content_type: None,
+                    data: b"Holiday".to_vec()
+                },
+                Part {
+                    name: String::from("photo"),
+                    filename: Some(String::from("a \"b\";c.png")),
+                    disposition: Some(ContentDisposition::new("form-data").name("photo").filename("a \"b\";c.png")),
+                    content_type: Some(String::from("image/png")),
+                    data: vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x00, 0xFF, b'-', b'-']
+                }
+            ],
+            "Test multipart::parse-1 failed."
+        );
+        
+        assert_eq!(
+            parse(&message("Multipart/Form-Data; charset=utf-8; Boundary=\"a b\"", b"--a b\r\nContent-Disposition: form-data; name=x\r\n\r\n\r\n--a b--")).unwrap(),
+            vec![
+                Part {
+                    name: String::from("x"),
+                    filename: None,
+                    disposition: Some(ContentDisposition::new("form-data").name("x")),
+                    content_type: None,
+                    data: Vec::new()
+                }
+            ],
+            "Test multipart::parse-2 failed."
+        );
+        
+        assert_eq!(
+            parse(&message("multipart/form-data", &body)),
+            Err(ParseError::MissingBoundary),
+            "Test multipart::parse-3 failed."
+        );
+        
+        assert_eq!(
+            parse(&message("text/plain; boundary=AaB03x", &body)),
+            Err(ParseError::UnexpectedContentType(String::from("text/plain; boundary=AaB03x"))),
+            "Test multipart::parse-4 failed."
+        );
+        
+        assert_eq!(
+            parse(&message("multipart/form-data; boundary=AaB03x", b"--AaB03x\r\nContent-Disposition: form-data; name=x\r\n\r\ndata")),
+            Err(ParseError::InvalidMultipart(String::from("missing closing boundary"))),
+            "Test multipart::parse-5 failed."
+        );
+        
+        assert_eq!(
+            parse(&message("multipart/form-data; boundary=AaB03x", b"--AaB03x\r\nContent-Type: text/plain\r\n\r\ndata\r\n--AaB03x--")),
+            Err(ParseError::InvalidMultipart(String::from("part missing name: `Content-Type: text/plain`"))),
+            "Test multipart::parse-6 failed."
+        );
+        
+        assert_eq!(
+            parse(&message("multipart/form-data; boundary=AaB03x", b"--AaB03x\r\nContent-Disposition: form data; name=x; filename=\"a.txt\"\r\n\r\ndata\r\n--AaB03x--")),
+            Ok(vec![
+                Part {
+                    name: String::from("x"),
+                    filename: Some(String::from("a.txt")),
+                    disposition: None,
+                    content_type: None,
+                    data: b"data".to_vec()
+                }
+            ]),
+            "Test multipart::parse-7 failed."
+        );
+    }
+}
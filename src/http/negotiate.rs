@@ -0,0 +1,499 @@
+//! `negotiate` is a module to handle proactive content negotiation using the `Accept` family of Header Fields.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::cmp::Ordering;
+use std::iter;
+use std::string::String;
+use super::token;
+
+#[derive(Clone, PartialEq, Debug)]
+/// A `MediaRange` is a single entry of an `Accept` Header Field, e.g. `text/*;q=0.5`.
+pub struct MediaRange {
+    /// The lowercase top-level type, which may be `*`.
+    pub media_type: String,
+    /// The lowercase subtype, which may be `*`.
+    pub subtype: String,
+    /// The lowercase names and values of the parameters before the q-value.
+    pub parameters: Vec<(String, String)>,
+    /// The quality of the range from `0.0` to `1.0`, where `0.0` is not acceptable.
+    pub q: f32
+}
+
+impl MediaRange {
+    /// Returns `true` if the passed media type falls within this `MediaRange`.
+    ///
+    /// Every parameter of the range must also be a parameter of the media type.
+    ///
+    /// # Params
+    ///
+    /// media_type --- The media type to check, e.g. `text/html;level=1`.
+    pub fn matches(&self, media_type: &str) -> bool {
+        match parse_range(media_type) {
+            Some(other) => (self.media_type == "*" || self.media_type == other.media_type)
+                && (self.subtype == "*" || self.subtype == other.subtype)
+                && self.parameters.iter().all(|param| other.parameters.contains(param)),
+            None => false
+        }
+    }
+    /// Returns how specific the `MediaRange` is, a more specific range overrides a less
+    /// specific one.
+    fn specificity(&self) -> (bool, bool, usize) {
+        (self.media_type != "*", self.subtype != "*", self.parameters.len())
+    }
+}
+
+/// Returns the `MediaRange`s of an `Accept` Header Field, sorted by q-value and then
+/// by specificity, most preferred first.
+///
+/// Entries which are not of the form `type/subtype` and entries with a malformed
+/// q-value are skipped, a malformed q-value is never guessed at.
+///
+/// # Params
+///
+/// header --- The value of the `Accept` Header Field.
+pub fn parse_accept(header: &str) -> Vec<MediaRange> {
+    let mut res: Vec<MediaRange> = token::split_list(header).into_iter()
+        .filter_map(parse_range)
+        .collect();
+    
+    // The sort is stable so equally preferred ranges keep the order they were sent in.
+    res.sort_by(
+        |a, b| b.q.partial_cmp(&a.q).unwrap_or(Ordering::Equal)
+            .then_with(|| b.specificity().cmp(&a.specificity()))
+    );
+    res
+}
+
+/// Returns the most preferred of the `available` media types according to the `Accept`
+/// Header Field, or `None` if none of them are acceptable.
+///
+/// Each media type is given the q-value of the most specific range which matches it,
+/// ties go to the type listed first in `available`. An empty or missing `Accept`
+/// Header Field accepts anything.
+///
+/// # Params
+///
+/// available --- The media types which can be sent, in order of the server's preference.</br>
+/// accept --- The value of the `Accept` Header Field.
+pub fn best_match<'a>(available: &[&'a str], accept: &str) -> Option<&'a str> {
+    if accept.trim().is_empty() {
+        return available.first().cloned();
+    }
+    
+    let ranges = parse_accept(accept);
+    let mut best = None;
+    let mut best_q = 0.0;
+    for &media_type in available {
+        let mut matched: Option<&MediaRange> = None;
+        for range in ranges.iter().filter(|range| range.matches(media_type)) {
+            match matched {
+                Some(current) if current.specificity() >= range.specificity() => (),
+                _ => matched = Some(range)
+            }
+        }
+        
+        let q = matched.map(|range| range.q).unwrap_or_default();
+        if q > best_q {
+            best = Some(media_type);
+            best_q = q;
+        }
+    }
+    best
+}
+
+/// Returns the most preferred of the `supported` content codings according to the
+/// `Accept-Encoding` Header Field, or `None` if none of them are acceptable.
+///
+/// `identity` is always supported and is acceptable unless it is excluded by
+/// `identity;q=0` or by `*;q=0` without being listed, but it is preferred least when
+/// it is not listed. A coding with a q-value of `0` is not acceptable and `*` gives
+/// its q-value to every coding which is not listed. An empty or missing
+/// `Accept-Encoding` Header Field only accepts `identity`.</br>
+/// Ties go to the coding listed first in `supported`.
+///
+/// # Params
+///
+/// header --- The value of the `Accept-Encoding` Header Field.</br>
+/// supported --- The content codings which can be applied, in order of the server's preference.
+pub fn preferred_encoding(header: &str, supported: &[&str]) -> Option<String> {
+    let codings: Vec<(String, f32)> = token::split_list(header).into_iter()
+        .filter_map(parse_weighted)
+        .collect();
+    let quality = |coding: &str| codings.iter()
+        .find(|entry| entry.0.eq_ignore_ascii_case(coding))
+        .map(|entry| entry.1);
+    let wildcard = quality("*");
+    
+    let mut best = None;
+    let mut best_key = (0, 0.0);
+    for &coding in supported.iter().chain(iter::once(&"identity")) {
+        // Listed codings are always preferred over an unlisted `identity`.
+        let key = match quality(coding).or(wildcard) {
+            Some(q) if q > 0.0 => (2, q),
+            Some(_) => continue,
+            None if coding.eq_ignore_ascii_case("identity") => (1, 0.0),
+            None => continue
+        };
+        if key > best_key {
+            best = Some(String::from(coding));
+            best_key = key;
+        }
+    }
+    best
+}
+
+/// Returns `true` if the `Accept-Encoding` Header Field allows a body with the passed
+/// content coding, ignoring case.
+///
+/// The coding is acceptable if its q-value, or that of `*` if it is not listed, is above
+/// `0`. `identity` is acceptable unless it is excluded as by `preferred_encoding`, so an
+/// empty or missing `Accept-Encoding` Header Field only accepts `identity`.
+///
+/// # Params
+///
+/// header --- The value of the `Accept-Encoding` Header Field.</br>
+/// encoding --- The content coding to check, e.g. `gzip`.
+pub fn accepts_encoding(header: &str, encoding: &str) -> bool {
+    let codings: Vec<(String, f32)> = token::split_list(header).into_iter()
+        .filter_map(parse_weighted)
+        .collect();
+    let quality = |coding: &str| codings.iter()
+        .find(|entry| entry.0.eq_ignore_ascii_case(coding))
+        .map(|entry| entry.1);
+    
+    match quality(encoding).or_else(|| quality("*")) {
+        Some(q) => q > 0.0,
+        None => encoding.eq_ignore_ascii_case("identity")
+    }
+}
+
+/// Returns the most preferred of the `available` language tags according to the
+/// `Accept-Language` Header Field, or `None` if none of them are acceptable.
+///
+/// Language ranges are matched by the basic filtering of RFC 4647 section 3.3.1, ignoring
+/// case, so `fr` matches `fr` and `fr-CH` but `fr-CH` does not match `fr`. Each tag is
+/// given the q-value of the longest range which matches it, `*` matches any tag and a
+/// q-value of `0` excludes a tag. An empty or missing `Accept-Language` Header Field
+/// accepts the first available tag.</br>
+/// Ties go to the tag listed first in `available`.
+///
+/// # Params
+///
+/// header --- The value of the `Accept-Language` Header Field.</br>
+/// available --- The language tags which can be sent, in order of the server's preference.
+pub fn preferred_language(header: &str, available: &[&str]) -> Option<String> {
+    if header.trim().is_empty() {
+        return available.first().map(|tag| String::from(*tag));
+    }
+    
+    let ranges: Vec<(String, f32)> = token::split_list(header).into_iter()
+        .filter_map(parse_weighted)
+        .filter(|range| is_language_range(&range.0))
+        .collect();
+    
+    let mut best = None;
+    let mut best_q = 0.0;
+    for &tag in available {
+        let lowercase = tag.to_lowercase();
+        let matched = ranges.iter()
+            .filter(|range| range.0 == "*" || lowercase == range.0 || lowercase.starts_with(&format!("{}-", range.0)))
+            // `*` is less specific than any other range.
+            .max_by_key(|range| if range.0 == "*" { 0 } else { range.0.len() });
+        
+        let q = matched.map(|range| range.1).unwrap_or_default();
+        if q > best_q {
+            best = Some(String::from(tag));
+            best_q = q;
+        }
+    }
+    best
+}
+
+/// Returns `true` if the passed `str` is `*` or a language range of subtags of one to
+/// eight letters or digits separated by `-`, as in RFC 4647 section 2.1.
+///
+/// # Params
+///
+/// range --- The language range to check.
+fn is_language_range(range: &str) -> bool {
+    range == "*" || range.split('-').all(|subtag| !subtag.is_empty() && subtag.len() <= 8 && subtag.bytes().all(|b| b.is_ascii_alphanumeric()))
+}
+
+/// Converts a single entry of an `Accept-Encoding` or `Accept-Language` Header Field to
+/// its lowercase value and q-value, ignoring any other parameters.
+///
+/// # Params
+///
+/// entry --- The entry to convert, e.g. `gzip;q=0.5`.
+fn parse_weighted(entry: &str) -> Option<(String, f32)> {
+    let mut params = entry.split(';');
+    let value = params.next().unwrap_or_default().trim().to_lowercase();
+    if value.is_empty() {
+        return None;
+    }
+    
+    let mut q = 1.0;
+    for param in params {
+        let mut parts = param.splitn(2, '=');
+        if parts.next().unwrap_or_default().trim().eq_ignore_ascii_case("q") {
+            q = parse_q(parts.next().unwrap_or_default().trim())?;
+        }
+    }
+    Some((value, q))
+}
+
+/// Converts a single media range to a `MediaRange`, ignoring any accept extensions
+/// after the q-value.
+///
+/// # Params
+///
+/// range --- The media range to convert.
+fn parse_range(range: &str) -> Option<MediaRange> {
+    let mut params = range.split(';');
+    let mut types = params.next().unwrap_or_default().splitn(2, '/');
+    let media_type = types.next().unwrap_or_default().trim().to_lowercase();
+    let subtype = types.next().unwrap_or_default().trim().to_lowercase();
+    // A wildcard top-level type is only valid with a wildcard subtype.
+    if media_type.is_empty() || subtype.is_empty() || (media_type == "*" && subtype != "*") {
+        return None;
+    }
+    
+    let mut parameters = Vec::new();
+    let mut q = 1.0;
+    for param in params {
+        let mut parts = param.splitn(2, '=');
+        let name = parts.next().unwrap_or_default().trim().to_lowercase();
+        let value = parts.next().unwrap_or_default().trim();
+        
+        if name == "q" {
+            q = parse_q(value)?;
+            // Everything after the q-value is an accept extension.
+            break;
+        }
+        if !name.is_empty() {
+            parameters.push((name, token::unquote(value).to_lowercase()));
+        }
+    }
+    
+    Some(
+        MediaRange {
+            media_type,
+            subtype,
+            parameters,
+            q
+        }
+    )
+}
+
+/// Converts a q-value to a quality, a q-value is `0` or `1` with at most three decimal
+/// places and no greater than `1`.
+///
+/// # Params
+///
+/// q --- The q-value to convert.
+fn parse_q(q: &str) -> Option<f32> {
+    let valid = match q.as_bytes() {
+        [b'0'] | [b'1'] => true,
+        [b'0', b'.', rest @ ..] => rest.len() <= 3 && rest.iter().all(u8::is_ascii_digit),
+        [b'1', b'.', rest @ ..] => rest.len() <= 3 && rest.iter().all(|b| *b == b'0'),
+        _ => false
+    };
+    
+    if valid {
+        q.parse().ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Returns the `type/subtype` and q-value of each `MediaRange`.
+    fn summary(ranges: Vec<MediaRange>) -> Vec<(String, f32)> {
+        ranges.into_iter()
+            .map(|range| (format!("{}/{}", range.media_type, range.subtype), range.q))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_accept() {
+        assert_eq!(
+            summary(parse_accept("text/html,application/json;q=0.9,*/*;q=0.1")),
+            vec![(String::from("text/html"), 1.0), (String::from("application/json"), 0.9), (String::from("*/*"), 0.1)],
+            "Test negotiate::parse_accept-1 failed."
+        );
+        
+        assert_eq!(
+            summary(parse_accept("*/*, text/*, text/plain;format=flowed, text/plain")),
+            vec![
+                (String::from("text/plain"), 1.0), (String::from("text/plain"), 1.0),
+                (String::from("text/*"), 1.0), (String::from("*/*"), 1.0)
+            ],
+            "Test negotiate::parse_accept-2 failed."
+        );
+        assert_eq!(
+            parse_accept("*/*, text/*, text/plain;format=flowed, text/plain")[0].parameters,
+            vec![(String::from("format"), String::from("flowed"))],
+            "Test negotiate::parse_accept-3 failed."
+        );
+        
+        assert_eq!(
+            summary(parse_accept("Text/HTML;q=0.5;ext=1, text/plain;q=1.5, image/png;q=abc, */png, nonsense, audio/basic;q=0.500")),
+            vec![(String::from("text/html"), 0.5), (String::from("audio/basic"), 0.5)],
+            "Test negotiate::parse_accept-4 failed."
+        );
+        
+        assert_eq!(
+            parse_accept(""),
+            Vec::new(),
+            "Test negotiate::parse_accept-5 failed."
+        );
+    }
+    #[test]
+    fn test_preferred_encoding() {
+        let header = "gzip, deflate;q=0.5, br;q=0";
+        assert_eq!(
+            (preferred_encoding(header, &["br", "deflate", "gzip"]), preferred_encoding(header, &["br", "deflate"])),
+            (Some(String::from("gzip")), Some(String::from("deflate"))),
+            "Test negotiate::preferred_encoding-1 failed."
+        );
+        
+        assert_eq!(
+            (preferred_encoding(header, &["br"]), preferred_encoding(header, &[])),
+            (Some(String::from("identity")), Some(String::from("identity"))),
+            "Test negotiate::preferred_encoding-2 failed."
+        );
+        
+        assert_eq!(
+            (preferred_encoding("", &["gzip"]), preferred_encoding("  ", &["gzip"])),
+            (Some(String::from("identity")), Some(String::from("identity"))),
+            "Test negotiate::preferred_encoding-3 failed."
+        );
+        
+        assert_eq!(
+            preferred_encoding("gzip;q=0.5, *;q=0", &["br", "gzip"]),
+            Some(String::from("gzip")),
+            "Test negotiate::preferred_encoding-4 failed."
+        );
+        assert_eq!(
+            preferred_encoding("br;q=0, *;q=0", &["br", "gzip"]),
+            None,
+            "Test negotiate::preferred_encoding-5 failed."
+        );
+        assert_eq!(
+            preferred_encoding("*;q=0, identity", &["gzip"]),
+            Some(String::from("identity")),
+            "Test negotiate::preferred_encoding-6 failed."
+        );
+        
+        assert_eq!(
+            preferred_encoding("identity;q=0", &[]),
+            None,
+            "Test negotiate::preferred_encoding-7 failed."
+        );
+        
+        assert_eq!(
+            preferred_encoding("*;q=0.2, GZIP;q=0.1", &["gzip", "br"]),
+            Some(String::from("br")),
+            "Test negotiate::preferred_encoding-8 failed."
+        );
+        
+        assert_eq!(
+            preferred_encoding("gzip;q=2, br", &["gzip", "br"]),
+            Some(String::from("br")),
+            "Test negotiate::preferred_encoding-9 failed."
+        );
+    }
+    #[test]
+    fn test_preferred_language() {
+        let header = "fr-CH, fr;q=0.9, en;q=0.8, *;q=0.5";
+        let tests: [(&[&str], Option<&str>); 6] = [
+            (&["en", "fr", "fr-CH"], Some("fr-CH")),
+            (&["en", "fr"], Some("fr")),
+            (&["en-GB", "fr-CA"], Some("fr-CA")),
+            (&["de", "en-US"], Some("en-US")),
+            (&["de", "ja"], Some("de")),
+            (&[], None)
+        ];
+        
+        for &(available, expected) in tests.iter() {
+            assert_eq!(
+                preferred_language(header, available),
+                expected.map(String::from),
+                "Test negotiate::preferred_language-1 failed for `{:?}`.", available
+            );
+        }
+        
+        assert_eq!(
+            (preferred_language("EN-us", &["de", "en-US-x-twain"]), preferred_language("en-US", &["en"])),
+            (Some(String::from("en-US-x-twain")), None),
+            "Test negotiate::preferred_language-2 failed."
+        );
+        
+        assert_eq!(
+            (preferred_language("*, de;q=0", &["de", "de-AT", "en"]), preferred_language("de-AT;q=0, de", &["de-AT", "de-DE"])),
+            (Some(String::from("en")), Some(String::from("de-DE"))),
+            "Test negotiate::preferred_language-3 failed."
+        );
+        
+        assert_eq!(
+            (preferred_language("", &["en", "fr"]), preferred_language("en;q=2, fr_FR, fr;q=0.1", &["en", "fr"])),
+            (Some(String::from("en")), Some(String::from("fr"))),
+            "Test negotiate::preferred_language-4 failed."
+        );
+    }
+    #[test]
+    fn test_best_match() {
+        // RFC 7231 section 5.3.2.
+        assert_eq!(
+            best_match(&["audio/mpeg", "audio/basic"], "audio/*; q=0.2, audio/basic"),
+            Some("audio/basic"),
+            "Test negotiate::best_match-1 failed."
+        );
+        
+        let accept = "text/plain; q=0.5, text/html, text/x-dvi; q=0.8, text/x-c";
+        assert_eq!(
+            (best_match(&["text/plain", "text/x-dvi"], accept), best_match(&["text/x-c", "text/html"], accept)),
+            (Some("text/x-dvi"), Some("text/x-c")),
+            "Test negotiate::best_match-2 failed."
+        );
+        
+        let accept = "text/*;q=0.3, text/html;q=0.7, text/html;level=1, text/html;level=2;q=0.4, */*;q=0.5";
+        let expected = [
+            ("text/html;level=1", 1.0), ("text/html", 0.7), ("text/plain", 0.3),
+            ("image/jpeg", 0.5), ("text/html;level=2", 0.4), ("text/html;level=3", 0.7)
+        ];
+        for &(media_type, q) in expected.iter() {
+            // The media type beats an alternative only matching `*/*` exactly when its q-value is higher.
+            assert_eq!(
+                best_match(&["application/octet-stream", media_type], accept),
+                if q > 0.5 { Some(media_type) } else { Some("application/octet-stream") },
+                "Test negotiate::best_match-3 failed for `{}`.", media_type
+            );
+        }
+        
+        assert_eq!(
+            best_match(&["text/html", "application/json"], ""),
+            Some("text/html"),
+            "Test negotiate::best_match-4 failed."
+        );
+        
+        assert_eq!(
+            best_match(&["text/html", "application/json"], "image/*, text/html;q=0"),
+            None,
+            "Test negotiate::best_match-5 failed."
+        );
+        
+        assert_eq!(
+            best_match(&["text/html", "application/json"], "*/*"),
+            Some("text/html"),
+            "Test negotiate::best_match-6 failed."
+        );
+    }
+}
@@ -0,0 +1,515 @@
+//! `parser` is a module to parse HTTP messages incrementally as their bytes arrive, e.g.
+//! from a nonblocking socket.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::mem;
+use std::str;
+use super::{MessageHTTP, ParseError, ParseMode, ParseLimits, BodyFraming};
+use super::chunked;
+use super::token;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// The progress a `MessageParser` has made after being fed.
+pub enum ParseProgress {
+    /// More bytes are needed before anything new is known.
+    NeedMore,
+    /// The header section is complete and the body is still being read.
+    HeadersComplete,
+    /// The message is complete.
+    Complete(MessageHTTP)
+}
+
+/// How the rest of the body of the current message is read.
+enum Framing {
+    /// The body has this many more bytes.
+    Length(usize),
+    /// The next line is a chunk size line.
+    ChunkSize,
+    /// The current chunk has this many more bytes, including its line break.
+    ChunkData(usize),
+    /// The trailer section is being read, with this many bytes read so far.
+    Trailers(usize),
+    /// The body continues until the connection is closed.
+    Close
+}
+
+/// What part of a message a `MessageParser` is reading.
+enum State {
+    /// The header section.
+    Head,
+    /// The body of a message whose header section has been read.
+    Body(MessageHTTP, Framing)
+}
+
+/// A `MessageParser` parses a `MessageHTTP` from bytes fed to it as they arrive.
+///
+/// Bytes past the end of a message are kept for the next message, so pipelined messages
+/// are parsed in turn by feeding an empty slice after each `Complete`. The same
+/// `ParseLimits` as `read_message_with_limits` are enforced.
+pub struct MessageParser {
+    /// The largest messages to accept.
+    limits: ParseLimits,
+    /// How strictly the header section is parsed.
+    mode: ParseMode,
+    /// The bytes which have been fed but not yet parsed.
+    buffer: Vec<u8>,
+    /// The index in `buffer` the search for the next line break resumes from.
+    scanned: usize,
+    /// The decoded length of the chunked body read so far.
+    decoded: usize,
+    /// The part of the message being read.
+    state: State
+}
+
+impl MessageParser {
+    /// Returns a new `MessageParser` with the default `ParseLimits`.
+    pub fn new() -> MessageParser {
+        MessageParser::with_limits(ParseLimits::default())
+    }
+    /// Returns a new `MessageParser`.
+    ///
+    /// # Params
+    ///
+    /// limits --- The largest header section and body to accept.
+    pub fn with_limits(limits: ParseLimits) -> MessageParser {
+        MessageParser::with_mode(limits, ParseMode::Lenient)
+    }
+    /// Returns a new `MessageParser` which parses header sections as by `mode`.
+    ///
+    /// # Params
+    ///
+    /// limits --- The largest header section and body to accept.</br>
+    /// mode --- How strictly the header section is parsed.
+    pub fn with_mode(limits: ParseLimits, mode: ParseMode) -> MessageParser {
+        MessageParser {
+            limits,
+            mode,
+            buffer: Vec::new(),
+            scanned: 0,
+            decoded: 0,
+            state: State::Head
+        }
+    }
+    /// Returns the message whose body is being read, without its body, or `None` if the
+    /// header section is not complete, e.g. to answer an `Expect: 100-continue`.
+    pub fn head(&self) -> Option<&MessageHTTP> {
+        match self.state {
+            State::Body(ref message, _) => Some(message),
+            State::Head => None
+        }
+    }
+    /// Parses as much of the current message as possible after appending `bytes`.
+    ///
+    /// `HeadersComplete` is returned once, when the header section completes but the body
+    /// does not. A chunked body is decoded as by `MessageHTTP::decode_chunked`. Bytes
+    /// already searched for a line break are not searched again.</br>
+    /// Fails with `UriTooLong`, `TooManyHeaderFields`, `HeaderTooLarge` or `BodyTooLarge`
+    /// if a limit is exceeded, or with any error from parsing the message. The parser should not be fed after failing.
+    ///
+    /// # Params
+    ///
+    /// bytes --- The bytes which have arrived.
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<ParseProgress, ParseError> {
+        self.buffer.extend_from_slice(bytes);
+        
+        let mut progress = ParseProgress::NeedMore;
+        if let State::Head = self.state {
+            if !self.parse_head()? {
+                return Ok(ParseProgress::NeedMore);
+            }
+            progress = ParseProgress::HeadersComplete;
+        }
+        match self.parse_body()? {
+            Some(message) => Ok(ParseProgress::Complete(message)),
+            None => Ok(progress)
+        }
+    }
+    /// Returns the current message once the connection has closed.
+    ///
+    /// Fails with `ConnectionClosed` if no part of a message was fed, or `UnexpectedEof`
+    /// if the message is incomplete and its body is not delimited by the connection
+    /// closing.
+    pub fn finish(&mut self) -> Result<MessageHTTP, ParseError> {
+        match mem::replace(&mut self.state, State::Head) {
+            State::Body(message, Framing::Close) => Ok(message),
+            State::Head if self.buffer.is_empty() => Err(ParseError::ConnectionClosed),
+            _ => Err(ParseError::UnexpectedEof)
+        }
+    }
+    /// Parses the header section if it is complete.</br>
+    /// Returns `true` if the header section was parsed.
+    fn parse_head(&mut self) -> Result<bool, ParseError> {
+        // The end of the header section may straddle the bytes already searched.
+        let start = self.scanned.saturating_sub(3);
+        let end = match token::head_len(&self.buffer[start..]) {
+            Some(index) => start + index,
+            None => {
+                // A long start line is caught as soon as its line break is overdue.
+                let overdue = self.limits.max_start_line.saturating_add(2);
+                if self.scanned < overdue && self.buffer.len() >= overdue {
+                    self.limits.check_head(&self.buffer)?;
+                }
+                if self.buffer.len() > self.limits.max_header_bytes {
+                    return Err(ParseError::HeaderTooLarge { limit: self.limits.max_header_bytes, size: self.buffer.len() });
+                }
+                self.scanned = self.buffer.len();
+                return Ok(false);
+            }
+        };
+        self.limits.check_head(&self.buffer[..end])?;
+        
+        let message = MessageHTTP::from_head(&self.buffer[..end], self.mode)?;
+        self.buffer.drain(..end);
+        self.scanned = 0;
+        
+        let framing = match message.check_framing()? {
+            BodyFraming::ContentLength(length) => {
+                if length > self.limits.max_body_bytes as u64 {
+                    return Err(ParseError::BodyTooLarge);
+                }
+                Framing::Length(length as usize)
+            },
+            BodyFraming::Chunked => Framing::ChunkSize,
+            BodyFraming::Close => Framing::Close,
+            BodyFraming::Error => unreachable!("`check_framing` never returns `BodyFraming::Error`.")
+        };
+        self.state = State::Body(message, framing);
+        Ok(true)
+    }
+    /// Parses as much of the body as possible.</br>
+    /// Returns the message if its body is complete.
+    fn parse_body(&mut self) -> Result<Option<MessageHTTP>, ParseError> {
+        let (mut message, mut framing) = match mem::replace(&mut self.state, State::Head) {
+            State::Body(message, framing) => (message, framing),
+            State::Head => return Ok(None)
+        };
+        
+        loop {
+            framing = match framing {
+                Framing::Length(remaining) => match remaining - self.take(&mut message, remaining) {
+                    0 => break,
+                    remaining => return self.pause(message, Framing::Length(remaining))
+                },
+                Framing::ChunkSize => match self.take_line(0)? {
+                    Some(line) => {
+                        let size = match str::from_utf8(&line[..line.len() - 2]) {
+                            Ok(line) => chunked::chunk_size(line)?,
+                            Err(_) => return Err(ParseError::InvalidUtf8)
+                        };
+                        
+                        message.message_body.extend_from_slice(&line);
+                        if size == 0 {
+                            Framing::Trailers(0)
+                        } else {
+                            self.decoded = self.decoded.saturating_add(size);
+                            if self.decoded > self.limits.max_body_bytes {
+                                return Err(ParseError::BodyTooLarge);
+                            }
+                            // The chunk data is followed by a line break.
+                            Framing::ChunkData(size + 2)
+                        }
+                    },
+                    None => return self.pause(message, Framing::ChunkSize)
+                },
+                Framing::ChunkData(remaining) => match remaining - self.take(&mut message, remaining) {
+                    0 => Framing::ChunkSize,
+                    remaining => return self.pause(message, Framing::ChunkData(remaining))
+                },
+                // The trailer section shares the limit of the header section.
+                Framing::Trailers(length) => match self.take_line(length)? {
+                    Some(line) => {
+                        message.message_body.extend_from_slice(&line);
+                        if line == b"\r\n" {
+                            break;
+                        }
+                        Framing::Trailers(length + line.len())
+                    },
+                    None => return self.pause(message, Framing::Trailers(length))
+                },
+                Framing::Close => {
+                    let available = self.buffer.len();
+                    
+                    self.take(&mut message, available);
+                    if message.message_body.len() > self.limits.max_body_bytes {
+                        return Err(ParseError::BodyTooLarge);
+                    }
+                    return self.pause(message, Framing::Close);
+                }
+            };
+        }
+        self.decoded = 0;
+        message.decode_chunked()?;
+        Ok(Some(message))
+    }
+    /// Keeps the partly read message until more bytes are fed.</br>
+    /// Always returns `Ok(None)`.
+    ///
+    /// # Params
+    ///
+    /// message --- The message whose body is being read.</br>
+    /// framing --- How the rest of the body is read.
+    fn pause(&mut self, message: MessageHTTP, framing: Framing) -> Result<Option<MessageHTTP>, ParseError> {
+        self.state = State::Body(message, framing);
+        Ok(None)
+    }
+    /// Moves up to `max` bytes from the buffer to the body of `message`.</br>
+    /// Returns the number of bytes moved.
+    ///
+    /// # Params
+    ///
+    /// message --- The message to add the bytes to.</br>
+    /// max --- The most bytes to move.
+    fn take(&mut self, message: &mut MessageHTTP, max: usize) -> usize {
+        let len = max.min(self.buffer.len());
+        
+        message.message_body.extend(self.buffer.drain(..len));
+        self.scanned = self.scanned.saturating_sub(len);
+        len
+    }
+    /// Removes the next line from the buffer, including its terminating `\r\n`.</br>
+    /// Returns `None` if the line is not complete.
+    ///
+    /// # Params
+    ///
+    /// used --- The bytes of the section before the line, which shares the limit of the
+    /// header section.
+    fn take_line(&mut self, used: usize) -> Result<Option<Vec<u8>>, ParseError> {
+        let limit = self.limits.max_header_bytes;
+        // The line break may straddle the bytes already searched.
+        let start = self.scanned.saturating_sub(1);
+        
+        match self.buffer[start..].windows(2).position(|w| w == b"\r\n") {
+            Some(index) => {
+                let end = start + index + 2;
+                
+                if used + end > limit {
+                    return Err(ParseError::HeaderTooLarge { limit, size: used + end });
+                }
+                self.scanned = 0;
+                Ok(Some(self.buffer.drain(..end).collect()))
+            },
+            None => {
+                if used + self.buffer.len() > limit {
+                    return Err(ParseError::HeaderTooLarge { limit, size: used + self.buffer.len() });
+                }
+                self.scanned = self.buffer.len();
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl Default for MessageParser {
+    /// Returns a new `MessageParser` with the default `ParseLimits`.
+    fn default() -> MessageParser {
+        MessageParser::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::headers;
+    
+    /// A request with a chunked body and a trailer.
+    const CHUNKED: &[u8] = b"POST /upload HTTP/1.1\r\nHost: example.com\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n6;ext=1\r\n world\r\n0\r\nChecksum: abc123\r\n\r\n";
+    
+    /// Feeds each of the passed pieces to a new `MessageParser`.</br>
+    /// Returns the progress after each piece.
+    fn feed_all(pieces: &[&[u8]]) -> Vec<ParseProgress> {
+        let mut parser = MessageParser::new();
+        
+        pieces.iter().map(|piece| parser.feed(piece).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_message_parser_split() {
+        let requests: [&[u8]; 3] = [
+            b"GET /index.html HTTP/1.1\r\nHost: example.com\r\nAccept: */*\r\n\r\n",
+            b"POST /form HTTP/1.1\r\nHost: example.com\r\nContent-Length: 11\r\n\r\nhello world",
+            CHUNKED
+        ];
+        
+        for request in requests.iter() {
+            let mut expected = MessageHTTP::from_bytes(request).unwrap();
+            expected.decode_chunked().unwrap();
+            
+            for split in 0..request.len() + 1 {
+                let progress = feed_all(&[&request[..split], &request[split..]]);
+                
+                // Splitting at the end feeds an empty slice after the message is complete.
+                assert_eq!(
+                    progress.iter().find(|progress| matches!(**progress, ParseProgress::Complete(_))),
+                    Some(&ParseProgress::Complete(expected.clone())),
+                    "Test MessageParser::feed-1 failed for `{}` split at {}.", String::from_utf8_lossy(request), split
+                );
+            }
+            
+            // Only a message with a body has to wait for more bytes after its header section.
+            let headers_complete = if expected.message_body.is_empty() { 0 } else { 1 };
+            let pieces = request.iter().map(::std::slice::from_ref).collect::<Vec<&[u8]>>();
+            let progress = feed_all(&pieces);
+            assert_eq!(
+                progress.last(),
+                Some(&ParseProgress::Complete(expected)),
+                "Test MessageParser::feed-2 failed for `{}`.", String::from_utf8_lossy(request)
+            );
+            assert_eq!(
+                progress.iter().filter(|progress| **progress == ParseProgress::HeadersComplete).count(),
+                headers_complete,
+                "Test MessageParser::feed-3 failed for `{}`.", String::from_utf8_lossy(request)
+            );
+        }
+    }
+    #[test]
+    fn test_message_parser_progress() {
+        let mut parser = MessageParser::new();
+        
+        assert_eq!(
+            parser.feed(b"POST /form HTTP/1.1\r\nContent-Length: 5\r\n"),
+            Ok(ParseProgress::NeedMore),
+            "Test MessageParser::feed-4 failed."
+        );
+        assert_eq!(
+            parser.feed(b"\r\nhel"),
+            Ok(ParseProgress::HeadersComplete),
+            "Test MessageParser::feed-5 failed."
+        );
+        assert_eq!(
+            parser.head().and_then(|message| message.header(headers::CONTENT_LENGTH)),
+            Some("5"),
+            "Test MessageParser::head-1 failed."
+        );
+        assert_eq!(
+            parser.feed(b"l"),
+            Ok(ParseProgress::NeedMore),
+            "Test MessageParser::feed-6 failed."
+        );
+        
+        // The start of the next pipelined message is kept.
+        let message = match parser.feed(b"oGET / HTTP/1.1\r\n\r\n") {
+            Ok(ParseProgress::Complete(message)) => message,
+            progress => panic!("Test MessageParser::feed-7 failed: {:?}", progress)
+        };
+        assert_eq!(
+            message.message_body,
+            b"hello".to_vec(),
+            "Test MessageParser::feed-8 failed."
+        );
+        assert_eq!(
+            parser.feed(b""),
+            Ok(ParseProgress::Complete(MessageHTTP::from("GET / HTTP/1.1\r\n\r\n").unwrap())),
+            "Test MessageParser::feed-9 failed."
+        );
+        assert_eq!(
+            parser.finish(),
+            Err(ParseError::ConnectionClosed),
+            "Test MessageParser::finish-1 failed."
+        );
+    }
+    #[test]
+    fn test_message_parser_finish() {
+        let mut parser = MessageParser::new();
+        
+        assert_eq!(
+            parser.feed(b"HTTP/1.1 200 OK\r\n\r\nuntil the"),
+            Ok(ParseProgress::HeadersComplete),
+            "Test MessageParser::finish-2 failed."
+        );
+        parser.feed(b" end").unwrap();
+        assert_eq!(
+            parser.finish().map(|message| message.message_body),
+            Ok(b"until the end".to_vec()),
+            "Test MessageParser::finish-3 failed."
+        );
+        
+        let mut parser = MessageParser::new();
+        parser.feed(&CHUNKED[..CHUNKED.len() - 4]).unwrap();
+        assert_eq!(
+            parser.finish(),
+            Err(ParseError::UnexpectedEof),
+            "Test MessageParser::finish-4 failed."
+        );
+    }
+    #[test]
+    fn test_message_parser_illegal_character() {
+        let mut parser = MessageParser::new();
+        
+        assert_eq!(
+            parser.feed(b"GET /a\0 HTTP/1.1\r\nHost: localhost\r\n\r\n"),
+            Err(ParseError::IllegalCharacter { position: 6 }),
+            "Test MessageParser::feed illegal-character failed."
+        );
+    }
+    #[test]
+    fn test_message_parser_with_mode() {
+        let request = b"GET / HTTP/1.1\r\nHost : localhost\r\n\r\n";
+        
+        assert!(
+            MessageParser::new().feed(request).is_ok(),
+            "Test MessageParser::with_mode-1 failed."
+        );
+        assert_eq!(
+            MessageParser::with_mode(ParseLimits::default(), ParseMode::Strict).feed(request),
+            Err(ParseError::WhitespaceBeforeColon(String::from("Host : localhost"))),
+            "Test MessageParser::with_mode-2 failed."
+        );
+    }
+    #[test]
+    fn test_message_parser_framing() {
+        let tests: [(&[u8], ParseError); 3] = [
+            (b"POST / HTTP/1.1\r\nContent-Length: 13\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nSMUGGLED", ParseError::AmbiguousFraming),
+            (b"POST / HTTP/1.1\r\nContent-Length: 3\r\nTransfer-Encoding: chunked\r\n\r\n8\r\nSMUGGLED\r\n0\r\n\r\n", ParseError::AmbiguousFraming),
+            (b"POST / HTTP/1.1\r\nContent-Length: 3\r\nContent-Length: 8\r\n\r\nSMUGGLED", ParseError::ConflictingContentLength)
+        ];
+        
+        for &(bytes, ref error) in tests.iter() {
+            assert_eq!(
+                MessageParser::new().feed(bytes).as_ref(),
+                Err(error),
+                "Test MessageParser framing failed for `{}`.", String::from_utf8_lossy(bytes)
+            );
+        }
+    }
+    #[test]
+    fn test_message_parser_limits() {
+        let limits = ParseLimits { max_header_bytes: 64, max_body_bytes: 8, ..ParseLimits::default() };
+        let tests: [(&[u8], ParseError); 5] = [
+            (b"GET / HTTP/1.1\r\nHost: example.com\r\nUser-Agent: a very long user agent\r\n\r\n", ParseError::HeaderTooLarge { limit: 64, size: 73 }),
+            (b"GET / HTTP/1.1\r\nHost: example.com\r\nUser-Agent: a very long user agent", ParseError::HeaderTooLarge { limit: 64, size: 69 }),
+            (b"POST / HTTP/1.1\r\nContent-Length: 9\r\n\r\n", ParseError::BodyTooLarge),
+            (b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n5\r\n12345\r\n5\r\n", ParseError::BodyTooLarge),
+            (b"HTTP/1.1 200 OK\r\n\r\n123456789", ParseError::BodyTooLarge)
+        ];
+        
+        for &(bytes, ref error) in tests.iter() {
+            assert_eq!(
+                MessageParser::with_limits(limits).feed(bytes).as_ref(),
+                Err(error),
+                "Test MessageParser limits failed for `{}`.", String::from_utf8_lossy(bytes)
+            );
+        }
+        
+        let limits = ParseLimits { max_start_line: 15, max_header_count: 1, ..ParseLimits::default() };
+        assert_eq!(
+            MessageParser::with_limits(limits).feed(b"GET /a HTTP/1.1\r\nHost: example.com\r\n\r\n"),
+            Ok(ParseProgress::Complete(MessageHTTP::from("GET /a HTTP/1.1\r\nHost: example.com\r\n\r\n").unwrap())),
+            "Test MessageParser limits failed at the start line and Header field limits."
+        );
+        let mut parser = MessageParser::with_limits(limits);
+        assert_eq!(
+            (parser.feed(b"GET /ab HTTP/1.1"), parser.feed(b"\r")),
+            (Ok(ParseProgress::NeedMore), Err(ParseError::UriTooLong { limit: 15, size: 16 })),
+            "Test MessageParser limits failed for a long start line."
+        );
+        assert_eq!(
+            MessageParser::with_limits(limits).feed(b"GET /a HTTP/1.1\r\nHost: example.com\r\nAccept: */*\r\n\r\n"),
+            Err(ParseError::TooManyHeaderFields { limit: 1, count: 2 }),
+            "Test MessageParser limits failed for too many Header fields."
+        );
+    }
+}
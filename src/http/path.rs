@@ -0,0 +1,195 @@
+//! `path` is a module to safely map the path of a request target onto the filesystem.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::string::String;
+use std::path::PathBuf;
+use super::ParseError;
+use super::percent;
+
+/// Returns the path of the passed request target as a relative `PathBuf`, to be joined
+/// onto the root directory being served.
+///
+/// The query is ignored and the path is percent-decoded before it is split on `/`, so
+/// encoded separators and dots are resolved like any other. `.` and `..` segments are
+/// resolved lexically without touching the filesystem.</br>
+/// Fails if a `..` would escape the root or if the decoded path contains a NUL, a
+/// backslash or, on Windows, a `:`, any of which could name a file outside the root.
+///
+/// # Params
+///
+/// target --- The request target to sanitize.
+pub fn sanitize(target: &str) -> Result<PathBuf, ParseError> {
+    let path = target.split('?').next().unwrap_or_default();
+    let decoded = percent::decode(path)?;
+    
+    if decoded.contains(&['\0', '\\'][..]) || (cfg!(windows) && decoded.contains(':')) {
+        return Err(ParseError::InvalidPath(String::from(target)));
+    }
+    
+    let mut segments = Vec::new();
+    for segment in decoded.split('/') {
+        match segment {
+            "" | "." => {},
+            ".." => if segments.pop().is_none() {
+                return Err(ParseError::InvalidPath(String::from(target)));
+            },
+            segment => segments.push(segment)
+        }
+    }
+    Ok(segments.iter().collect())
+}
+
+/// Returns the passed path normalized so that equivalent paths compare equal, e.g. as a
+/// routing or caching key.
+///
+/// Runs of `/` are collapsed into one before the `.` and `..` segments are removed. A
+/// `..` which would go above the root stays at `/` rather than failing, so this is not a
+/// substitute for `sanitize` when serving files.
+///
+/// # Params
+///
+/// path --- The path to normalize, without a query.
+pub fn normalize(path: &str) -> String {
+    normalize_with(path, true)
+}
+
+/// Returns the passed path normalized as in `normalize`, only collapsing runs of `/` if
+/// asked to, for applications where an empty segment is significant.
+///
+/// # Params
+///
+/// path --- The path to normalize, without a query.</br>
+/// collapse_slashes --- If `true` runs of `/` are collapsed into one.
+pub fn normalize_with(path: &str, collapse_slashes: bool) -> String {
+    let res = if collapse_slashes {
+        let mut collapsed = String::with_capacity(path.len());
+        
+        for c in path.chars() {
+            if !(c == '/' && collapsed.ends_with('/')) {
+                collapsed.push(c);
+            }
+        }
+        remove_dot_segments(&collapsed)
+    } else {
+        remove_dot_segments(path)
+    };
+    
+    if res.starts_with('/') { res } else { format!("/{}", res) }
+}
+
+/// Returns the passed path with its `.` and `..` segments removed, as in RFC 3986
+/// section 5.2.4.
+///
+/// # Params
+///
+/// path --- The path to remove the dot segments from.
+pub fn remove_dot_segments(path: &str) -> String {
+    let mut input = path;
+    let mut output: Vec<&str> = Vec::new();
+    
+    while !input.is_empty() {
+        if let Some(rest) = input.strip_prefix("../").or_else(|| input.strip_prefix("./")) {
+            input = rest;
+        } else if input.starts_with("/./") {
+            input = &input[2..];
+        } else if input == "/." {
+            input = "/";
+        } else if input.starts_with("/../") || input == "/.." {
+            input = if input == "/.." { "/" } else { &input[3..] };
+            output.pop();
+        } else if input == "." || input == ".." {
+            input = "";
+        } else {
+            // Move the first segment, and its leading `/`, to the output.
+            let start = if input.starts_with('/') { 1 } else { 0 };
+            let end = input[start..].find('/').map_or(input.len(), |index| index + start);
+            output.push(&input[..end]);
+            input = &input[end..];
+        }
+    }
+    output.concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize() {
+        assert_eq!(
+            sanitize("/"),
+            Ok(PathBuf::new()),
+            "Test path::sanitize-1 failed."
+        );
+        assert_eq!(
+            sanitize("/css/./site.css?v=2"),
+            Ok(PathBuf::from("css").join("site.css")),
+            "Test path::sanitize-2 failed."
+        );
+        assert_eq!(
+            sanitize("/a/b/../c%20d"),
+            Ok(PathBuf::from("a").join("c d")),
+            "Test path::sanitize-3 failed."
+        );
+        
+        for &target in ["/../etc/passwd", "/a/../../etc/passwd", "/..%2fetc/passwd", "/a/..%2f..%2fetc", "/%2e%2e/etc/passwd", "/%2E%2E"].iter() {
+            assert_eq!(
+                sanitize(target),
+                Err(ParseError::InvalidPath(String::from(target))),
+                "Test path::sanitize-4 failed for `{}`.", target
+            );
+        }
+        
+        assert_eq!(
+            sanitize("/index.html%00.png"),
+            Err(ParseError::InvalidPath(String::from("/index.html%00.png"))),
+            "Test path::sanitize-5 failed."
+        );
+        assert_eq!(
+            sanitize("/..%5c..%5cwindows"),
+            Err(ParseError::InvalidPath(String::from("/..%5c..%5cwindows"))),
+            "Test path::sanitize-6 failed."
+        );
+        assert_eq!(
+            sanitize("/%zz"),
+            Err(ParseError::InvalidPercentEncoding(String::from("%zz"))),
+            "Test path::sanitize-7 failed."
+        );
+    }
+    #[test]
+    fn test_normalize() {
+        let cases = [
+            ("/a/./b//c/../d", true, "/a/b/d"),
+            ("/", true, "/"),
+            ("", true, "/"),
+            ("/a/b/", true, "/a/b/"),
+            ("/a/b/..", true, "/a/"),
+            ("/a/b/.", true, "/a/b/"),
+            ("/../a", true, "/a"),
+            ("/a/../../..", true, "/"),
+            ("../a/./b", true, "/a/b"),
+            ("///a///b", true, "/a/b"),
+            ("/a/..b/c.", true, "/a/..b/c."),
+            ("/a//b", false, "/a//b"),
+            ("/a//../b", true, "/b"),
+            ("/a//../b", false, "/a/b")
+        ];
+        
+        for &(path, collapse_slashes, normalized) in cases.iter() {
+            assert_eq!(
+                normalize_with(path, collapse_slashes),
+                normalized,
+                "Test path::normalize-1 failed for `{}`.", path
+            );
+        }
+        assert_eq!(
+            normalize("/a//b/./"),
+            "/a/b/",
+            "Test path::normalize-2 failed."
+        );
+    }
+}
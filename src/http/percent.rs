@@ -0,0 +1,156 @@
+//! `percent` is a module to handle the percent-encoding of request targets and their components.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::string::String;
+use super::ParseError;
+
+/// Returns `true` if the byte is in the unreserved set of RFC 3986 and never needs encoding.
+///
+/// # Params
+///
+/// byte --- The byte to check.
+fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'.' || byte == b'_' || byte == b'~'
+}
+
+/// Decodes every percent-encoded byte in the passed `str`.
+///
+/// A `%` which is not followed by two hex digits is an error, as are decoded bytes
+/// which are not valid utf8.
+///
+/// # Params
+///
+/// encoded --- The `str` to decode.
+pub fn decode(encoded: &str) -> Result<String, ParseError> {
+    decode_bytes(encoded, false)
+}
+
+/// Decodes a component of a query string or form body, where `+` encodes a space.
+///
+/// # Params
+///
+/// encoded --- The `str` to decode.
+pub fn decode_form(encoded: &str) -> Result<String, ParseError> {
+    decode_bytes(encoded, true)
+}
+
+/// Decodes every percent-encoded byte in `encoded`, optionally treating `+` as a space.
+///
+/// # Params
+///
+/// encoded --- The `str` to decode.</br>
+/// plus_as_space --- If `+` should be decoded as a space.
+fn decode_bytes(encoded: &str, plus_as_space: bool) -> Result<String, ParseError> {
+    let bytes = encoded.as_bytes();
+    let mut res = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                // There must be two hex digits following the `%`.
+                match encoded.get(i + 1..i + 3) {
+                    Some(hex) if hex.bytes().all(|b| b.is_ascii_hexdigit()) => {
+                        res.push(u8::from_str_radix(hex, 16).unwrap_or_default());
+                        i += 2;
+                    },
+                    _ => return Err(ParseError::InvalidPercentEncoding(String::from(&encoded[i..])))
+                }
+            },
+            b'+' if plus_as_space => res.push(b' '),
+            b => res.push(b)
+        }
+        i += 1;
+    }
+    
+    match String::from_utf8(res) {
+        Ok(res) => Ok(res),
+        Err(_) => Err(ParseError::InvalidUtf8)
+    }
+}
+
+/// Percent-encodes every byte of the passed `str` which is outside the unreserved set.
+///
+/// # Params
+///
+/// decoded --- The `str` to encode.
+pub fn encode(decoded: &str) -> String {
+    let mut res = String::with_capacity(decoded.len());
+    
+    for byte in decoded.bytes() {
+        if is_unreserved(byte) {
+            res.push(byte as char);
+        } else {
+            res.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            decode("/my%20file.html").unwrap(),
+            "/my file.html",
+            "Test percent::decode-1 failed."
+        );
+        
+        assert_eq!(
+            decode("caf%C3%A9+%c3%a9").unwrap(),
+            "caf\u{e9}+\u{e9}",
+            "Test percent::decode-2 failed."
+        );
+        
+        assert_eq!(
+            decode("%ZZ"),
+            Err(ParseError::InvalidPercentEncoding(String::from("%ZZ"))),
+            "Test percent::decode-3 failed."
+        );
+        
+        assert_eq!(
+            decode("100%"),
+            Err(ParseError::InvalidPercentEncoding(String::from("%"))),
+            "Test percent::decode-4 failed."
+        );
+        
+        assert_eq!(
+            decode("%C3"),
+            Err(ParseError::InvalidUtf8),
+            "Test percent::decode-5 failed."
+        );
+        
+        assert_eq!(
+            decode_form("hello+world%21").unwrap(),
+            "hello world!",
+            "Test percent::decode_form-1 failed."
+        );
+    }
+    #[test]
+    fn test_encode() {
+        assert_eq!(
+            encode("/my file.html"),
+            "%2Fmy%20file.html",
+            "Test percent::encode-1 failed."
+        );
+        
+        assert_eq!(
+            encode("caf\u{e9}~-_."),
+            "caf%C3%A9~-_.",
+            "Test percent::encode-2 failed."
+        );
+        
+        assert_eq!(
+            decode(&encode("a+b=c&d/\u{1F600}")).unwrap(),
+            "a+b=c&d/\u{1F600}",
+            "Test percent::encode-3 failed."
+        );
+    }
+}
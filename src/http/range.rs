@@ -0,0 +1,222 @@
+//! `range` is a module to handle byte `Range` requests and their `206 Partial Content` responses.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::string::String;
+use super::{MessageHTTP, RangeError, ResponseBuilder, StatusCode};
+use super::headers;
+
+/// Returns the satisfiable byte ranges of a `Range` Header Field as inclusive
+/// `(first, last)` pairs, in the order they were requested.
+///
+/// Suffix ranges (`bytes=-500`) select the final bytes and open-ended ranges
+/// (`bytes=500-`) run to the end. Ranges running past the end are cut short and ranges
+/// starting past the end are dropped.</br>
+/// Fails with `Malformed` if the header is not a valid `bytes` range set, or with
+/// `Unsatisfiable` if none of the ranges can be satisfied.
+///
+/// # Params
+///
+/// header --- The value of the `Range` Header Field.</br>
+/// total_len --- The length of the representation the ranges select from.
+pub fn parse(header: &str, total_len: u64) -> Result<Vec<(u64, u64)>, RangeError> {
+    let malformed = || RangeError::Malformed(String::from(header));
+    let header = header.trim();
+    
+    // The range unit is case-insensitive.
+    let ranges = match header.get(..6) {
+        Some(unit) if unit.eq_ignore_ascii_case("bytes=") => &header[6..],
+        _ => return Err(malformed())
+    };
+    
+    let mut res = Vec::new();
+    let mut empty = true;
+    for range in ranges.split(',').map(str::trim).filter(|range| !range.is_empty()) {
+        empty = false;
+        
+        let index = range.find('-').ok_or_else(malformed)?;
+        let (first, last) = (&range[..index], &range[index + 1..]);
+        let range = match (number(first), number(last)) {
+            // A suffix range selects the final bytes.
+            (None, Some(suffix)) if first.is_empty() => if suffix > 0 && total_len > 0 {
+                Some((total_len - suffix.min(total_len), total_len - 1))
+            } else {
+                None
+            },
+            (Some(first), None) if last.is_empty() => if first < total_len {
+                Some((first, total_len - 1))
+            } else {
+                None
+            },
+            (Some(first), Some(last)) if first <= last => if first < total_len {
+                Some((first, last.min(total_len - 1)))
+            } else {
+                None
+            },
+            _ => return Err(malformed())
+        };
+        res.extend(range);
+    }
+    
+    if empty {
+        Err(malformed())
+    } else if res.is_empty() {
+        Err(RangeError::Unsatisfiable(total_len))
+    } else {
+        Ok(res)
+    }
+}
+
+/// Returns the value of the `Content-Range` Header Field for the passed range.
+///
+/// # Params
+///
+/// range --- The inclusive `(first, last)` range being sent.</br>
+/// total_len --- The length of the complete representation.
+pub fn content_range(range: (u64, u64), total_len: u64) -> String {
+    format!("bytes {}-{}/{}", range.0, range.1, total_len)
+}
+
+/// Returns a `206 Partial Content` response containing the passed range of `body`.
+///
+/// # Params
+///
+/// body --- The complete representation.</br>
+/// range --- The inclusive `(first, last)` range to send, as returned by `parse`.
+///
+/// # Panics
+///
+/// Panics if the range does not lie within `body`.
+pub fn partial_content(body: &[u8], range: (u64, u64)) -> MessageHTTP {
+    ResponseBuilder::new(StatusCode::PARTIAL_CONTENT)
+        .header(headers::CONTENT_RANGE, &content_range(range, body.len() as u64))
+        .body(&body[range.0 as usize..=range.1 as usize])
+        .build()
+        .expect("A `Content-Range` is always a valid Header Field value.")
+}
+
+/// Returns the response to a `Range` request for `body`.
+///
+/// Fails with `MultipleRanges` if more than one range can be satisfied, otherwise
+/// fails as `parse` does.
+///
+/// # Params
+///
+/// header --- The value of the `Range` Header Field.</br>
+/// body --- The complete representation.
+pub fn respond(header: &str, body: &[u8]) -> Result<MessageHTTP, RangeError> {
+    match parse(header, body.len() as u64)?.as_slice() {
+        [range] => Ok(partial_content(body, *range)),
+        _ => Err(RangeError::MultipleRanges)
+    }
+}
+
+/// Returns a `416 Range Not Satisfiable` response for a representation of the passed length.
+///
+/// # Params
+///
+/// total_len --- The length of the complete representation.
+pub fn not_satisfiable(total_len: u64) -> MessageHTTP {
+    ResponseBuilder::new(StatusCode::RANGE_NOT_SATISFIABLE)
+        .header(headers::CONTENT_RANGE, &format!("bytes */{}", total_len))
+        .body(Vec::new())
+        .build()
+        .expect("A `Content-Range` is always a valid Header Field value.")
+}
+
+/// Returns the value of a range position, or `None` if it is not plain digits.
+///
+/// # Params
+///
+/// digits --- The position to convert.
+fn number(digits: &str) -> Option<u64> {
+    if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+        digits.parse().ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::HTTP;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("bytes=0-1023", 5000),
+            Ok(vec![(0, 1023)]),
+            "Test range::parse-1 failed."
+        );
+        
+        assert_eq!(
+            parse("bytes=-500", 5000),
+            Ok(vec![(4500, 4999)]),
+            "Test range::parse-2 failed."
+        );
+        
+        assert_eq!(
+            parse("bytes=500-", 5000),
+            Ok(vec![(500, 4999)]),
+            "Test range::parse-3 failed."
+        );
+        
+        assert_eq!(
+            parse("Bytes=4000-9000, -9000, 6000-7000, 0-0", 5000),
+            Ok(vec![(4000, 4999), (0, 4999), (0, 0)]),
+            "Test range::parse-4 failed."
+        );
+        
+        assert_eq!(
+            (parse("bytes=5000-", 5000), parse("bytes=-0", 5000), parse("bytes=0-10", 0)),
+            (Err(RangeError::Unsatisfiable(5000)), Err(RangeError::Unsatisfiable(5000)), Err(RangeError::Unsatisfiable(0))),
+            "Test range::parse-5 failed."
+        );
+        
+        for header in ["bytes=10-5", "bytes=a-b", "bytes=-", "bytes=", "items=0-5", "bytes=0-5-6", "bytes=+1-5", "", "bytes 0-5"].iter() {
+            assert_eq!(
+                parse(header, 5000),
+                Err(RangeError::Malformed(String::from(*header))),
+                "Test range::parse-6 failed for `{}`.", header
+            );
+        }
+    }
+    #[test]
+    fn test_respond() {
+        let body = b"0123456789";
+        
+        assert_eq!(
+            respond("bytes=2-4", body).unwrap().to_http().unwrap(),
+            "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes 2-4/10\r\nContent-Length: 3\r\n\r\n234",
+            "Test range::respond-1 failed."
+        );
+        
+        assert_eq!(
+            respond("bytes=-3", body).unwrap().message_body,
+            b"789".to_vec(),
+            "Test range::respond-2 failed."
+        );
+        
+        assert_eq!(
+            respond("bytes=0-1,5-6", body),
+            Err(RangeError::MultipleRanges),
+            "Test range::respond-3 failed."
+        );
+        
+        assert_eq!(
+            respond("bytes=10-", body),
+            Err(RangeError::Unsatisfiable(10)),
+            "Test range::respond-4 failed."
+        );
+        
+        assert_eq!(
+            not_satisfiable(10).to_http().unwrap(),
+            "HTTP/1.1 416 Range Not Satisfiable\r\nContent-Range: bytes */10\r\nContent-Length: 0\r\n\r\n",
+            "Test range::not_satisfiable-1 failed."
+        );
+    }
+}
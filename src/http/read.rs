@@ -0,0 +1,589 @@
+//! `read` is a module to read complete HTTP messages from a stream, such as a `TcpStream`.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::str;
+use std::io;
+use std::io::prelude::*;
+use super::{MessageHTTP, ParseError, ParseMode, BodyFraming, Version};
+use super::chunked;
+use super::token;
+#[cfg(test)]
+use super::headers;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// The largest messages a reader will accept, so a client can not exhaust the memory of
+/// a server.
+pub struct ParseLimits {
+    /// The most bytes in the start line, without its line break.
+    pub max_start_line: usize,
+    /// The most Header fields in the header section.
+    pub max_header_count: usize,
+    /// The most bytes in the header section, including the start line and the empty line.
+    pub max_header_bytes: usize,
+    /// The most bytes in the message body, after any chunked framing is removed.
+    pub max_body_bytes: usize
+}
+
+impl Default for ParseLimits {
+    /// Returns limits of an 8 KB start line, 100 Header fields, 64 KB of headers and 10 MB
+    /// of body.
+    fn default() -> ParseLimits {
+        ParseLimits {
+            max_start_line: 8 * 1024,
+            max_header_count: 100,
+            max_header_bytes: 64 * 1024,
+            max_body_bytes: 10 * 1024 * 1024
+        }
+    }
+}
+
+impl ParseLimits {
+    /// Checks a header section, or the start of one, against the limits.
+    ///
+    /// Fails with `UriTooLong` if the start line is too long, `TooManyHeaderFields` if there
+    /// are too many Header fields, or `HeaderTooLarge` if there are too many bytes.
+    ///
+    /// # Params
+    ///
+    /// head --- The start line and header fields, with or without the final empty line.
+    pub fn check_head(&self, head: &[u8]) -> Result<(), ParseError> {
+        let start_line = match head.iter().position(|&b| b == b'\n') {
+            Some(index) if index > 0 && head[index - 1] == b'\r' => index - 1,
+            Some(index) => index,
+            // An unterminated start line may end with the first half of its line break.
+            None if head.ends_with(b"\r") => head.len() - 1,
+            None => head.len()
+        };
+        if start_line > self.max_start_line {
+            return Err(ParseError::UriTooLong { limit: self.max_start_line, size: start_line });
+        }
+        
+        let fields = head.split(|&b| b == b'\n')
+            .skip(1)
+            .filter(|line| !line.is_empty() && *line != b"\r")
+            .count();
+        if fields > self.max_header_count {
+            return Err(ParseError::TooManyHeaderFields { limit: self.max_header_count, count: fields });
+        }
+        if head.len() > self.max_header_bytes {
+            return Err(ParseError::HeaderTooLarge { limit: self.max_header_bytes, size: head.len() });
+        }
+        Ok(())
+    }
+    /// Checks a complete message against the limits.
+    ///
+    /// [Read more](#method.check_head)
+    ///
+    /// # Params
+    ///
+    /// msg --- The bytes of the message.
+    pub fn check_message(&self, msg: &[u8]) -> Result<(), ParseError> {
+        let (head, body) = match token::head_len(msg) {
+            Some(index) => msg.split_at(index),
+            None => (msg, &msg[msg.len()..])
+        };
+        
+        self.check_head(head)?;
+        if body.len() > self.max_body_bytes {
+            return Err(ParseError::BodyTooLarge);
+        }
+        Ok(())
+    }
+}
+
+/// Reads one complete `MessageHTTP` from `stream` within the default `ParseLimits`.
+///
+/// [Read more](fn.read_message_with_limits.html)
+///
+/// # Params
+///
+/// stream --- The stream to read the message from.
+pub fn read_message<R: Read>(stream: &mut R) -> Result<MessageHTTP, ParseError> {
+    read_message_with_limits(stream, ParseLimits::default())
+}
+
+/// Reads one complete `MessageHTTP` from `stream`, parsed as by `ParseMode::Lenient`.
+///
+/// [Read more](fn.read_message_with_mode.html)
+///
+/// # Params
+///
+/// stream --- The stream to read the message from.</br>
+/// limits --- The largest header section and body to accept.
+pub fn read_message_with_limits<R: Read>(stream: &mut R, limits: ParseLimits) -> Result<MessageHTTP, ParseError> {
+    read_message_with_mode(stream, limits, ParseMode::Lenient)
+}
+
+/// Reads one complete `MessageHTTP` from `stream`.
+///
+/// The header section is read up to its empty line, then the body is read according to
+/// its `Content-Length` or chunked framing. A chunked body is decoded as by
+/// `MessageHTTP::decode_chunked`. Nothing past the end of the message is read, so
+/// further messages on the same stream can be read in turn. The head is read a byte at
+/// a time so an unbuffered stream should be wrapped in a `BufReader`.</br>
+/// Fails with `ConnectionClosed` if the stream ends before the message starts,
+/// `UnexpectedEof` if it ends part way through, `UriTooLong`, `TooManyHeaderFields`,
+/// `HeaderTooLarge` or `BodyTooLarge` if a limit is exceeded, or with any error from
+/// parsing the message. Nothing more is read once the start line or header section passes
+/// its limit.
+///
+/// # Params
+///
+/// stream --- The stream to read the message from.</br>
+/// limits --- The largest header section and body to accept.</br>
+/// mode --- How strictly the header section is parsed.
+pub fn read_message_with_mode<R: Read>(stream: &mut R, limits: ParseLimits, mode: ParseMode) -> Result<MessageHTTP, ParseError> {
+    read_message_with_http09(stream, limits, mode, false)
+}
+
+/// Reads one complete `MessageHTTP` from `stream` as by `read_message_with_mode`,
+/// accepting an HTTP/0.9 simple-request if `http09` is `true`.
+///
+/// A simple-request ends with its Request line and has no body.
+///
+/// # Params
+///
+/// stream --- The stream to read the message from.</br>
+/// limits --- The largest header section and body to accept.</br>
+/// mode --- How strictly the header section is parsed.</br>
+/// http09 --- If a simple-request is accepted.
+pub fn read_message_with_http09<R: Read>(stream: &mut R, limits: ParseLimits, mode: ParseMode, http09: bool) -> Result<MessageHTTP, ParseError> {
+    let mut message = read_head_with_http09(stream, limits, mode, http09)?;
+    
+    read_body(stream, &mut message, limits)?;
+    Ok(message)
+}
+
+/// Reads the start line and header section of a HTTP message from `stream`, leaving its
+/// body unread.
+///
+/// The header section is read and checked as by `read_message_with_mode`, the body can
+/// then be read with `read_body`.
+///
+/// # Params
+///
+/// stream --- The stream to read the header section from.</br>
+/// limits --- The largest header section to accept.</br>
+/// mode --- How strictly the header section is parsed.
+pub fn read_head_with_mode<R: Read>(stream: &mut R, limits: ParseLimits, mode: ParseMode) -> Result<MessageHTTP, ParseError> {
+    read_head_with_http09(stream, limits, mode, false)
+}
+
+/// Reads the start line and header section of a HTTP message from `stream` as by
+/// `read_head_with_mode`, accepting an HTTP/0.9 simple-request if `http09` is `true`.
+///
+/// The head of a simple-request ends with its Request line, nothing after it is read.
+///
+/// # Params
+///
+/// stream --- The stream to read the header section from.</br>
+/// limits --- The largest header section to accept.</br>
+/// mode --- How strictly the header section is parsed.</br>
+/// http09 --- If a simple-request is accepted.
+pub fn read_head_with_http09<R: Read>(stream: &mut R, limits: ParseLimits, mode: ParseMode, http09: bool) -> Result<MessageHTTP, ParseError> {
+    let mut head = Vec::new();
+    let mut first_line = true;
+    
+    // The header section ends at the first empty line, `from_head` rejects a bare LF when
+    // parsing `Strict`ly. Only the last byte read can complete that line so only the last
+    // three bytes are searched, keeping the read linear in the size of the head.
+    while token::head_len(&head[head.len().saturating_sub(3)..]).is_none() {
+        match read_byte(stream)? {
+            Some(byte) => head.push(byte),
+            None if head.is_empty() => return Err(ParseError::ConnectionClosed),
+            None => return Err(ParseError::UnexpectedEof)
+        }
+        // A long start line is caught as soon as its line break is overdue.
+        if head.len() == limits.max_start_line.saturating_add(2) {
+            limits.check_head(&head)?;
+        }
+        if head.len() > limits.max_header_bytes {
+            return Err(ParseError::HeaderTooLarge { limit: limits.max_header_bytes, size: head.len() });
+        }
+        // A simple-request has no header section to wait for.
+        if http09 && first_line && head.ends_with(b"\n") {
+            first_line = false;
+            limits.check_head(&head)?;
+            
+            let message = MessageHTTP::from_head_with_http09(&head, mode, true)?;
+            if message.start_line.version() == Version::HTTP_09 {
+                return Ok(message);
+            }
+        }
+    }
+    limits.check_head(&head)?;
+    MessageHTTP::from_head_with_http09(&head, mode, http09)
+}
+
+/// Reads the body of `message` from `stream`, after its header section was read by
+/// `read_head_with_mode`, according to its `Content-Length` or chunked framing.
+///
+/// The body is read and checked as by `read_message_with_mode`.
+///
+/// # Params
+///
+/// stream --- The stream to read the body from.</br>
+/// message --- The message to read the body of.</br>
+/// limits --- The largest body to accept.
+pub fn read_body<R: Read>(stream: &mut R, message: &mut MessageHTTP, limits: ParseLimits) -> Result<(), ParseError> {
+    match message.check_framing()? {
+        BodyFraming::ContentLength(length) => {
+            if length > limits.max_body_bytes as u64 {
+                return Err(ParseError::BodyTooLarge);
+            }
+            message.message_body = vec![0; length as usize];
+            read_exact(stream, &mut message.message_body)?;
+        },
+        BodyFraming::Chunked => {
+            message.message_body = read_chunked(stream, limits)?;
+            message.decode_chunked()?;
+        },
+        BodyFraming::Close => {
+            stream.take(limits.max_body_bytes as u64 + 1)
+                .read_to_end(&mut message.message_body)
+                .map_err(|e| ParseError::Io(e.to_string()))?;
+            if message.message_body.len() > limits.max_body_bytes {
+                return Err(ParseError::BodyTooLarge);
+            }
+        },
+        BodyFraming::Error => unreachable!("`check_framing` never returns `BodyFraming::Error`.")
+    }
+    Ok(())
+}
+
+/// Reads the chunks and trailer section of a chunked body.</br>
+/// Returns the body with its chunked framing still in place.
+///
+/// # Params
+///
+/// stream --- The stream to read the body from.</br>
+/// limits --- The largest body to accept, the trailer section shares the header limit.
+fn read_chunked<R: Read>(stream: &mut R, limits: ParseLimits) -> Result<Vec<u8>, ParseError> {
+    let mut raw = Vec::new();
+    let mut length: usize = 0;
+    
+    loop {
+        let line = read_line(stream, limits.max_header_bytes, 0)?;
+        raw.extend_from_slice(&line);
+        
+        let size = match str::from_utf8(&line[..line.len() - 2]) {
+            Ok(line) => chunked::chunk_size(line)?,
+            Err(_) => return Err(ParseError::InvalidUtf8)
+        };
+        if size == 0 {
+            break;
+        }
+        
+        length = length.saturating_add(size);
+        if length > limits.max_body_bytes {
+            return Err(ParseError::BodyTooLarge);
+        }
+        // The chunk data is followed by a line break.
+        let start = raw.len();
+        raw.resize(start + size + 2, 0);
+        read_exact(stream, &mut raw[start..])?;
+    }
+    
+    // The trailer section ends with an empty line.
+    let mut trailers = 0;
+    loop {
+        let line = read_line(stream, limits.max_header_bytes, trailers)?;
+        
+        trailers += line.len();
+        raw.extend_from_slice(&line);
+        if line == b"\r\n" {
+            return Ok(raw);
+        }
+    }
+}
+
+/// Reads a line from `stream`, including its terminating `\r\n`.
+///
+/// # Params
+///
+/// stream --- The stream to read the line from.</br>
+/// limit --- The most bytes the section the line is part of may have.</br>
+/// used --- The bytes of the section before the line.
+fn read_line<R: Read>(stream: &mut R, limit: usize, used: usize) -> Result<Vec<u8>, ParseError> {
+    let mut line = Vec::new();
+    
+    while !line.ends_with(b"\r\n") {
+        match read_byte(stream)? {
+            Some(byte) => line.push(byte),
+            None => return Err(ParseError::UnexpectedEof)
+        }
+        if used + line.len() > limit {
+            return Err(ParseError::HeaderTooLarge { limit, size: used + line.len() });
+        }
+    }
+    Ok(line)
+}
+
+/// Reads a single byte from `stream`, or `None` at the end of the stream.
+///
+/// # Params
+///
+/// stream --- The stream to read from.
+fn read_byte<R: Read>(stream: &mut R) -> Result<Option<u8>, ParseError> {
+    let mut byte = [0];
+    
+    loop {
+        match stream.read(&mut byte) {
+            Ok(0) => return Ok(None),
+            Ok(_) => return Ok(Some(byte[0])),
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => (),
+            Err(e) => return Err(ParseError::Io(e.to_string()))
+        }
+    }
+}
+
+/// Fills `buffer` from `stream`.
+///
+/// # Params
+///
+/// stream --- The stream to read from.</br>
+/// buffer --- The buffer to fill.
+fn read_exact<R: Read>(stream: &mut R, buffer: &mut [u8]) -> Result<(), ParseError> {
+    stream.read_exact(buffer)
+        .map_err(
+            |e| match e.kind() {
+                io::ErrorKind::UnexpectedEof => ParseError::UnexpectedEof,
+                _ => ParseError::Io(e.to_string())
+            }
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::start_line::HTTP2_PREFACE;
+    
+    /// A reader which returns at most one byte per read, as a slow client might.
+    struct OneByte<'a> {
+        bytes: &'a [u8]
+    }
+    
+    impl<'a> Read for OneByte<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.bytes.is_empty() || buf.is_empty() {
+                return Ok(0);
+            }
+            buf[0] = self.bytes[0];
+            self.bytes = &self.bytes[1..];
+            Ok(1)
+        }
+    }
+    
+    /// Reads a message from the passed bytes one byte at a time.
+    fn read(bytes: &[u8], limits: ParseLimits) -> Result<MessageHTTP, ParseError> {
+        read_message_with_limits(&mut OneByte { bytes }, limits)
+    }
+
+    #[test]
+    fn test_read_message() {
+        // The header section is longer than any single read buffer the server used.
+        let head = format!("GET / HTTP/1.1\r\nHost: example.com\r\nCookie: {}\r\n\r\n", "a".repeat(600));
+        assert_eq!(
+            read(head.as_bytes(), ParseLimits::default()),
+            MessageHTTP::from(&head),
+            "Test read_message-1 failed."
+        );
+        
+        let post = "POST /form HTTP/1.1\r\nHost: example.com\r\nContent-Length: 11\r\n\r\nhello world";
+        assert_eq!(
+            read(post.as_bytes(), ParseLimits::default()).map(|message| message.message_body),
+            Ok(b"hello world".to_vec()),
+            "Test read_message-2 failed."
+        );
+        
+        let chunked = b"POST /upload HTTP/1.1\r\nHost: example.com\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n6;ext=1\r\n world\r\n0\r\nChecksum: abc123\r\n\r\n";
+        let message = read(chunked, ParseLimits::default()).unwrap();
+        assert_eq!(
+            (message.message_body.as_slice(), message.header(headers::CONTENT_LENGTH), message.header("Checksum")),
+            (&b"hello world"[..], Some("11"), Some("abc123")),
+            "Test read_message-3 failed."
+        );
+    }
+    #[test]
+    fn test_read_message_with_mode() {
+        let request = b"get / HTTP/1.1\r\nHost: example.com\r\n\r\n";
+        
+        assert_eq!(
+            read_message_with_mode(&mut OneByte { bytes: request }, ParseLimits::default(), ParseMode::Lenient).map(|message| message.start_line),
+            MessageHTTP::from_bytes(request).map(|message| message.start_line),
+            "Test read_message_with_mode-1 failed."
+        );
+        assert_eq!(
+            read_message_with_mode(&mut OneByte { bytes: request }, ParseLimits::default(), ParseMode::Strict),
+            Err(ParseError::NonCanonicalMethod(String::from("get"))),
+            "Test read_message_with_mode-2 failed."
+        );
+        
+        let request = b"POST / HTTP/1.1\nHost: example.com\nContent-Length: 2\n\nhi";
+        assert_eq!(
+            (
+                read_message_with_mode(&mut OneByte { bytes: request }, ParseLimits::default(), ParseMode::Lenient).map(|message| message.message_body),
+                read_message_with_mode(&mut OneByte { bytes: request }, ParseLimits::default(), ParseMode::Strict)
+            ),
+            (Ok(b"hi".to_vec()), Err(ParseError::BareLineFeed { position: 15 })),
+            "Test read_message_with_mode-3 failed."
+        );
+    }
+    #[test]
+    fn test_read_message_with_http09() {
+        let mut stream = OneByte { bytes: b"GET /a\r\nGET /b HTTP/1.1\r\nHost: a\r\n\r\n" };
+        
+        let first = read_message_with_http09(&mut stream, ParseLimits::default(), ParseMode::Strict, true).unwrap();
+        let second = read_message_with_http09(&mut stream, ParseLimits::default(), ParseMode::Strict, true).unwrap();
+        assert_eq!(
+            (first.start_line.path(), first.start_line.version(), second.start_line.path(), second.start_line.version()),
+            (Some("/a"), Version::HTTP_09, Some("/b"), Version::HTTP_11),
+            "Test read_message_with_http09-1 failed."
+        );
+        assert_eq!(
+            read_message_with_mode(&mut OneByte { bytes: b"GET /a\r\n" }, ParseLimits::default(), ParseMode::Strict),
+            Err(ParseError::UnexpectedEof),
+            "Test read_message_with_http09-2 failed."
+        );
+    }
+    #[test]
+    fn test_read_message_pipelined() {
+        let bytes = b"POST /a HTTP/1.1\r\nHost: example.com\r\nContent-Length: 3\r\n\r\nabcGET /b HTTP/1.1\r\nHost: example.com\r\n\r\n";
+        let mut stream = OneByte { bytes };
+        
+        let first = read_message(&mut stream).unwrap();
+        let second = read_message(&mut stream).unwrap();
+        assert_eq!(
+            (first.start_line.as_request().map(|line| line.1.as_str()), first.message_body.as_slice(), second.start_line.as_request().map(|line| line.1.as_str())),
+            (Some("/a"), &b"abc"[..], Some("/b")),
+            "Test read_message-4 failed."
+        );
+        assert_eq!(
+            read_message(&mut stream),
+            Err(ParseError::ConnectionClosed),
+            "Test read_message-5 failed."
+        );
+    }
+    #[test]
+    fn test_read_message_http2_preface() {
+        assert_eq!(
+            read(HTTP2_PREFACE, ParseLimits::default()),
+            Err(ParseError::Http2Preface),
+            "Test read_message HTTP/2 preface failed."
+        );
+    }
+    #[test]
+    fn test_read_message_errors() {
+        let tests: [(&[u8], ParseError); 8] = [
+            (b"", ParseError::ConnectionClosed),
+            (b"GET / HTTP/1.1\r\nHost: exa", ParseError::UnexpectedEof),
+            (b"POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\nshort", ParseError::UnexpectedEof),
+            (b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhel", ParseError::UnexpectedEof),
+            (b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\nZ\r\n", ParseError::InvalidChunk(String::from("Z"))),
+            (b"POST / HTTP/1.1\r\nContent-Length: 13\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nSMUGGLED", ParseError::AmbiguousFraming),
+            (b"POST / HTTP/1.1\r\nContent-Length: 3\r\nTransfer-Encoding: chunked\r\n\r\n8\r\nSMUGGLED\r\n0\r\n\r\n", ParseError::AmbiguousFraming),
+            (b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked, gzip\r\n\r\n", ParseError::InvalidFraming)
+        ];
+        for &(bytes, ref error) in tests.iter() {
+            assert_eq!(
+                read(bytes, ParseLimits::default()).as_ref(),
+                Err(error),
+                "Test read_message errors failed for `{}`.", String::from_utf8_lossy(bytes)
+            );
+        }
+    }
+    #[test]
+    fn test_read_message_limits() {
+        let limits = ParseLimits { max_header_bytes: 64, max_body_bytes: 8, ..ParseLimits::default() };
+        let tests: [(&[u8], ParseError); 4] = [
+            (b"GET / HTTP/1.1\r\nHost: example.com\r\nUser-Agent: a very long user agent\r\n\r\n", ParseError::HeaderTooLarge { limit: 64, size: 65 }),
+            (b"POST / HTTP/1.1\r\nContent-Length: 9\r\n\r\n123456789", ParseError::BodyTooLarge),
+            (b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n5\r\n12345\r\n5\r\n12345\r\n0\r\n\r\n", ParseError::BodyTooLarge),
+            (b"HTTP/1.1 200 OK\r\n\r\n123456789", ParseError::BodyTooLarge)
+        ];
+        for &(bytes, ref error) in tests.iter() {
+            assert_eq!(
+                read(bytes, limits).as_ref(),
+                Err(error),
+                "Test read_message limits failed for `{}`.", String::from_utf8_lossy(bytes)
+            );
+        }
+        
+        assert_eq!(
+            read(b"POST / HTTP/1.1\r\nContent-Length: 8\r\n\r\n12345678", limits).map(|message| message.message_body),
+            Ok(b"12345678".to_vec()),
+            "Test read_message limits failed at the limit."
+        );
+        
+        let limits = ParseLimits { max_start_line: 15, max_header_count: 1, ..ParseLimits::default() };
+        assert!(
+            read(b"GET /a HTTP/1.1\r\nHost: example.com\r\n\r\n", limits).is_ok(),
+            "Test read_message limits failed at the start line and Header field limits."
+        );
+        assert_eq!(
+            read(b"GET /ab HTTP/1.1\r\nHost: example.com\r\n\r\n", limits),
+            Err(ParseError::UriTooLong { limit: 15, size: 16 }),
+            "Test read_message limits failed for a long start line."
+        );
+        assert_eq!(
+            read(&[b'A'; 1024], limits),
+            Err(ParseError::UriTooLong { limit: 15, size: 17 }),
+            "Test read_message limits failed for an unterminated start line."
+        );
+        assert_eq!(
+            read(b"GET /a HTTP/1.1\r\nHost: example.com\r\nAccept: */*\r\n\r\n", limits),
+            Err(ParseError::TooManyHeaderFields { limit: 1, count: 2 }),
+            "Test read_message limits failed for too many Header fields."
+        );
+    }
+    #[test]
+    fn test_read_message_stops_early() {
+        let limits = ParseLimits { max_start_line: 15, max_header_bytes: 64, ..ParseLimits::default() };
+        let long_target = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(1024));
+        let long_head = format!("GET / HTTP/1.1\r\nCookie: {}\r\n\r\n", "a".repeat(1024));
+        let long_trailer = format!("POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n0\r\nX: {}\r\n\r\n", "a".repeat(1024));
+        let tests = [
+            (long_target.as_bytes(), ParseError::UriTooLong { limit: 15, size: 17 }, 17),
+            (long_head.as_bytes(), ParseError::HeaderTooLarge { limit: 64, size: 65 }, 65),
+            (long_trailer.as_bytes(), ParseError::HeaderTooLarge { limit: 64, size: 65 }, 115)
+        ];
+        
+        for &(bytes, ref error, consumed) in tests.iter() {
+            let mut stream = OneByte { bytes };
+            
+            assert_eq!(
+                read_message_with_limits(&mut stream, limits).as_ref(),
+                Err(error),
+                "Test read_message stops early failed for `{}`.", String::from_utf8_lossy(&bytes[..consumed])
+            );
+            assert_eq!(
+                bytes.len() - stream.bytes.len(),
+                consumed,
+                "Test read_message stops early failed to stop for `{}`.", String::from_utf8_lossy(&bytes[..consumed])
+            );
+        }
+    }
+    #[test]
+    fn test_read_message_linear() {
+        use std::time::{Duration, Instant};
+        
+        // A header section just inside the default limits, rescanning it after every byte
+        // takes seconds.
+        let limits = ParseLimits::default();
+        let head = format!("GET / HTTP/1.1\r\nCookie: {}\r\n\r\n", "a".repeat(limits.max_header_bytes - 40));
+        let start = Instant::now();
+        
+        assert!(
+            read(head.as_bytes(), limits).is_ok(),
+            "Test read_message_linear-1 failed."
+        );
+        assert!(
+            start.elapsed() < Duration::from_secs(1),
+            "Test read_message_linear-2 failed."
+        );
+    }
+}
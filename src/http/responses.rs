@@ -0,0 +1,437 @@
+//! `responses` is a module of ready-made responses for common situations.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::time::{Duration, SystemTime};
+use super::{MessageHTTP, ResponseBuilder, BuildError, ParseError, StatusCode, Method};
+use super::headers;
+use super::date::RetryAfter;
+
+/// The `Content-Type` of the plain-text bodies of the ready-made responses.
+static TEXT_PLAIN: &str = "text/plain; charset=utf-8";
+
+/// Returns a `200 OK` with the passed body.
+///
+/// # Params
+///
+/// body --- The bytes of the body.
+pub fn ok<B: Into<Vec<u8>>>(body: B) -> MessageHTTP {
+    ResponseBuilder::new(StatusCode::OK)
+        .body(body)
+        .build()
+        .expect("A `200 OK` is always valid.")
+}
+
+/// Returns a `200 OK` with the passed body and `Content-Type`.
+///
+/// # Panics
+///
+/// Panics if the `Content-Type` contains a CR, LF or NUL.
+///
+/// # Params
+///
+/// body --- The bytes of the body.</br>
+/// content_type --- The media type of the body, e.g. `text/html`.
+pub fn ok_with_type<B: Into<Vec<u8>>>(body: B, content_type: &str) -> MessageHTTP {
+    ResponseBuilder::new(StatusCode::OK)
+        .header(headers::CONTENT_TYPE, content_type)
+        .body(body)
+        .build()
+        .expect("Called `ok_with_type` with a `Content-Type` which is not a valid Header Field value.")
+}
+
+/// Returns a `204 No Content`, which never has a body.
+pub fn no_content() -> MessageHTTP {
+    ResponseBuilder::new(StatusCode::NO_CONTENT)
+        .build()
+        .expect("An empty `204 No Content` is always valid.")
+}
+
+/// Returns a `400 Bad Request` with the passed reason as a plain-text body.
+///
+/// # Params
+///
+/// reason --- Why the request was bad.
+pub fn bad_request(reason: &str) -> MessageHTTP {
+    text(StatusCode::BAD_REQUEST, reason)
+}
+
+/// Returns a `404 Not Found` with a plain-text body.
+pub fn not_found() -> MessageHTTP {
+    text(StatusCode::NOT_FOUND, "Not Found")
+}
+
+/// Returns a `500 Internal Server Error` with a plain-text body.
+pub fn internal_server_error() -> MessageHTTP {
+    text(StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error")
+}
+
+/// Returns a response with the passed status code and a plain-text body.
+///
+/// # Params
+///
+/// code --- The status code of the response.</br>
+/// body --- The text of the body.
+fn text(code: StatusCode, body: &str) -> MessageHTTP {
+    ResponseBuilder::new(code)
+        .header(headers::CONTENT_TYPE, TEXT_PLAIN)
+        .body(body)
+        .build()
+        .expect("A plain-text response is always valid.")
+}
+
+/// Returns a response with a `Retry-After` of the passed number of seconds.
+///
+/// Fails if the status code is not a `3xx`, `429 Too Many Requests` or
+/// `503 Service Unavailable`.
+///
+/// # Params
+///
+/// status --- The status code of the response.</br>
+/// secs --- How many seconds the client should wait before retrying.
+pub fn retry_after_secs(status: u16, secs: u64) -> Result<MessageHTTP, BuildError> {
+    retry_after(status, RetryAfter::Delay(Duration::from_secs(secs)))
+}
+
+/// Returns a response with a `Retry-After` of the passed time, as an HTTP-date.
+///
+/// Fails if the status code is not a `3xx`, `429 Too Many Requests` or
+/// `503 Service Unavailable`.
+///
+/// # Params
+///
+/// status --- The status code of the response.</br>
+/// time --- When the client may retry.
+pub fn retry_after_at(status: u16, time: SystemTime) -> Result<MessageHTTP, BuildError> {
+    retry_after(status, RetryAfter::Date(time))
+}
+
+/// Returns an empty response with the passed `Retry-After`.
+///
+/// # Params
+///
+/// status --- The status code of the response.</br>
+/// value --- When the client may retry.
+fn retry_after(status: u16, value: RetryAfter) -> Result<MessageHTTP, BuildError> {
+    let code = StatusCode(status);
+    
+    if !code.is_redirection() && code != StatusCode::TOO_MANY_REQUESTS && code != StatusCode::SERVICE_UNAVAILABLE {
+        return Err(BuildError::RetryAfterNotAllowed(status));
+    }
+    
+    let builder = ResponseBuilder::new(code)
+        .header(headers::RETRY_AFTER, &value.to_string());
+    // A `304 Not Modified` can not be given a body, even an empty one.
+    if code.allows_body() {
+        builder.body(Vec::new()).build()
+    } else {
+        builder.build()
+    }
+}
+
+/// Returns the response to a server-wide `OPTIONS *` request, advertising every method
+/// recognised by the crate in the `Allow` Header Field.
+pub fn server_options() -> MessageHTTP {
+    ResponseBuilder::new(StatusCode::OK)
+        .header(headers::ALLOW, &Method::ALL.iter().map(Method::as_str).collect::<Vec<_>>().join(", "))
+        .body(Vec::new())
+        .build()
+        .expect("The `Allow` is always a valid Header Field value.")
+}
+
+/// Returns a `505 HTTP Version Not Supported` which closes the connection, e.g. for a
+/// client which sent the HTTP/2 connection preface.
+pub fn http_version_not_supported() -> MessageHTTP {
+    ResponseBuilder::new(StatusCode::HTTP_VERSION_NOT_SUPPORTED)
+        .header(headers::CONNECTION, "close")
+        .body(Vec::new())
+        .build()
+        .expect("`close` is always a valid Header Field value.")
+}
+
+/// Returns a `414 URI Too Long` which closes the connection, for a request whose start
+/// line passed `ParseLimits::max_start_line`.
+///
+/// The rest of the request is left unread so the connection can not be reused.
+pub fn uri_too_long() -> MessageHTTP {
+    ResponseBuilder::new(StatusCode::URI_TOO_LONG)
+        .header(headers::CONNECTION, "close")
+        .body(Vec::new())
+        .build()
+        .expect("`close` is always a valid Header Field value.")
+}
+
+/// Returns a `431 Request Header Fields Too Large` which closes the connection, for a
+/// request whose header section passed its `ParseLimits`.
+///
+/// The rest of the request is left unread so the connection can not be reused.
+pub fn header_fields_too_large() -> MessageHTTP {
+    ResponseBuilder::new(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE)
+        .header(headers::CONNECTION, "close")
+        .body(Vec::new())
+        .build()
+        .expect("`close` is always a valid Header Field value.")
+}
+
+/// Returns a `413 Content Too Large` which closes the connection, for a request whose body
+/// passed `ParseLimits::max_body_bytes`.
+///
+/// The rest of the body is left unread so the connection can not be reused.
+pub fn content_too_large() -> MessageHTTP {
+    ResponseBuilder::new(StatusCode::CONTENT_TOO_LARGE)
+        .header(headers::CONNECTION, "close")
+        .body(Vec::new())
+        .build()
+        .expect("`close` is always a valid Header Field value.")
+}
+
+/// Returns a `408 Request Timeout` which closes the connection, for a request which started
+/// but did not finish arriving in time.
+pub fn request_timeout() -> MessageHTTP {
+    ResponseBuilder::new(StatusCode::REQUEST_TIMEOUT)
+        .header(headers::CONNECTION, "close")
+        .body(Vec::new())
+        .build()
+        .expect("`close` is always a valid Header Field value.")
+}
+
+/// Returns the response to a request which could not be read, which closes the connection.
+///
+/// The response is a `505 HTTP Version Not Supported` for the HTTP/2 connection preface, a
+/// `414 URI Too Long` or `431 Request Header Fields Too Large` for a head which passed its
+/// `ParseLimits`, a `413 Content Too Large` for a body which passed them, or otherwise a
+/// `400 Bad Request` with the error as its body.</br>
+/// Returns `None` if the connection closed before the request or failed, as there is no
+/// one to answer.
+///
+/// # Params
+///
+/// error --- The error reading the request.
+pub fn parse_error(error: &ParseError) -> Option<MessageHTTP> {
+    match *error {
+        ParseError::ConnectionClosed | ParseError::Io(_) => None,
+        ParseError::Http2Preface => Some(http_version_not_supported()),
+        ParseError::UriTooLong { .. } => Some(uri_too_long()),
+        ParseError::HeaderTooLarge { .. } | ParseError::TooManyHeaderFields { .. } => Some(header_fields_too_large()),
+        ParseError::BodyTooLarge => Some(content_too_large()),
+        ref error => {
+            let mut res = bad_request(&error.to_string());
+            
+            res.set_connection_close();
+            Some(res)
+        }
+    }
+}
+
+/// Returns a `405 Method Not Allowed` with an `Allow` Header Field listing the methods the
+/// target resource does support.
+///
+/// The methods are normalized as by `MessageHTTP::with_allow`.
+///
+/// # Panics
+///
+/// Panics if a method is not a token.
+///
+/// # Params
+///
+/// allowed --- The methods the target resource supports.
+pub fn method_not_allowed(allowed: &[&str]) -> MessageHTTP {
+    let mut res = ResponseBuilder::new(StatusCode::METHOD_NOT_ALLOWED)
+        .body(Vec::new())
+        .build()
+        .expect("An empty `405 Method Not Allowed` is always valid.");
+    
+    res.with_allow(allowed)
+        .expect("Called `method_not_allowed` with a method which is not a token.");
+    res
+}
+
+/// Returns a `501 Not Implemented`, e.g. for a request whose `Method` is not recognised.
+pub fn not_implemented() -> MessageHTTP {
+    ResponseBuilder::new(StatusCode::NOT_IMPLEMENTED)
+        .body(Vec::new())
+        .build()
+        .expect("An empty `501 Not Implemented` is always valid.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{HTTP, BuildError};
+    use super::super::target::RequestTarget;
+
+    #[test]
+    fn test_ok() {
+        assert_eq!(
+            ok("hello").to_http().unwrap(),
+            "HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello",
+            "Test responses::ok-1 failed."
+        );
+        assert_eq!(
+            ok_with_type(b"<p>hi</p>".to_vec(), "text/html").to_http().unwrap(),
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: 9\r\n\r\n<p>hi</p>",
+            "Test responses::ok_with_type-1 failed."
+        );
+        
+        let mut response = ok("{}");
+        response.set_header(headers::CONTENT_TYPE, "application/json").unwrap();
+        assert_eq!(
+            response.to_http().unwrap(),
+            "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nContent-Type: application/json\r\n\r\n{}",
+            "Test responses::ok-2 failed."
+        );
+        assert_eq!(
+            no_content().to_http().unwrap(),
+            "HTTP/1.1 204 No Content\r\n\r\n",
+            "Test responses::no_content-1 failed."
+        );
+    }
+    #[test]
+    fn test_error_responses() {
+        assert_eq!(
+            not_found().to_http().unwrap(),
+            "HTTP/1.1 404 Not Found\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: 9\r\n\r\nNot Found",
+            "Test responses::not_found-1 failed."
+        );
+        assert_eq!(
+            bad_request("Missing `name`.").to_http().unwrap(),
+            "HTTP/1.1 400 Bad Request\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: 15\r\n\r\nMissing `name`.",
+            "Test responses::bad_request-1 failed."
+        );
+        assert_eq!(
+            internal_server_error().body_as_string(),
+            Ok(String::from("Internal Server Error")),
+            "Test responses::internal_server_error-1 failed."
+        );
+    }
+    #[test]
+    fn test_retry_after() {
+        use std::time::UNIX_EPOCH;
+        
+        assert_eq!(
+            retry_after_secs(429, 120).map(|response| response.to_http().unwrap()),
+            Ok(String::from("HTTP/1.1 429 Too Many Requests\r\nRetry-After: 120\r\nContent-Length: 0\r\n\r\n")),
+            "Test responses::retry_after_secs-1 failed."
+        );
+        assert_eq!(
+            retry_after_at(503, UNIX_EPOCH + Duration::from_secs(784111777)).map(|response| response.to_http().unwrap()),
+            Ok(String::from("HTTP/1.1 503 Service Unavailable\r\nRetry-After: Sun, 06 Nov 1994 08:49:37 GMT\r\nContent-Length: 0\r\n\r\n")),
+            "Test responses::retry_after_at-1 failed."
+        );
+        assert!(
+            retry_after_secs(301, 5).is_ok() && retry_after_secs(304, 5).is_ok(),
+            "Test responses::retry_after_secs-2 failed."
+        );
+        for &status in [200, 404, 500, 99].iter() {
+            assert_eq!(
+                retry_after_secs(status, 5),
+                Err(BuildError::RetryAfterNotAllowed(status)),
+                "Test responses::retry_after_secs-3 failed for `{}`.", status
+            );
+        }
+    }
+    #[test]
+    fn test_server_options() {
+        let request = MessageHTTP::from("OPTIONS * HTTP/1.1\r\nHost: example.com\r\n\r\n").unwrap();
+        
+        assert_eq!(
+            request.start_line.target_form(),
+            Some(Ok(RequestTarget::AsteriskForm)),
+            "Test responses::server_options-1 failed."
+        );
+        
+        assert_eq!(
+            server_options().to_http().unwrap(),
+            "HTTP/1.1 200 OK\r\nAllow: GET, HEAD, POST, PUT, DELETE, CONNECT, OPTIONS, TRACE, PATCH\r\nContent-Length: 0\r\n\r\n",
+            "Test responses::server_options-2 failed."
+        );
+    }
+    #[test]
+    fn test_http_version_not_supported() {
+        assert_eq!(
+            http_version_not_supported().to_http().unwrap(),
+            "HTTP/1.1 505 HTTP Version Not Supported\r\nConnection: close\r\nContent-Length: 0\r\n\r\n",
+            "Test responses::http_version_not_supported-1 failed."
+        );
+    }
+    #[test]
+    fn test_too_large() {
+        assert_eq!(
+            uri_too_long().to_http().unwrap(),
+            "HTTP/1.1 414 URI Too Long\r\nConnection: close\r\nContent-Length: 0\r\n\r\n",
+            "Test responses::uri_too_long-1 failed."
+        );
+        assert_eq!(
+            header_fields_too_large().to_http().unwrap(),
+            "HTTP/1.1 431 Request Header Fields Too Large\r\nConnection: close\r\nContent-Length: 0\r\n\r\n",
+            "Test responses::header_fields_too_large-1 failed."
+        );
+    }
+    #[test]
+    fn test_parse_error() {
+        let tests = [
+            (ParseError::ConnectionClosed, None),
+            (ParseError::Io(String::from("timed out")), None),
+            (ParseError::Http2Preface, Some(http_version_not_supported())),
+            (ParseError::UriTooLong { limit: 1, size: 2 }, Some(uri_too_long())),
+            (ParseError::TooManyHeaderFields { limit: 1, count: 2 }, Some(header_fields_too_large())),
+            (ParseError::BodyTooLarge, Some(content_too_large()))
+        ];
+        for (error, res) in tests.iter() {
+            assert_eq!(
+                parse_error(error).as_ref(),
+                res.as_ref(),
+                "Test responses::parse_error failed for `{:?}`.", error
+            );
+        }
+        
+        assert_eq!(
+            parse_error(&ParseError::AmbiguousFraming).unwrap().to_http().unwrap(),
+            "HTTP/1.1 400 Bad Request\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: 78\r\nConnection: close\r\n\r\nAmbiguous message body framing, both a Transfer-Encoding and a Content-Length.",
+            "Test responses::parse_error-1 failed."
+        );
+    }
+    #[test]
+    fn test_method_not_allowed() {
+        assert_eq!(
+            method_not_allowed(&["get", "HEAD", "Post", "GET", " head ", "x-custom"]).to_http().unwrap(),
+            "HTTP/1.1 405 Method Not Allowed\r\nContent-Length: 0\r\nAllow: GET, HEAD, POST, X-CUSTOM\r\n\r\n",
+            "Test responses::method_not_allowed-1 failed."
+        );
+        assert_eq!(
+            method_not_allowed(&[]).header(headers::ALLOW),
+            Some(""),
+            "Test responses::method_not_allowed-2 failed."
+        );
+        
+        let mut response = not_implemented();
+        assert_eq!(
+            (response.with_allow(&["options", "GET"]), response.header(headers::ALLOW)),
+            (Ok(()), Some("OPTIONS, GET")),
+            "Test MessageHTTP::with_allow-1 failed."
+        );
+        assert_eq!(
+            (response.with_allow(&["GET", "BR EW"]), response.header(headers::ALLOW)),
+            (Err(BuildError::InvalidMethod(String::from("BR EW"))), Some("OPTIONS, GET")),
+            "Test MessageHTTP::with_allow-2 failed."
+        );
+    }
+    #[test]
+    fn test_not_implemented() {
+        let request = MessageHTTP::from("BREW /pot HTTP/1.1\r\nHost: example.com\r\n\r\n").unwrap();
+        
+        assert_eq!(
+            request.start_line.method().map(|method| method.is_recognized()),
+            Some(false),
+            "Test responses::not_implemented-1 failed."
+        );
+        assert_eq!(
+            not_implemented().to_http().unwrap(),
+            "HTTP/1.1 501 Not Implemented\r\nContent-Length: 0\r\n\r\n",
+            "Test responses::not_implemented-2 failed."
+        );
+    }
+}
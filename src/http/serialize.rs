@@ -0,0 +1,284 @@
+//! `serialize` is a module to implement `Serialize` and `Deserialize` for HTTP messages and
+//! their components when the `serde` feature is enabled.
+//!
+//! A `MessageHTTP` is a map of its `start_line`, `header_fields` and `message_body`, with
+//! the body encoded as base64 so binary bodies survive formats such as JSON. A
+//! `RequestLine` is a map of its `method`, `target` and `version` and a `StatusLine` a map
+//! of its `version`, `code` and `reason`, where methods and versions are plain strings.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::fmt;
+use std::string::String;
+use serde::ser::{Serialize, Serializer, SerializeStruct};
+use serde::de::{self, Deserialize, Deserializer, Visitor, MapAccess, IgnoredAny};
+use super::{MessageHTTP, StatusCode, Version, Method};
+use super::base64;
+use super::builder::check_target;
+use super::header_field::HeaderField;
+use super::start_line::StartLine;
+
+/// The fields of a serialized `HeaderField`.
+static HEADER_FIELD_FIELDS: &[&str] = &["name", "value"];
+/// The fields of a serialized `MessageHTTP`.
+static MESSAGE_FIELDS: &[&str] = &["start_line", "header_fields", "message_body"];
+
+impl Serialize for HeaderField {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("HeaderField", 2)?;
+        
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("value", &self.value)?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for HeaderField {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<HeaderField, D::Error> {
+        /// Visits the map of a `HeaderField`.
+        struct FieldVisitor;
+        
+        impl<'de> Visitor<'de> for FieldVisitor {
+            type Value = HeaderField;
+            
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a Header Field")
+            }
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<HeaderField, A::Error> {
+                let (mut name, mut value) = (None, None);
+                
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "name" => name = Some(map.next_value::<String>()?),
+                        "value" => value = Some(map.next_value::<String>()?),
+                        _ => { map.next_value::<IgnoredAny>()?; }
+                    }
+                }
+                
+                let name = name.ok_or_else(|| de::Error::missing_field("name"))?;
+                let value = value.ok_or_else(|| de::Error::missing_field("value"))?;
+                // A value with a line break would inject Header fields when written.
+                HeaderField::new(&name, &value).map_err(de::Error::custom)
+            }
+        }
+        
+        deserializer.deserialize_struct("HeaderField", HEADER_FIELD_FIELDS, FieldVisitor)
+    }
+}
+
+impl Serialize for StartLine {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            StartLine::RequestLine { ref method, ref target, version } => {
+                let mut state = serializer.serialize_struct("RequestLine", 3)?;
+                
+                state.serialize_field("method", method.as_str())?;
+                state.serialize_field("target", target)?;
+                state.serialize_field("version", &version.to_string())?;
+                state.end()
+            },
+            StartLine::StatusLine { version, code, ref reason } => {
+                let mut state = serializer.serialize_struct("StatusLine", 3)?;
+                
+                state.serialize_field("version", &version.to_string())?;
+                state.serialize_field("code", &code.as_u16())?;
+                state.serialize_field("reason", reason)?;
+                state.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for StartLine {
+    /// Deserializes a `RequestLine` from a map with a `method` and a `StatusLine` from a
+    /// map with a `code`, a missing `reason` is `None`.
+    ///
+    /// The target is checked as by `RequestBuilder::build`, the code must be from 100 to
+    /// 599 and the reason must not contain control characters.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<StartLine, D::Error> {
+        /// Visits the map of a `StartLine`.
+        struct LineVisitor;
+        
+        impl<'de> Visitor<'de> for LineVisitor {
+            type Value = StartLine;
+            
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a Request line or a Status line")
+            }
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<StartLine, A::Error> {
+                let (mut method, mut target, mut version, mut code, mut reason) = (None, None, None, None, None);
+                
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "method" => method = Some(map.next_value::<String>()?),
+                        "target" => target = Some(map.next_value::<String>()?),
+                        "version" => version = Some(map.next_value::<String>()?),
+                        "code" => code = Some(map.next_value()?),
+                        "reason" => reason = map.next_value::<Option<String>>()?,
+                        _ => { map.next_value::<IgnoredAny>()?; }
+                    }
+                }
+                
+                let version = version.ok_or_else(|| de::Error::missing_field("version"))?;
+                let version = Version::from(&version).map_err(de::Error::custom)?;
+                match (method, code) {
+                    (Some(method), None) => {
+                        let target = target.ok_or_else(|| de::Error::missing_field("target"))?;
+                        
+                        check_target(&target).map_err(de::Error::custom)?;
+                        Ok(
+                            StartLine::RequestLine {
+                                method: Method::from(&method).map_err(de::Error::custom)?,
+                                target,
+                                version
+                            }
+                        )
+                    },
+                    (None, Some(code)) => {
+                        if reason.as_ref().is_some_and(|reason: &String| reason.chars().any(|c| c.is_control() && c != '\t')) {
+                            return Err(de::Error::custom("the `reason` contains control characters"));
+                        }
+                        Ok(
+                            StartLine::StatusLine {
+                                version,
+                                code: StatusCode::new(code).map_err(de::Error::custom)?,
+                                reason
+                            }
+                        )
+                    },
+                    (Some(_), Some(_)) => Err(de::Error::custom("a Start line can not have both a `method` and a `code`")),
+                    (None, None) => Err(de::Error::missing_field("method"))
+                }
+            }
+        }
+        
+        deserializer.deserialize_map(LineVisitor)
+    }
+}
+
+impl Serialize for MessageHTTP {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("MessageHTTP", 3)?;
+        
+        state.serialize_field("start_line", &self.start_line)?;
+        state.serialize_field("header_fields", &self.header_fields)?;
+        state.serialize_field("message_body", &base64::encode(&self.message_body))?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for MessageHTTP {
+    /// Deserializes a `MessageHTTP` exactly as it was serialized, the body is not checked
+    /// against the `Content-Length`.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<MessageHTTP, D::Error> {
+        /// Visits the map of a `MessageHTTP`.
+        struct MessageVisitor;
+        
+        impl<'de> Visitor<'de> for MessageVisitor {
+            type Value = MessageHTTP;
+            
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a HTTP message")
+            }
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<MessageHTTP, A::Error> {
+                let (mut start_line, mut header_fields, mut message_body) = (None, None, None);
+                
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "start_line" => start_line = Some(map.next_value()?),
+                        "header_fields" => header_fields = Some(map.next_value()?),
+                        "message_body" => message_body = Some(map.next_value::<String>()?),
+                        _ => { map.next_value::<IgnoredAny>()?; }
+                    }
+                }
+                
+                let message_body = match message_body {
+                    Some(body) => base64::decode(&body).ok_or_else(|| de::Error::custom("the `message_body` is not valid base64"))?,
+                    None => Vec::new()
+                };
+                Ok(
+                    MessageHTTP::new(
+                        start_line.ok_or_else(|| de::Error::missing_field("start_line"))?,
+                        header_fields.unwrap_or_default(),
+                        message_body
+                    )
+                )
+            }
+        }
+        
+        deserializer.deserialize_struct("MessageHTTP", MESSAGE_FIELDS, MessageVisitor)
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn test_serialize_message() {
+        let mut request = MessageHTTP::from("brew /pot?milk=yes HTTP/1.1\r\nHost: example.com\r\nContent-Length: 4\r\n\r\nabcd").unwrap();
+        request.message_body = vec![0x00, 0xFF, b'\r', b'\n'];
+        let json = serde_json::to_string(&request).unwrap();
+        
+        assert_eq!(
+            json,
+            "{\"start_line\":{\"method\":\"brew\",\"target\":\"/pot?milk=yes\",\"version\":\"HTTP/1.1\"},\
+            \"header_fields\":[{\"name\":\"Host\",\"value\":\"example.com\"},{\"name\":\"Content-Length\",\"value\":\"4\"}],\
+            \"message_body\":\"AP8NCg==\"}",
+            "Test MessageHTTP serialize-1 failed."
+        );
+        assert_eq!(
+            serde_json::from_str::<MessageHTTP>(&json).unwrap(),
+            request,
+            "Test MessageHTTP deserialize-1 failed to round-trip."
+        );
+        
+        let responses = [
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\n\r\nhello",
+            "HTTP/1.0 404\r\n\r\n",
+            "HTTP/1.1 299  Custom  Reason\r\n\r\n"
+        ];
+        for response in responses.iter() {
+            let response = MessageHTTP::from(response).unwrap();
+            
+            assert_eq!(
+                serde_json::from_str::<MessageHTTP>(&serde_json::to_string(&response).unwrap()).unwrap(),
+                response,
+                "Test MessageHTTP deserialize-2 failed to round-trip `{}`.", response.start_line
+            );
+        }
+    }
+    #[test]
+    fn test_deserialize_message() {
+        assert_eq!(
+            serde_json::from_str::<MessageHTTP>("{\"start_line\":{\"version\":\"HTTP/1.1\",\"code\":204}}").unwrap(),
+            MessageHTTP::from("HTTP/1.1 204\r\n\r\n").unwrap(),
+            "Test MessageHTTP deserialize-3 failed."
+        );
+        
+        let invalid = [
+            "{\"start_line\":{\"method\":\"GET\",\"target\":\"/\",\"version\":\"HTTP/11\"}}",
+            "{\"start_line\":{\"method\":\"G ET\",\"target\":\"/\",\"version\":\"HTTP/1.1\"}}",
+            "{\"start_line\":{\"method\":\"GET\",\"code\":200,\"version\":\"HTTP/1.1\"}}",
+            "{\"start_line\":{\"version\":\"HTTP/1.1\",\"code\":200},\"message_body\":\"!\"}",
+            "{\"header_fields\":[]}",
+            "{\"start_line\":{\"version\":\"HTTP/1.1\",\"code\":7}}",
+            "{\"start_line\":{\"version\":\"HTTP/1.1\",\"code\":600}}",
+            "{\"start_line\":{\"version\":\"HTTP/1.1\",\"code\":200,\"reason\":\"OK\\r\\nSet-Cookie: evil=1\"}}",
+            "{\"start_line\":{\"method\":\"GET\",\"target\":\"/ HTTP/1.1\\r\\nX-Evil: 1\",\"version\":\"HTTP/1.1\"}}",
+            "{\"start_line\":{\"method\":\"GET\",\"target\":\"\",\"version\":\"HTTP/1.1\"}}",
+            "{\"start_line\":{\"version\":\"HTTP/1.1\",\"code\":200},\"header_fields\":[{\"name\":\"X-A\",\"value\":\"a\\r\\nSet-Cookie: evil=1\"}]}",
+            "{\"start_line\":{\"version\":\"HTTP/1.1\",\"code\":200},\"header_fields\":[{\"name\":\"X A\",\"value\":\"a\"}]}"
+        ];
+        for json in invalid.iter() {
+            assert!(
+                serde_json::from_str::<MessageHTTP>(json).is_err(),
+                "Test MessageHTTP deserialize-4 failed for `{}`.", json
+            );
+        }
+    }
+}
@@ -6,7 +6,16 @@
 //! Date --- 06/09/2017
 
 use std::string::String;
-use super::{HTTP, ErrorToHTTP, HTTP_METHOD};
+use std::fmt;
+use std::str::FromStr;
+use super::{HTTP, ErrorToHTTP, ParseError, StatusCode, Version, Method, ParseMode};
+use super::percent;
+use super::token;
+use super::target::RequestTarget;
+
+/// The connection preface an HTTP/2 client sends before anything else, as in RFC 7540
+/// section 3.5.
+pub const HTTP2_PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 /// A `StartLine` is the first line of a HTTP message defining how the message should be treated.
@@ -14,18 +23,18 @@ pub enum StartLine {
     /// A `RequestLine` defines some action to be taken by the recipient.
     RequestLine {
         /// The `method` denoted by the request.
-        method: &'static str,
+        method: Method,
         /// The resource target to perform the `method` on.
         target: String,
         /// The HTTP version of this message.
-        version: String
+        version: Version
     },
     /// A `StatusLine` is a response to a request message.
     StatusLine {
         /// The HTTP version of this message.
-        version: String,
+        version: Version,
         /// The response code associated with this message.
-        code: u32,
+        code: StatusCode,
         /// The optional reason given for the response.
         reason: Option<String>
     }
@@ -37,31 +46,93 @@ impl StartLine {
     /// # Params
     ///
     /// msg --- The `str` to convert to a `StartLine`.
-    pub fn from(msg: &str) -> Result<StartLine, String> {
-        // Get the parts of the string, attempting to divide by either spaces or quotes.
-        let parts: Vec<&str> = {
-            // Split the string on quotes.
-            let quot_split: Vec<&str> = msg.trim().split("\"").collect();
-            
-            // If the string is divided into three parts then this is a valid split.
-            if quot_split.len() == 3 {
-                // Return the split message.
-                quot_split
-            } else {
-                // Split the string on spaces.
-                msg.trim().split(" ").collect::<Vec<&str>>()
-            }
-        };
-        
+    pub fn from(msg: &str) -> Result<StartLine, ParseError> {
+        msg.parse()
+    }
+    /// Converts the passed `str` to a `StartLine`, parsed according to `mode`.
+    ///
+    /// A Request line target may be quoted to include spaces.</br>
+    /// Fails with `Http2Preface` if this is the first line of the `HTTP2_PREFACE`, with
+    /// `IllegalCharacter` if the line contains a control character other than a horizontal
+    /// tab or a final line terminator and with `MalformedStartLine` if a quoted target is unterminated
+    /// or a Request line continues after its version.</br>
+    /// A `Strict` parse also fails with `NonCanonicalMethod` if a recognised method is not
+    /// uppercase and with `NonCanonicalVersion` if the version is not exactly `HTTP/x.y`.
+    ///
+    /// # Params
+    ///
+    /// msg --- The `str` to convert to a `StartLine`.</br>
+    /// mode --- How strictly the line is parsed.
+    pub fn from_with_mode(msg: &str, mode: ParseMode) -> Result<StartLine, ParseError> {
+        StartLine::parse(msg, mode, false)
+    }
+    /// Converts the passed `str` to a `StartLine` as by `from_with_mode`, accepting an
+    /// HTTP/0.9 simple-request if `http09` is `true`.
+    ///
+    /// A simple-request is a Request line with a method and a target but no version, it
+    /// becomes a `RequestLine` with the version `HTTP/0.9`.
+    ///
+    /// # Params
+    ///
+    /// msg --- The `str` to convert to a `StartLine`.</br>
+    /// mode --- How strictly the line is parsed.</br>
+    /// http09 --- If a simple-request is accepted.
+    pub fn from_with_http09(msg: &str, mode: ParseMode, http09: bool) -> Result<StartLine, ParseError> {
+        StartLine::parse(msg, mode, http09)
+    }
+    /// Converts the passed `str` to a `StartLine`.
+    ///
+    /// # Params
+    ///
+    /// msg --- The `str` to convert to a `StartLine`.</br>
+    /// mode --- How strictly the line is parsed.</br>
+    /// http09 --- If a simple-request is accepted.
+    fn parse(msg: &str, mode: ParseMode, http09: bool) -> Result<StartLine, ParseError> {
+        // Control characters could hide a NUL in a path or inject lines into a log.
+        token::check_line_controls(msg.as_bytes(), mode)?;
+        // `PRI` would otherwise be an extension method.
+        if msg.trim() == "PRI * HTTP/2.0" {
+            return Err(ParseError::Http2Preface);
+        }
+        let line = msg.trim();
         // The first_part of the line should always be uppercase.
-        let first_part = parts[0].trim().to_uppercase();
+        let first_part = line.split(' ').next().unwrap_or_default().to_uppercase();
+        
+        // An empty line is not a Start line at all.
+        if first_part.is_empty() {
+            return Err(ParseError::MissingStartLine);
+        }
         
         // Returns a `RequestLine`.
         macro_rules! get_request {
             () => {{
-                let method = HTTP_METHOD[HTTP_METHOD.iter().position(|m| *m == first_part).unwrap()];
-                let target = String::from(parts[1].trim());
-                let version = String::from(parts[2].trim()).to_uppercase();
+                let (parts, rest) = split_request(line)?;
+                let method = Method::from(parts[0].trim())?;
+                
+                // Recognised methods are matched ignoring case unless the parse is strict.
+                if mode == ParseMode::Strict && method.is_recognized() && method.as_str() != parts[0] {
+                    return Err(ParseError::NonCanonicalMethod(String::from(parts[0])));
+                }
+                
+                // Make sure the line has all three parts before using them.
+                let target = match parts.get(1) {
+                    Some(target) if !target.trim().is_empty() => String::from(target.trim()),
+                    _ => return Err(ParseError::MissingTarget)
+                };
+                // Only a server-wide `OPTIONS` request may target `*`.
+                if target == "*" && method != Method::Options {
+                    return Err(ParseError::InvalidTarget(target));
+                }
+                let version = match parts.get(2) {
+                    Some(version) if !version.trim().is_empty() => parse_version(version.trim(), mode)?,
+                    // Only a simple-request has exactly two parts.
+                    None if http09 => Version::HTTP_09,
+                    _ => return Err(ParseError::MissingVersion)
+                };
+                // Nothing may follow the version.
+                if !rest.is_empty() {
+                    return Err(ParseError::MalformedStartLine(String::from(line)));
+                }
                 
                 Ok(
                     StartLine::RequestLine {
@@ -76,28 +147,31 @@ impl StartLine {
         // Returns a `StatusLine`.
         macro_rules! get_status {
             () => {{
-                let version = first_part;
+                // The line is split on single spaces so the reason is kept exactly as it was
+                // sent, quotes and all.
+                let mut fields = msg.trim_start()
+                    .trim_end_matches(|c| c == '\r' || c == '\n')
+                    .splitn(3, ' ');
+                let version = parse_version(fields.next().unwrap_or_default().trim(), mode)?;
                 
-                // Try to convert the status code to an integer.
-                let code = if let Ok(i) = parts[1].trim().parse::<u32>() {
-                    i
-                } else {
-                    // The status code was not a valid integer.
-                    return Err(format!("Bad code for Status line, not an unsigned integer: `{}`", parts[1]));
+                // Make sure the line has a status code before using it.
+                let code = match fields.next() {
+                    Some(code) if !code.trim().is_empty() => code,
+                    _ => return Err(ParseError::MissingStatusCode)
+                };
+                // The status code must be an unsigned integer.
+                let code = code.trim();
+                if !code.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(ParseError::InvalidStatusCode(String::from(code)));
+                }
+                // Only codes from 100 to 599 are valid, larger integers may not fit a `u16`.
+                let code = match code.parse() {
+                    Ok(code) if StatusCode(code).is_valid() => StatusCode(code),
+                    _ => return Err(ParseError::StatusCodeOutOfRange(String::from(code)))
                 };
                 
-                // Get the reason by folding the remaining parts of the message together.
-                let reason = String::from(
-                    parts.iter().skip(2)
-                        .fold(
-                            String::new(), 
-                            |mut res, s| {
-                                res.push(' ');
-                                res.push_str(s);
-                                res
-                            }
-                        ).trim()
-                );
+                // The reason is the rest of the line after the space following the code.
+                let reason = String::from(fields.next().unwrap_or_default());
                 
                 // If the reason is empty then there is no reason given.
                 let reason = if reason.is_empty() {
@@ -117,53 +191,224 @@ impl StartLine {
             }}
         }
         
-        // If the first part is found to match a HTTP_METHOD string then it is a Request line.
-        for m in HTTP_METHOD.iter() {
-            if first_part == *m {
-                return get_request!();
-            }
+        // A Status line always begins with the HTTP version.
+        if first_part.starts_with("HTTP/") {
+            return get_status!();
         }
-        // Otherwise it is a Status line.
-        return get_status!();
+        // Otherwise it is a Request line, unrecognised methods are kept as an `Extension`.
+        get_request!()
     }
-    /// Unwraps the `RequestLine` to its values.
-    pub fn request<'a>(&'a self) -> (&'static str, &'a String, &'a String) {
-        if let StartLine::RequestLine { method, ref target, ref version } = *self {
-            (method, target, version)
+    /// Returns the values of the `RequestLine`, or `None` if this is a `StatusLine`.
+    ///
+    /// The method is given as its name, use `method` to match on the `Method` itself.
+    pub fn as_request(&self) -> Option<(&str, &String, Version)> {
+        if let StartLine::RequestLine { ref method, ref target, version } = *self {
+            Some((method.as_str(), target, version))
         } else {
-            panic!("Called `request` on a non `RequestLine`.");
+            None
         }
     }
+    /// Returns the `Method` of a `RequestLine`, or `None` if this is a `StatusLine`.
+    pub fn method(&self) -> Option<&Method> {
+        match *self {
+            StartLine::RequestLine { ref method, .. } => Some(method),
+            StartLine::StatusLine { .. } => None
+        }
+    }
+    /// Returns the values of the `StatusLine`, or `None` if this is a `RequestLine`.
+    pub fn as_status(&self) -> Option<(Version, StatusCode, &Option<String>)> {
+        if let StartLine::StatusLine { version, code, ref reason } = *self {
+            Some((version, code, reason))
+        } else {
+            None
+        }
+    }
+    /// Returns the HTTP version of the `StartLine`.
+    pub fn version(&self) -> Version {
+        match *self {
+            StartLine::RequestLine { version, .. } | StartLine::StatusLine { version, .. } => version
+        }
+    }
+    /// Returns the path of a `RequestLine`'s target, the part before any `?`.</br>
+    /// Returns `None` for a `StatusLine`.
+    pub fn path(&self) -> Option<&str> {
+        match *self {
+            StartLine::RequestLine { ref target, .. } => target.split('?').next(),
+            StartLine::StatusLine { .. } => None
+        }
+    }
+    /// Returns the percent-decoded path of a `RequestLine`'s target.</br>
+    /// Returns `None` for a `StatusLine`.
+    ///
+    /// The raw path remains available through `path`.
+    pub fn decoded_path(&self) -> Option<Result<String, ParseError>> {
+        self.path().map(percent::decode)
+    }
+    /// Returns the raw query of a `RequestLine`'s target, the part after the first `?`.</br>
+    /// Returns `None` for a `StatusLine` or a target without a query.
+    pub fn query(&self) -> Option<&str> {
+        match *self {
+            StartLine::RequestLine { ref target, .. } => target.find('?').map(|i| &target[i + 1..]),
+            StartLine::StatusLine { .. } => None
+        }
+    }
+    /// Returns the form of a `RequestLine`'s target.</br>
+    /// Returns `None` for a `StatusLine`.
+    pub fn target_form(&self) -> Option<Result<RequestTarget, ParseError>> {
+        match *self {
+            StartLine::RequestLine { ref target, .. } => Some(RequestTarget::from(target)),
+            StartLine::StatusLine { .. } => None
+        }
+    }
+    /// Returns the decoded `key=value` pairs of a `RequestLine`'s query in order.
+    ///
+    /// `+` is decoded as a space and percent-encoded bytes are decoded, keys without a
+    /// value are given an empty value and repeated keys are all kept. A pair whose key or
+    /// value fails to decode is skipped, so a raw escape is never mistaken for its
+    /// decoded form.</br>
+    /// A `StatusLine` or a target without a query has no pairs.
+    pub fn query_pairs(&self) -> Vec<(String, String)> {
+        match self.query() {
+            Some(query) => query.split('&')
+                .filter(|pair| !pair.is_empty())
+                .filter_map(
+                    |pair| {
+                        let mut parts = pair.splitn(2, '=');
+                        let key = parts.next().unwrap_or_default();
+                        let value = parts.next().unwrap_or_default();
+                        
+                        Some((percent::decode_form(key).ok()?, percent::decode_form(value).ok()?))
+                    }
+                ).collect(),
+            None => Vec::new()
+        }
+    }
+    #[deprecated(since = "0.2.0", note = "panics on a `StatusLine`, use `as_request` instead")]
     /// Unwraps the `RequestLine` to its values.
-    pub fn status<'a>(&'a self) -> (&String, u32, &Option<String>) {
-        if let StartLine::StatusLine { ref version, code, ref reason } = *self {
-            (version, code, reason)
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is a `StatusLine`.
+    pub fn request(&self) -> (&str, &String, Version) {
+        self.as_request()
+            .expect("Called `request` on a non `RequestLine`.")
+    }
+    #[deprecated(since = "0.2.0", note = "panics on a `RequestLine`, use `as_status` instead")]
+    /// Unwraps the `StatusLine` to its values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is a `RequestLine`.
+    pub fn status(&self) -> (Version, StatusCode, &Option<String>) {
+        self.as_status()
+            .expect("Called `status` on a non `StatusLine`.")
+    }
+}
+
+/// Converts the passed HTTP version, failing with `NonCanonicalVersion` if `mode` is
+/// `ParseMode::Strict` and it is not exactly `HTTP/` and a digit either side of a `.`.
+///
+/// # Params
+///
+/// version --- The HTTP version to convert.</br>
+/// mode --- How strictly the version is parsed.
+fn parse_version(version: &str, mode: ParseMode) -> Result<Version, ParseError> {
+    let res = Version::from(version)?;
+    let bytes = version.as_bytes();
+    
+    if mode == ParseMode::Strict
+        && !(bytes.len() == 8 && version.starts_with("HTTP/") && bytes[5].is_ascii_digit() && bytes[6] == b'.' && bytes[7].is_ascii_digit()) {
+        return Err(ParseError::NonCanonicalVersion(String::from(version)));
+    }
+    Ok(res)
+}
+
+/// Splits a Request line, from left to right, into up to three parts for its method, target
+/// and version, and returns them with whatever is left of the line after the version.
+///
+/// A target beginning with a quote runs to the closing quote and is returned without its
+/// quotes, every other part ends at the next space.
+///
+/// # Params
+///
+/// line --- The trimmed Request line to split.
+fn split_request(line: &str) -> Result<(Vec<&str>, &str), ParseError> {
+    let malformed = || ParseError::MalformedStartLine(String::from(line));
+    let mut parts = Vec::with_capacity(3);
+    let mut rest = line;
+    
+    while parts.len() < 3 && !rest.is_empty() {
+        // Only the target may be quoted.
+        let (part, after) = if parts.len() == 1 && rest.starts_with('"') {
+            let end = rest[1..].find('"').ok_or_else(malformed)? + 1;
+            
+            (&rest[1..end], &rest[end + 1..])
         } else {
-            panic!("Called `status` on a non `StartLine`.");
+            match rest.find(' ') {
+                Some(index) => rest.split_at(index),
+                None => (rest, "")
+            }
+        };
+        // Every part must be followed by a space or the end of the line.
+        if !after.is_empty() && !after.starts_with(' ') {
+            return Err(malformed());
         }
+        parts.push(part);
+        rest = if after.is_empty() { after } else { &after[1..] };
     }
+    Ok((parts, rest))
 }
 
-impl HTTP for StartLine {
-    fn to_http(&self) -> Result<String, ErrorToHTTP> {
-        match self {
-            &StartLine::RequestLine {
+impl FromStr for StartLine {
+    type Err = ParseError;
+    
+    /// Converts the passed `str` to a `StartLine`.
+    ///
+    /// # Params
+    ///
+    /// msg --- The `str` to convert to a `StartLine`.
+    fn from_str(msg: &str) -> Result<StartLine, ParseError> {
+        StartLine::parse(msg, ParseMode::Lenient, false)
+    }
+}
+
+impl fmt::Display for StartLine {
+    /// Writes the `StartLine` in its wire form.
+    ///
+    /// A `RequestLine` becomes `METHOD target VERSION` and a `StatusLine` becomes
+    /// `VERSION code reason`, with the code written as at least three digits. When there
+    /// is no reason the canonical reason phrase of the code is written, if it has one.</br>
+    /// A target containing whitespace is quoted, as it was when it was parsed.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StartLine::RequestLine {
                 ref method,
                 ref target,
-                ref version
-            } => Ok(format!("{} \"{}\" {}", method, target, version)),
-            &StartLine::StatusLine {
-                ref version,
-                ref code,
+                version
+            } => if target.contains(char::is_whitespace) {
+                write!(f, "{} \"{}\" {}", method, target, version)
+            } else {
+                write!(f, "{} {} {}", method, target, version)
+            },
+            StartLine::StatusLine {
+                version,
+                code,
                 ref reason
-            } => match reason {
-                &Some(ref s) => Ok(format!("{} {} {}", version, code, s)),
-                &None => Ok(format!("{} {}", version, code))
+            } => match reason.as_ref().map(String::as_str).or_else(|| code.reason_phrase()) {
+                Some(reason) => write!(f, "{} {} {}", version, code, reason),
+                None => write!(f, "{} {}", version, code)
             }
         }
     }
 }
 
+impl HTTP for StartLine {
+    /// Converts the `StartLine` into its wire form, as written by `Display`.
+    fn to_http(&self) -> Result<String, ErrorToHTTP> {
+        Ok(self.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -173,9 +418,9 @@ mod tests {
         assert_eq!(
             StartLine::from("get / http/1.1").unwrap(),
             StartLine::RequestLine {
-                method: "GET",
+                method: Method::Get,
                 target: String::from("/"),
-                version: String::from("HTTP/1.1")
+                version: Version::HTTP_11
             },
             "Test RequestLine::from-1 failed."
         );
@@ -183,75 +428,531 @@ mod tests {
         assert_eq!(
             StartLine::from("GET \"/space test\" http/2.1").unwrap(),
             StartLine::RequestLine {
-                method: "GET",
+                method: Method::Get,
                 target: String::from("/space test"),
-                version: String::from("HTTP/2.1")
+                version: Version { major: 2, minor: 1 }
             },
             "Test RequestLine::from-2 failed."
         );
         
-        assert!(
-            StartLine::from("fail \"/space test\" http/2.1").is_err(),
+        assert_eq!(
+            StartLine::from("f(ail \"/space test\" http/2.1"),
+            Err(ParseError::InvalidMethod(String::from("f(ail"))),
             "Test RequestLine::from-3 failed."
         );
         
-        assert!(
-            StartLine::from("fail /space test http/2.1").is_err(),
+        assert_eq!(
+            StartLine::from("fail /space test http/2.1"),
+            Err(ParseError::InvalidVersion(String::from("test"))),
             "Test RequestLine::from-4 failed."
         );
+        
+        let methods = [
+            ("head", Method::Head), ("post", Method::Post), ("put", Method::Put), ("delete", Method::Delete),
+            ("connect", Method::Connect), ("options", Method::Options), ("trace", Method::Trace), ("patch", Method::Patch)
+        ];
+        for &(input, ref method) in methods.iter() {
+            assert_eq!(
+                StartLine::from(&format!("{} /index.html HTTP/1.1", input)).unwrap(),
+                StartLine::RequestLine {
+                    method: method.clone(),
+                    target: String::from("/index.html"),
+                    version: Version::HTTP_11
+                },
+                "Test RequestLine::from-5 failed for `{}`.", method
+            );
+        }
+        
+        assert_eq!(
+            StartLine::from("BREW /pot HTTP/1.1"),
+            Ok(StartLine::RequestLine {
+                method: Method::Extension(String::from("BREW")),
+                target: String::from("/pot"),
+                version: Version::HTTP_11
+            }),
+            "Test RequestLine::from-6 failed."
+        );
+        assert!(
+            StartLine::from("GE T / HTTP/1.1").is_err(),
+            "Test RequestLine::from-8 failed."
+        );
+        assert_eq!(
+            (StartLine::from("GET /etc/passwd\0.html HTTP/1.1"), StartLine::from("GET / HTTP/1.1\r\n").is_ok()),
+            (Err(ParseError::IllegalCharacter { position: 15 }), true),
+            "Test RequestLine::from-9 failed."
+        );
+        assert_eq!(
+            StartLine::from("GET \"/a b\" HTTP/1.1 extra"),
+            Err(ParseError::MalformedStartLine(String::from("GET \"/a b\" HTTP/1.1 extra"))),
+            "Test RequestLine::from-10 failed."
+        );
+        for &line in ["GET / HTTP/1.1 extra", "GET \"/a b HTTP/1.1", "GET \"/a\"b HTTP/1.1"].iter() {
+            assert_eq!(
+                StartLine::from(line),
+                Err(ParseError::MalformedStartLine(String::from(line))),
+                "Test RequestLine::from-11 failed for `{}`.", line
+            );
+        }
+        
+        assert_eq!(
+            StartLine::from("GET / HTTP/11"),
+            Err(ParseError::InvalidVersion(String::from("HTTP/11"))),
+            "Test RequestLine::from-7 failed."
+        );
     }
     #[test]
     fn test_status_line() {
         assert_eq!(
-            StartLine::from("http/1.1 000 OK").unwrap(),
+            StartLine::from("http/1.1 200 OK").unwrap(),
             StartLine::StatusLine {
-                version: String::from("HTTP/1.1"),
-                code: 0,
+                version: Version::HTTP_11,
+                code: StatusCode::OK,
                 reason: Some(String::from("OK"))
             },
             "Test StatusLine::from-1 failed."
         );
         
         assert_eq!(
-            StartLine::from("http/2.1 012 test").unwrap(),
+            StartLine::from("http/2.1 299 test").unwrap(),
             StartLine::StatusLine {
-                version: String::from("HTTP/2.1"),
-                code: 12,
+                version: Version { major: 2, minor: 1 },
+                code: StatusCode(299),
                 reason: Some(String::from("test"))
             },
             "Test StatusLine::from-2 failed."
         );
         
         assert_eq!(
-            StartLine::from("http/2.1 012 testing with spaces in reason").unwrap(),
+            StartLine::from("http/2.1 299 testing with spaces in reason").unwrap(),
             StartLine::StatusLine {
-                version: String::from("HTTP/2.1"),
-                code: 12,
+                version: Version { major: 2, minor: 1 },
+                code: StatusCode(299),
                 reason: Some(String::from("testing with spaces in reason"))
             },
             "Test StatusLine::from-3 failed."
         );
         
         assert_eq!(
-            StartLine::from("http/2.1 012").unwrap(),
+            StartLine::from("http/2.1 299").unwrap(),
             StartLine::StatusLine {
-                version: String::from("HTTP/2.1"),
-                code: 12,
+                version: Version { major: 2, minor: 1 },
+                code: StatusCode(299),
                 reason: None
             },
             "Test StatusLine::from-4 failed."
         );
         
         assert_eq!(
-            StartLine::from("http/2.1 012").unwrap().to_http().unwrap(),
-            "HTTP/2.1 12",
+            StartLine::from("http/2.1 299").unwrap().to_http().unwrap(),
+            "HTTP/2.1 299",
             "Test StatusLine::from-5 failed."
         );
         
         assert_eq!(
-            StartLine::from("http/2.1 012 With reason to be stringified.").unwrap().to_http().unwrap(),
-            "HTTP/2.1 12 With reason to be stringified.",
+            StartLine::from("http/2.1 299 With reason to be stringified.").unwrap().to_http().unwrap(),
+            "HTTP/2.1 299 With reason to be stringified.",
             "Test StatusLine::from-6 failed."
         );
+        
+        assert_eq!(
+            StartLine::from("http/1.1 two-hundred OK"),
+            Err(ParseError::InvalidStatusCode(String::from("two-hundred"))),
+            "Test StatusLine::from-7 failed."
+        );
+        
+        assert_eq!(
+            StartLine::from("HTTP/one.two 200 OK"),
+            Err(ParseError::InvalidVersion(String::from("HTTP/one.two"))),
+            "Test StatusLine::from-8 failed."
+        );
+        
+        for &code in ["000", "012", "099", "600", "9999", "4294967295", "99999999999999999999"].iter() {
+            assert_eq!(
+                StartLine::from(&format!("HTTP/1.1 {} OK", code)),
+                Err(ParseError::StatusCodeOutOfRange(String::from(code))),
+                "Test StatusLine::from-9 failed for `{}`.", code
+            );
+        }
+        assert_eq!(
+            StartLine::from("HTTP/1.1 +200 OK"),
+            Err(ParseError::InvalidStatusCode(String::from("+200"))),
+            "Test StatusLine::from-10 failed."
+        );
+        
+        for &reason in ["Not  Found", "Not\tFound", "\"Not\" Found", "Say \"hi\"", " padded "].iter() {
+            assert_eq!(
+                StartLine::from(&format!("HTTP/1.1 404 {}", reason)),
+                Ok(StartLine::StatusLine {
+                    version: Version::HTTP_11,
+                    code: StatusCode::NOT_FOUND,
+                    reason: Some(String::from(reason))
+                }),
+                "Test StatusLine::from-11 failed for `{}`.", reason
+            );
+        }
+        assert_eq!(
+            StartLine::from("HTTP/1.1 404 "),
+            Ok(StartLine::StatusLine {
+                version: Version::HTTP_11,
+                code: StatusCode::NOT_FOUND,
+                reason: None
+            }),
+            "Test StatusLine::from-12 failed."
+        );
+        assert_eq!(
+            StartLine::from("HTTP/1.1 200 O\x01K"),
+            Err(ParseError::IllegalCharacter { position: 14 }),
+            "Test StatusLine::from-13 failed."
+        );
+        assert_eq!(
+            StartLine::from("http/1.1 200 said \"hello\" twice"),
+            Ok(StartLine::StatusLine {
+                version: Version::HTTP_11,
+                code: StatusCode::OK,
+                reason: Some(String::from("said \"hello\" twice"))
+            }),
+            "Test StatusLine::from-14 failed."
+        );
+    }
+    #[test]
+    fn test_short_start_line() {
+        assert_eq!(
+            StartLine::from("GET"),
+            Err(ParseError::MissingTarget),
+            "Test StartLine::from-1 failed."
+        );
+        
+        assert_eq!(
+            StartLine::from("GET /"),
+            Err(ParseError::MissingVersion),
+            "Test StartLine::from-2 failed."
+        );
+        
+        assert_eq!(
+            StartLine::from(""),
+            Err(ParseError::MissingStartLine),
+            "Test StartLine::from-3 failed."
+        );
+        
+        assert_eq!(
+            StartLine::from("HTTP/1.1"),
+            Err(ParseError::MissingStatusCode),
+            "Test StartLine::from-4 failed."
+        );
+        
+        assert_eq!(
+            StartLine::from("HTTP/1.1 "),
+            Err(ParseError::MissingStatusCode),
+            "Test StartLine::from-5 failed."
+        );
+    }
+    #[test]
+    fn test_simple_request() {
+        for &mode in [ParseMode::Lenient, ParseMode::Strict].iter() {
+            assert_eq!(
+                StartLine::from_with_http09("GET /", mode, true),
+                Ok(StartLine::RequestLine {
+                    method: Method::Get,
+                    target: String::from("/"),
+                    version: Version::HTTP_09
+                }),
+                "Test StartLine::from_with_http09-1 failed for `{:?}`.", mode
+            );
+            assert_eq!(
+                (StartLine::from_with_mode("GET /", mode), StartLine::from_with_http09("GET /", mode, false)),
+                (Err(ParseError::MissingVersion), Err(ParseError::MissingVersion)),
+                "Test StartLine::from_with_http09-2 failed for `{:?}`.", mode
+            );
+            assert_eq!(
+                StartLine::from_with_http09("GET / HTTP/1.1", mode, true).map(|line| line.version()),
+                Ok(Version::HTTP_11),
+                "Test StartLine::from_with_http09-3 failed for `{:?}`.", mode
+            );
+            assert_eq!(
+                (StartLine::from_with_http09("GET", mode, true), StartLine::from_with_http09("G@T /", mode, true)),
+                (Err(ParseError::MissingTarget), Err(ParseError::InvalidMethod(String::from("G@T")))),
+                "Test StartLine::from_with_http09-4 failed for `{:?}`.", mode
+            );
+        }
+        // A simple-request is still parsed strictly.
+        assert_eq!(
+            StartLine::from_with_http09("get /", ParseMode::Strict, true),
+            Err(ParseError::NonCanonicalMethod(String::from("get"))),
+            "Test StartLine::from_with_http09-5 failed."
+        );
+    }
+    #[test]
+    fn test_start_line_accessors() {
+        let request = StartLine::from("GET /index.html HTTP/1.1").unwrap();
+        let status = StartLine::from("HTTP/1.1 404 Not Found").unwrap();
+        
+        assert_eq!(
+            request.as_request(),
+            Some(("GET", &String::from("/index.html"), Version::HTTP_11)),
+            "Test StartLine::as_request-1 failed."
+        );
+        assert_eq!(
+            status.as_request(),
+            None,
+            "Test StartLine::as_request-2 failed."
+        );
+        assert_eq!(
+            (request.method(), status.method()),
+            (Some(&Method::Get), None),
+            "Test StartLine::method-1 failed."
+        );
+        
+        assert_eq!(
+            status.as_status(),
+            Some((Version::HTTP_11, StatusCode::NOT_FOUND, &Some(String::from("Not Found")))),
+            "Test StartLine::as_status-1 failed."
+        );
+        assert_eq!(
+            request.as_status(),
+            None,
+            "Test StartLine::as_status-2 failed."
+        );
+        
+        assert_eq!(
+            StartLine::from("GET / HTTP/1.0").unwrap().version(),
+            Version::HTTP_10,
+            "Test StartLine::version-1 failed."
+        );
+        assert_eq!(
+            StartLine::from("HTTP/1.1 200 OK").unwrap().version(),
+            Version::HTTP_11,
+            "Test StartLine::version-2 failed."
+        );
+    }
+    #[test]
+    #[should_panic]
+    #[allow(deprecated)]
+    fn test_request_on_status_line() {
+        StartLine::from("HTTP/1.1 200 OK").unwrap().request();
+    }
+    #[test]
+    #[should_panic]
+    #[allow(deprecated)]
+    fn test_status_on_request_line() {
+        StartLine::from("GET / HTTP/1.1").unwrap().status();
+    }
+    #[test]
+    fn test_query() {
+        let line = StartLine::from("GET /search?q=rust+lang&page=2&q=web%20server&flag&&empty= HTTP/1.1").unwrap();
+        
+        assert_eq!(
+            line.path(),
+            Some("/search"),
+            "Test StartLine::path-1 failed."
+        );
+        assert_eq!(
+            line.query(),
+            Some("q=rust+lang&page=2&q=web%20server&flag&&empty="),
+            "Test StartLine::query-1 failed."
+        );
+        assert_eq!(
+            line.query_pairs(),
+            vec![
+                (String::from("q"), String::from("rust lang")),
+                (String::from("page"), String::from("2")),
+                (String::from("q"), String::from("web server")),
+                (String::from("flag"), String::new()),
+                (String::from("empty"), String::new())
+            ],
+            "Test StartLine::query_pairs-1 failed."
+        );
+        
+        let line = StartLine::from("GET /index.html? HTTP/1.1").unwrap();
+        assert_eq!(
+            line.path(),
+            Some("/index.html"),
+            "Test StartLine::path-2 failed."
+        );
+        assert_eq!(
+            line.query_pairs(),
+            Vec::new(),
+            "Test StartLine::query_pairs-2 failed."
+        );
+        
+        let line = StartLine::from("GET /index.html HTTP/1.1").unwrap();
+        assert_eq!(
+            line.query(),
+            None,
+            "Test StartLine::query-2 failed."
+        );
+        
+        let line = StartLine::from("GET /?a%3Db=%C3%A9&c=%ZZ&%ZZ=d&e=%FF HTTP/1.1").unwrap();
+        assert_eq!(
+            line.query_pairs(),
+            vec![(String::from("a=b"), String::from("é"))],
+            "Test StartLine::query_pairs-3 failed."
+        );
+        
+        let line = StartLine::from("GET /my%20file.html?a=1 HTTP/1.1").unwrap();
+        assert_eq!(
+            (line.path(), line.decoded_path()),
+            (Some("/my%20file.html"), Some(Ok(String::from("/my file.html")))),
+            "Test StartLine::decoded_path-1 failed."
+        );
+        assert_eq!(
+            StartLine::from("GET /100% HTTP/1.1").unwrap().decoded_path(),
+            Some(Err(ParseError::InvalidPercentEncoding(String::from("%")))),
+            "Test StartLine::decoded_path-2 failed."
+        );
+        
+        let line = StartLine::from("HTTP/1.1 200 OK").unwrap();
+        assert_eq!(
+            (line.path(), line.query(), line.query_pairs()),
+            (None, None, Vec::new()),
+            "Test StartLine::query_pairs-4 failed."
+        );
+    }
+    #[test]
+    fn test_target_form() {
+        assert_eq!(
+            StartLine::from("GET http://example.com/a?b HTTP/1.1").unwrap().target_form(),
+            Some(
+                Ok(
+                    RequestTarget::AbsoluteForm {
+                        scheme: String::from("http"),
+                        authority: String::from("example.com"),
+                        path: String::from("/a"),
+                        query: Some(String::from("b"))
+                    }
+                )
+            ),
+            "Test StartLine::target_form-1 failed."
+        );
+        
+        assert_eq!(
+            StartLine::from("CONNECT example.com:443 HTTP/1.1").unwrap().target_form(),
+            Some(Ok(RequestTarget::AuthorityForm { host: String::from("example.com"), port: 443 })),
+            "Test StartLine::target_form-2 failed."
+        );
+        
+        assert_eq!(
+            StartLine::from("HTTP/1.1 200 OK").unwrap().target_form(),
+            None,
+            "Test StartLine::target_form-3 failed."
+        );
+        
+        assert_eq!(
+            StartLine::from("OPTIONS * HTTP/1.1").unwrap().target_form(),
+            Some(Ok(RequestTarget::AsteriskForm)),
+            "Test StartLine::target_form-4 failed."
+        );
+        
+        assert_eq!(
+            StartLine::from("GET * HTTP/1.1"),
+            Err(ParseError::InvalidTarget(String::from("*"))),
+            "Test StartLine::target_form-5 failed."
+        );
+    }
+    #[test]
+    fn test_start_line_to_http() {
+        assert_eq!(
+            StartLine::from("get /index.html http/1.1").unwrap().to_http().unwrap(),
+            "GET /index.html HTTP/1.1",
+            "Test StartLine::to_http-1 failed."
+        );
+        
+        assert_eq!(
+            StartLine::from("http/1.1 404 Not Found").unwrap().to_http().unwrap(),
+            "HTTP/1.1 404 Not Found",
+            "Test StartLine::to_http-2 failed."
+        );
+        
+        let lines = [
+            StartLine::RequestLine {
+                method: Method::Post,
+                target: String::from("/submit?a=1"),
+                version: Version::HTTP_11
+            },
+            StartLine::RequestLine {
+                method: Method::Get,
+                target: String::from("/a b"),
+                version: Version::HTTP_11
+            },
+            StartLine::StatusLine {
+                version: Version::HTTP_10,
+                code: StatusCode::OK,
+                reason: Some(String::from("OK"))
+            },
+            StartLine::StatusLine {
+                version: Version::HTTP_11,
+                code: StatusCode(299),
+                reason: None
+            }
+        ];
+        for (i, line) in lines.iter().enumerate() {
+            assert_eq!(
+                StartLine::from(&line.to_http().unwrap()).unwrap(),
+                *line,
+                "Test StartLine::to_http-{} failed to round-trip.", i + 3
+            );
+        }
+        
+        assert_eq!(
+            StartLine::from("GET \"/a b\" HTTP/1.1").unwrap().to_http().unwrap(),
+            "GET \"/a b\" HTTP/1.1",
+            "Test StartLine::to_http-7 failed."
+        );
+    }
+    #[test]
+    fn test_start_line_from_str() {
+        assert_eq!(
+            "GET / HTTP/1.1".parse::<StartLine>(),
+            Ok(StartLine::RequestLine {
+                method: Method::Get,
+                target: String::from("/"),
+                version: Version::HTTP_11
+            }),
+            "Test StartLine::from_str-1 failed."
+        );
+        assert_eq!(
+            "HTTP/1.1 200 OK".parse::<StartLine>(),
+            StartLine::from("HTTP/1.1 200 OK"),
+            "Test StartLine::from_str-2 failed."
+        );
+        assert_eq!(
+            "FETCH / HTTP/1.1".parse::<StartLine>().map(|line| line.to_string()),
+            Ok(String::from("FETCH / HTTP/1.1")),
+            "Test StartLine::from_str-3 failed."
+        );
+    }
+    #[test]
+    fn test_start_line_display() {
+        assert_eq!(
+            format!("{}", StartLine::from("get /index.html?a=1 http/1.0").unwrap()),
+            "GET /index.html?a=1 HTTP/1.0",
+            "Test StartLine display-1 failed."
+        );
+        assert_eq!(
+            format!("{}", StartLine::from("HTTP/1.1 404 Not Found").unwrap()),
+            "HTTP/1.1 404 Not Found",
+            "Test StartLine display-2 failed."
+        );
+        assert_eq!(
+            StartLine::StatusLine {
+                version: Version::HTTP_11,
+                code: StatusCode(7),
+                reason: None
+            }.to_string(),
+            "HTTP/1.1 007",
+            "Test StartLine display-3 failed."
+        );
+        
+        // A missing reason falls back to the canonical phrase, a sent one is kept.
+        let line = StartLine::from("HTTP/1.1 418").unwrap();
+        assert_eq!(
+            (line.as_status().map(|status| status.2.clone()), line.to_string()),
+            (Some(None), String::from("HTTP/1.1 418 I'm a teapot")),
+            "Test StartLine display-4 failed."
+        );
+        assert_eq!(
+            StartLine::from("HTTP/1.1 200 Alright").unwrap().to_string(),
+            "HTTP/1.1 200 Alright",
+            "Test StartLine display-5 failed."
+        );
     }
 }
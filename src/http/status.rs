@@ -0,0 +1,279 @@
+//! `status` is a module to handle the status codes of HTTP responses and their reason phrases.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::fmt;
+use super::BuildError;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+/// A `StatusCode` is the code of a `StatusLine`.
+///
+/// Any three digit code can be represented but only codes from 100 to 599 are valid, the
+/// constants cover the codes in the IANA registry.
+pub struct StatusCode(pub u16);
+
+/// Defines a constant for each registered `StatusCode` and `StatusCode::reason_phrase`.
+macro_rules! status_codes {
+    ($(($code:expr, $name:ident, $phrase:expr)),* $(,)*) => {
+        impl StatusCode {
+            $(
+                #[doc = $phrase]
+                pub const $name: StatusCode = StatusCode($code);
+            )*
+            
+            /// Returns the canonical reason phrase of the `StatusCode`, or `None` if the
+            /// code is not registered.
+            pub fn reason_phrase(&self) -> Option<&'static str> {
+                match self.0 {
+                    $($code => Some($phrase),)*
+                    _ => None
+                }
+            }
+        }
+    }
+}
+
+status_codes! {
+    (100, CONTINUE, "Continue"),
+    (101, SWITCHING_PROTOCOLS, "Switching Protocols"),
+    (102, PROCESSING, "Processing"),
+    (103, EARLY_HINTS, "Early Hints"),
+    (200, OK, "OK"),
+    (201, CREATED, "Created"),
+    (202, ACCEPTED, "Accepted"),
+    (203, NON_AUTHORITATIVE_INFORMATION, "Non-Authoritative Information"),
+    (204, NO_CONTENT, "No Content"),
+    (205, RESET_CONTENT, "Reset Content"),
+    (206, PARTIAL_CONTENT, "Partial Content"),
+    (207, MULTI_STATUS, "Multi-Status"),
+    (208, ALREADY_REPORTED, "Already Reported"),
+    (226, IM_USED, "IM Used"),
+    (300, MULTIPLE_CHOICES, "Multiple Choices"),
+    (301, MOVED_PERMANENTLY, "Moved Permanently"),
+    (302, FOUND, "Found"),
+    (303, SEE_OTHER, "See Other"),
+    (304, NOT_MODIFIED, "Not Modified"),
+    (305, USE_PROXY, "Use Proxy"),
+    (307, TEMPORARY_REDIRECT, "Temporary Redirect"),
+    (308, PERMANENT_REDIRECT, "Permanent Redirect"),
+    (400, BAD_REQUEST, "Bad Request"),
+    (401, UNAUTHORIZED, "Unauthorized"),
+    (402, PAYMENT_REQUIRED, "Payment Required"),
+    (403, FORBIDDEN, "Forbidden"),
+    (404, NOT_FOUND, "Not Found"),
+    (405, METHOD_NOT_ALLOWED, "Method Not Allowed"),
+    (406, NOT_ACCEPTABLE, "Not Acceptable"),
+    (407, PROXY_AUTHENTICATION_REQUIRED, "Proxy Authentication Required"),
+    (408, REQUEST_TIMEOUT, "Request Timeout"),
+    (409, CONFLICT, "Conflict"),
+    (410, GONE, "Gone"),
+    (411, LENGTH_REQUIRED, "Length Required"),
+    (412, PRECONDITION_FAILED, "Precondition Failed"),
+    (413, CONTENT_TOO_LARGE, "Content Too Large"),
+    (414, URI_TOO_LONG, "URI Too Long"),
+    (415, UNSUPPORTED_MEDIA_TYPE, "Unsupported Media Type"),
+    (416, RANGE_NOT_SATISFIABLE, "Range Not Satisfiable"),
+    (417, EXPECTATION_FAILED, "Expectation Failed"),
+    (418, IM_A_TEAPOT, "I'm a teapot"),
+    (421, MISDIRECTED_REQUEST, "Misdirected Request"),
+    (422, UNPROCESSABLE_CONTENT, "Unprocessable Content"),
+    (423, LOCKED, "Locked"),
+    (424, FAILED_DEPENDENCY, "Failed Dependency"),
+    (425, TOO_EARLY, "Too Early"),
+    (426, UPGRADE_REQUIRED, "Upgrade Required"),
+    (428, PRECONDITION_REQUIRED, "Precondition Required"),
+    (429, TOO_MANY_REQUESTS, "Too Many Requests"),
+    (431, REQUEST_HEADER_FIELDS_TOO_LARGE, "Request Header Fields Too Large"),
+    (451, UNAVAILABLE_FOR_LEGAL_REASONS, "Unavailable For Legal Reasons"),
+    (500, INTERNAL_SERVER_ERROR, "Internal Server Error"),
+    (501, NOT_IMPLEMENTED, "Not Implemented"),
+    (502, BAD_GATEWAY, "Bad Gateway"),
+    (503, SERVICE_UNAVAILABLE, "Service Unavailable"),
+    (504, GATEWAY_TIMEOUT, "Gateway Timeout"),
+    (505, HTTP_VERSION_NOT_SUPPORTED, "HTTP Version Not Supported"),
+    (506, VARIANT_ALSO_NEGOTIATES, "Variant Also Negotiates"),
+    (507, INSUFFICIENT_STORAGE, "Insufficient Storage"),
+    (508, LOOP_DETECTED, "Loop Detected"),
+    (510, NOT_EXTENDED, "Not Extended"),
+    (511, NETWORK_AUTHENTICATION_REQUIRED, "Network Authentication Required")
+}
+
+/// Returns the canonical reason phrase of a status code, or `None` if the code is not
+/// registered.
+///
+/// # Params
+///
+/// code --- The status code to look up, e.g. `404`.
+pub fn reason_phrase(code: u16) -> Option<&'static str> {
+    StatusCode(code).reason_phrase()
+}
+
+impl StatusCode {
+    /// Returns the `StatusCode` of the passed code.</br>
+    /// Fails with `StatusCodeOutOfRange` if the code is not from 100 to 599.
+    ///
+    /// # Params
+    ///
+    /// code --- The numeric value of the code, e.g. `404`.
+    pub fn new(code: u16) -> Result<StatusCode, BuildError> {
+        if StatusCode(code).is_valid() {
+            Ok(StatusCode(code))
+        } else {
+            Err(BuildError::StatusCodeOutOfRange(code))
+        }
+    }
+    /// Returns the numeric value of the `StatusCode`.
+    pub fn as_u16(&self) -> u16 {
+        self.0
+    }
+    /// Returns `true` if the code is from 100 to 599, the only codes which may be sent.
+    pub fn is_valid(&self) -> bool {
+        self.0 >= 100 && self.0 < 600
+    }
+    /// Returns `true` if the code is `1xx`, the request was received and is continuing.
+    pub fn is_informational(&self) -> bool {
+        self.0 / 100 == 1
+    }
+    /// Returns `true` if the code is `2xx`, the request was accepted.
+    pub fn is_success(&self) -> bool {
+        self.0 / 100 == 2
+    }
+    /// Returns `true` if the code is `3xx`, further action is needed to complete the request.
+    pub fn is_redirection(&self) -> bool {
+        self.0 / 100 == 3
+    }
+    /// Returns `true` if the code is `4xx`, the request contained an error.
+    pub fn is_client_error(&self) -> bool {
+        self.0 / 100 == 4
+    }
+    /// Returns `true` if the code is `5xx`, the server failed to fulfil a valid request.
+    pub fn is_server_error(&self) -> bool {
+        self.0 / 100 == 5
+    }
+    /// Returns `true` if a response with the code may have a body, a `1xx`,
+    /// `204 No Content` or `304 Not Modified` response never does.
+    ///
+    /// Whether a response may have a body also depends on the request, see
+    /// `Method::response_body_allowed`.
+    pub fn allows_body(&self) -> bool {
+        !self.is_informational() && *self != StatusCode::NO_CONTENT && *self != StatusCode::NOT_MODIFIED
+    }
+}
+
+impl From<u16> for StatusCode {
+    fn from(code: u16) -> StatusCode {
+        StatusCode(code)
+    }
+}
+
+impl From<StatusCode> for u16 {
+    fn from(code: StatusCode) -> u16 {
+        code.0
+    }
+}
+
+impl fmt::Display for StatusCode {
+    /// Writes the code as at least three digits.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:03}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_code() {
+        assert_eq!(
+            (StatusCode::OK.reason_phrase(), StatusCode::NOT_FOUND.reason_phrase(), StatusCode::INTERNAL_SERVER_ERROR.reason_phrase()),
+            (Some("OK"), Some("Not Found"), Some("Internal Server Error")),
+            "Test StatusCode::reason_phrase-1 failed."
+        );
+        
+        assert_eq!(
+            (StatusCode(299).reason_phrase(), StatusCode(0).reason_phrase()),
+            (None, None),
+            "Test StatusCode::reason_phrase-2 failed."
+        );
+        
+        assert_eq!(
+            (StatusCode::from(404), u16::from(StatusCode::NOT_FOUND), StatusCode(418).as_u16()),
+            (StatusCode::NOT_FOUND, 404, 418),
+            "Test StatusCode::from-1 failed."
+        );
+        assert_eq!(
+            (StatusCode::new(404), StatusCode::new(99), StatusCode::new(600)),
+            (Ok(StatusCode::NOT_FOUND), Err(BuildError::StatusCodeOutOfRange(99)), Err(BuildError::StatusCodeOutOfRange(600))),
+            "Test StatusCode::new-1 failed."
+        );
+        
+        assert_eq!(
+            (StatusCode::OK.to_string(), StatusCode(7).to_string()),
+            (String::from("200"), String::from("007")),
+            "Test StatusCode::fmt-1 failed."
+        );
+    }
+    #[test]
+    fn test_status_code_class() {
+        let classes = |code: StatusCode| [code.is_informational(), code.is_success(), code.is_redirection(), code.is_client_error(), code.is_server_error()];
+        let tests = [
+            (100, 0), (199, 0), (200, 1), (204, 1), (299, 1), (301, 2), (399, 2),
+            (400, 3), (418, 3), (499, 3), (500, 4), (599, 4)
+        ];
+        
+        for &(code, class) in tests.iter() {
+            let mut expected = [false; 5];
+            
+            expected[class] = true;
+            assert_eq!(
+                (StatusCode(code).is_valid(), classes(StatusCode(code))),
+                (true, expected),
+                "Test StatusCode::is_valid-1 failed for `{}`.", code
+            );
+        }
+        for &code in [0, 7, 99, 600, 999, u16::MAX].iter() {
+            assert_eq!(
+                (StatusCode(code).is_valid(), classes(StatusCode(code))),
+                (false, [false; 5]),
+                "Test StatusCode::is_valid-2 failed for `{}`.", code
+            );
+        }
+        
+        assert_eq!(
+            [100, 101, 199, 200, 204, 205, 304, 307, 404, 500].iter().map(|&code| StatusCode(code).allows_body()).collect::<Vec<bool>>(),
+            vec![false, false, false, true, false, true, false, true, true, true],
+            "Test StatusCode::allows_body-1 failed."
+        );
+    }
+    #[test]
+    fn test_reason_phrase() {
+        let tests = [
+            (100, "Continue"), (103, "Early Hints"), (200, "OK"), (204, "No Content"),
+            (206, "Partial Content"), (301, "Moved Permanently"), (308, "Permanent Redirect"),
+            (401, "Unauthorized"), (407, "Proxy Authentication Required"), (418, "I'm a teapot"),
+            (421, "Misdirected Request"), (425, "Too Early"), (428, "Precondition Required"),
+            (429, "Too Many Requests"), (431, "Request Header Fields Too Large"),
+            (451, "Unavailable For Legal Reasons"), (505, "HTTP Version Not Supported"),
+            (511, "Network Authentication Required")
+        ];
+        
+        for &(code, phrase) in tests.iter() {
+            assert_eq!(
+                reason_phrase(code),
+                Some(phrase),
+                "Test reason_phrase-1 failed for `{}`.", code
+            );
+        }
+        for code in [99, 199, 306, 420, 599, 1000].iter() {
+            assert_eq!(
+                reason_phrase(*code),
+                None,
+                "Test reason_phrase-2 failed for `{}`.", code
+            );
+        }
+    }
+}
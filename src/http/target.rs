@@ -0,0 +1,178 @@
+//! `target` is a module to handle the forms a request target may take.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::string::String;
+use super::ParseError;
+use super::host;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// A `RequestTarget` is the target of a `RequestLine` in one of the four forms of RFC 7230.
+pub enum RequestTarget {
+    /// The usual form of a target, `/path?query`.
+    OriginForm {
+        /// The absolute path of the target.
+        path: String,
+        /// The query of the target, without the `?`.
+        query: Option<String>
+    },
+    /// The form of a target sent to a proxy, `http://example.com/path?query`.
+    AbsoluteForm {
+        /// The lowercase scheme of the target.
+        scheme: String,
+        /// The host and optional port of the target.
+        authority: String,
+        /// The absolute path of the target, `/` if there was no path.
+        path: String,
+        /// The query of the target, without the `?`.
+        query: Option<String>
+    },
+    /// The form of a `CONNECT` target, `example.com:443`.
+    AuthorityForm {
+        /// The lowercase host of the target.
+        host: String,
+        /// The port of the target.
+        port: u16
+    },
+    /// The form of a server-wide `OPTIONS` target, `*`.
+    AsteriskForm
+}
+
+impl RequestTarget {
+    /// Converts the passed `str` to a `RequestTarget`.
+    ///
+    /// # Params
+    ///
+    /// target --- The `str` to convert to a `RequestTarget`.
+    pub fn from(target: &str) -> Result<RequestTarget, ParseError> {
+        let invalid = || ParseError::InvalidTarget(String::from(target));
+        
+        if target == "*" {
+            return Ok(RequestTarget::AsteriskForm);
+        }
+        if target.starts_with('/') {
+            let (path, query) = split_query(target);
+            return Ok(RequestTarget::OriginForm { path: String::from(path), query });
+        }
+        
+        // An absolute target begins with a scheme, the authority follows the `//`.
+        if let Some(index) = target.find("://") {
+            let scheme = &target[..index];
+            if !is_scheme(scheme) {
+                return Err(invalid());
+            }
+            
+            let rest = &target[index + 3..];
+            let end = rest.find(&['/', '?'][..]).unwrap_or(rest.len());
+            let authority = &rest[..end];
+            host::parse(authority).map_err(|_| invalid())?;
+            let (path, query) = split_query(&rest[end..]);
+            
+            return Ok(
+                RequestTarget::AbsoluteForm {
+                    scheme: scheme.to_lowercase(),
+                    authority: String::from(authority),
+                    path: String::from(if path.is_empty() { "/" } else { path }),
+                    query
+                }
+            );
+        }
+        
+        // Otherwise the target can only be an authority, which must have a port.
+        match host::parse(target) {
+            Ok((host, Some(port))) => Ok(RequestTarget::AuthorityForm { host, port }),
+            _ => Err(invalid())
+        }
+    }
+}
+
+/// Returns `true` if the passed `str` is a valid URI scheme.
+///
+/// # Params
+///
+/// scheme --- The `str` to check.
+fn is_scheme(scheme: &str) -> bool {
+    scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'-' || b == b'.')
+}
+
+/// Returns the part of a target before the first `?` and the part after it, if any.
+///
+/// # Params
+///
+/// target --- The `str` to split.
+fn split_query(target: &str) -> (&str, Option<String>) {
+    match target.find('?') {
+        Some(index) => (&target[..index], Some(String::from(&target[index + 1..]))),
+        None => (target, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_target() {
+        assert_eq!(
+            RequestTarget::from("/where?q=now"),
+            Ok(RequestTarget::OriginForm { path: String::from("/where"), query: Some(String::from("q=now")) }),
+            "Test RequestTarget::from-1 failed."
+        );
+        
+        assert_eq!(
+            RequestTarget::from("/"),
+            Ok(RequestTarget::OriginForm { path: String::from("/"), query: None }),
+            "Test RequestTarget::from-2 failed."
+        );
+        
+        assert_eq!(
+            RequestTarget::from("HTTP://www.Example.org:8080/pub/WWW/TheProject.html?a=b"),
+            Ok(
+                RequestTarget::AbsoluteForm {
+                    scheme: String::from("http"),
+                    authority: String::from("www.Example.org:8080"),
+                    path: String::from("/pub/WWW/TheProject.html"),
+                    query: Some(String::from("a=b"))
+                }
+            ),
+            "Test RequestTarget::from-3 failed."
+        );
+        
+        assert_eq!(
+            RequestTarget::from("https://[::1]?x"),
+            Ok(
+                RequestTarget::AbsoluteForm {
+                    scheme: String::from("https"),
+                    authority: String::from("[::1]"),
+                    path: String::from("/"),
+                    query: Some(String::from("x"))
+                }
+            ),
+            "Test RequestTarget::from-4 failed."
+        );
+        
+        assert_eq!(
+            RequestTarget::from("www.example.com:443"),
+            Ok(RequestTarget::AuthorityForm { host: String::from("www.example.com"), port: 443 }),
+            "Test RequestTarget::from-5 failed."
+        );
+        
+        assert_eq!(
+            RequestTarget::from("*"),
+            Ok(RequestTarget::AsteriskForm),
+            "Test RequestTarget::from-6 failed."
+        );
+        
+        for target in ["http://", "http:///path", "1http://example.com/", "http://exa mple.com/", "http://user@example.com/", "example.com", "**", ""].iter() {
+            assert_eq!(
+                RequestTarget::from(target),
+                Err(ParseError::InvalidTarget(String::from(*target))),
+                "Test RequestTarget::from-7 failed for `{}`.", target
+            );
+        }
+    }
+}
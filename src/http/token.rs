@@ -0,0 +1,376 @@
+//! `token` is a module to handle the `token` and `quoted-string` grammar of RFC 7230 used by Header Field names, cookie names and parameters.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::string::String;
+use super::{ParseError, ParseMode};
+
+/// Returns `true` if the byte may appear in a token.
+///
+/// # Params
+///
+/// byte --- The byte to check.
+pub fn is_token_char(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte)
+}
+
+/// Fails with `IllegalCharacter` at the first control character in the passed bytes other
+/// than a horizontal tab or a line terminator, i.e. any byte below `0x20` and `0x7F`.
+///
+/// A line terminator is a CR immediately followed by a LF or, when parsing `Lenient`ly, a
+/// bare LF. A bare LF fails with `BareLineFeed` when parsing `Strict`ly.</br>
+/// Only the position is reported so the bytes do not need to be echoed to be logged.
+///
+/// # Params
+///
+/// bytes --- The bytes of a start line or header section to check.</br>
+/// mode --- If a bare LF is a line terminator.
+pub fn check_controls(bytes: &[u8], mode: ParseMode) -> Result<(), ParseError> {
+    let mut index = 0;
+    
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\r' if bytes.get(index + 1) == Some(&b'\n') => index += 1,
+            b'\t' => (),
+            b'\n' if mode == ParseMode::Lenient => (),
+            b'\n' => return Err(ParseError::BareLineFeed { position: index }),
+            byte if byte < 0x20 || byte == 0x7F => return Err(ParseError::IllegalCharacter { position: index }),
+            _ => ()
+        }
+        index += 1;
+    }
+    Ok(())
+}
+
+/// Checks a single start line or Header field line as by `check_controls`, only its end
+/// may be a line terminator.
+///
+/// A LF within the line fails with `IllegalCharacter` however strict the parse is, as it
+/// would inject another line.
+///
+/// # Params
+///
+/// line --- The bytes of the line to check.</br>
+/// mode --- If a bare LF is a line terminator.
+pub fn check_line_controls(line: &[u8], mode: ParseMode) -> Result<(), ParseError> {
+    let end = if line.ends_with(b"\r\n") {
+        line.len() - 2
+    } else if line.ends_with(b"\n") {
+        line.len() - 1
+    } else {
+        line.len()
+    };
+    
+    match check_controls(line, mode) {
+        Err(ParseError::BareLineFeed { position }) if position < end => Err(ParseError::IllegalCharacter { position }),
+        Err(error) => Err(error),
+        Ok(()) => match line[..end].iter().position(|&byte| byte == b'\n') {
+            Some(position) => Err(ParseError::IllegalCharacter { position }),
+            None => Ok(())
+        }
+    }
+}
+
+/// Returns the length of the header section at the start of the passed bytes, up to and
+/// including the empty line which ends it, if it is complete.
+///
+/// Either line of the empty line may end with a bare LF, `check_controls` decides if
+/// that is allowed.
+///
+/// # Params
+///
+/// bytes --- The bytes of a message, or the start of one.
+pub fn head_len(bytes: &[u8]) -> Option<usize> {
+    bytes.iter().enumerate()
+        .filter(|&(_, &byte)| byte == b'\n')
+        .filter_map(|(index, _)| match &bytes[index + 1..] {
+            rest if rest.starts_with(b"\n") => Some(index + 2),
+            rest if rest.starts_with(b"\r\n") => Some(index + 3),
+            _ => None
+        })
+        .next()
+}
+
+/// Returns `true` if the passed `str` is a non-empty token.
+///
+/// # Params
+///
+/// s --- The `str` to check.
+pub fn is_token(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(is_token_char)
+}
+
+/// Returns the `name=value` parameters following the first `;` of a Header Field value.
+///
+/// Names are lowercased and quoted values are unquoted.
+///
+/// # Params
+///
+/// value --- The Header Field value to get the parameters of.
+pub fn parameters(value: &str) -> Vec<(String, String)> {
+    split_unquoted(value, ';').into_iter()
+        .skip(1)
+        .filter_map(
+            |param| {
+                let index = param.find('=')?;
+                let name = param[..index].trim().to_lowercase();
+                
+                Some((name, unquote(param[index + 1..].trim())))
+            }
+        ).collect()
+}
+
+/// Returns the elements of a comma-separated list Header Field value, as in RFC 7230
+/// section 7.
+///
+/// The value is only split on commas which are not inside a quoted-string, each element
+/// is trimmed and empty elements are dropped.
+///
+/// # Params
+///
+/// value --- The Header Field value to split, e.g. `W/"a,b", "c"`.
+pub fn split_list(value: &str) -> Vec<&str> {
+    split_unquoted(value, ',').into_iter()
+        .map(str::trim)
+        .filter(|element| !element.is_empty())
+        .collect()
+}
+
+/// Returns the parts of the passed value, split on every `separator` which is not inside a
+/// quoted-string.
+///
+/// # Params
+///
+/// value --- The value to split.</br>
+/// separator --- The character to split on.
+fn split_unquoted(value: &str, separator: char) -> Vec<&str> {
+    let mut res = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+    for (index, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            _ if c == separator && !quoted => {
+                res.push(&value[start..index]);
+                start = index + 1;
+            },
+            _ => ()
+        }
+    }
+    res.push(&value[start..]);
+    res
+}
+
+/// Parses the quoted-string at the start of the passed `str`, as in RFC 7230 section 3.2.6.</br>
+/// Returns the unescaped contents of the quoted-string and the rest of the `str` after it.
+///
+/// Fails if the `str` does not start with a quote, the quoted-string is unterminated or it
+/// contains a control character other than a tab.
+///
+/// # Params
+///
+/// s --- The `str` starting with a quoted-string.
+pub fn parse_quoted_string(s: &str) -> Result<(String, &str), ParseError> {
+    let invalid = || ParseError::InvalidQuotedString(String::from(s));
+    
+    if !s.starts_with('"') {
+        return Err(invalid());
+    }
+    
+    let mut res = String::new();
+    let mut chars = s.char_indices().skip(1);
+    while let Some((index, c)) = chars.next() {
+        let c = match c {
+            '"' => return Ok((res, &s[index + 1..])),
+            // A quoted-pair stands for the character after the backslash.
+            '\\' => match chars.next() {
+                Some((_, c)) => c,
+                None => break
+            },
+            _ => c
+        };
+        if c.is_control() && c != '\t' {
+            return Err(invalid());
+        }
+        res.push(c);
+    }
+    Err(invalid())
+}
+
+/// Returns the contents of the passed value if it is a single quoted-string, otherwise the
+/// value as it is.
+///
+/// # Params
+///
+/// value --- The value to unquote.
+pub fn unquote(value: &str) -> String {
+    match parse_quoted_string(value) {
+        Ok((res, "")) => res,
+        _ => String::from(value)
+    }
+}
+
+/// Returns the passed `str` as a quoted-string, escaping any quotes and backslashes.
+///
+/// # Params
+///
+/// s --- The `str` to quote.
+pub fn quote_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_token() {
+        assert!(is_token("Content-Type"), "Test token::is_token-1 failed.");
+        assert!(is_token("!#$%&'*+-.^_`|~09azAZ"), "Test token::is_token-2 failed.");
+        assert!(!is_token(""), "Test token::is_token-3 failed.");
+        assert!(!is_token("Content Type"), "Test token::is_token-4 failed.");
+        assert!(!is_token("Host "), "Test token::is_token-5 failed.");
+        assert!(!is_token("X-\u{7}"), "Test token::is_token-6 failed.");
+        assert!(!is_token("a(b)"), "Test token::is_token-7 failed.");
+    }
+    #[test]
+    fn test_check_controls() {
+        for &s in ["", "GET / HTTP/1.1\r\n", "X: a\tb\r\nY: \u{80}c\r\n\r\n"].iter() {
+            assert_eq!(
+                (check_controls(s.as_bytes(), ParseMode::Lenient), check_controls(s.as_bytes(), ParseMode::Strict)),
+                (Ok(()), Ok(())),
+                "Test token::check_controls-1 failed for `{:?}`.", s
+            );
+        }
+        
+        let tests = [("/a\0b", 2), ("X: a\rb", 4), ("X: a\r\r\n", 4), ("\x01", 0), ("ok\r\n\x7F", 4), ("\r", 0)];
+        for &(s, position) in tests.iter() {
+            assert_eq!(
+                (check_controls(s.as_bytes(), ParseMode::Lenient), check_controls(s.as_bytes(), ParseMode::Strict)),
+                (Err(ParseError::IllegalCharacter { position }), Err(ParseError::IllegalCharacter { position })),
+                "Test token::check_controls-2 failed for `{:?}`.", s
+            );
+        }
+        
+        let tests = [("a\n", 1), ("X: a\r\nY: b\n\r\n", 10)];
+        for &(s, position) in tests.iter() {
+            assert_eq!(
+                (check_controls(s.as_bytes(), ParseMode::Lenient), check_controls(s.as_bytes(), ParseMode::Strict)),
+                (Ok(()), Err(ParseError::BareLineFeed { position })),
+                "Test token::check_controls-3 failed for `{:?}`.", s
+            );
+        }
+    }
+    #[test]
+    fn test_check_line_controls() {
+        for &s in ["GET / HTTP/1.1", "GET / HTTP/1.1\r\n"].iter() {
+            assert_eq!(
+                (check_line_controls(s.as_bytes(), ParseMode::Lenient), check_line_controls(s.as_bytes(), ParseMode::Strict)),
+                (Ok(()), Ok(())),
+                "Test token::check_line_controls-1 failed for `{:?}`.", s
+            );
+        }
+        assert_eq!(
+            (check_line_controls(b"GET / HTTP/1.1\n", ParseMode::Lenient), check_line_controls(b"GET / HTTP/1.1\n", ParseMode::Strict)),
+            (Ok(()), Err(ParseError::BareLineFeed { position: 14 })),
+            "Test token::check_line_controls-2 failed."
+        );
+        for &(s, position) in [("Location: x\nSet-Cookie: evil", 11), ("Location: x\r\nSet-Cookie: evil\r\n", 12)].iter() {
+            assert_eq!(
+                (check_line_controls(s.as_bytes(), ParseMode::Lenient), check_line_controls(s.as_bytes(), ParseMode::Strict)),
+                (Err(ParseError::IllegalCharacter { position }), Err(ParseError::IllegalCharacter { position })),
+                "Test token::check_line_controls-3 failed for `{:?}`.", s
+            );
+        }
+    }
+    #[test]
+    fn test_head_len() {
+        let tests = [
+            ("GET / HTTP/1.1\r\nHost: a\r\n\r\nbody\n\n", Some(27)),
+            ("GET / HTTP/1.1\nHost: a\n\nbody", Some(24)),
+            ("GET / HTTP/1.1\nHost: a\n\r\nbody", Some(25)),
+            ("GET / HTTP/1.1\r\nHost: a\r\n\nbody", Some(26)),
+            ("GET / HTTP/1.1\r\nHost: a\r\n", None),
+            ("GET / HTTP/1.1\r\nHost: a\r\n\r", None)
+        ];
+        for &(s, len) in tests.iter() {
+            assert_eq!(
+                head_len(s.as_bytes()),
+                len,
+                "Test token::head_len-1 failed for `{:?}`.", s
+            );
+        }
+    }
+    #[test]
+    fn test_parameters() {
+        assert_eq!(
+            parameters("form-data; Name=\"a \\\"b\\\";c\"; filename=x.txt; flag"),
+            vec![(String::from("name"), String::from("a \"b\";c")), (String::from("filename"), String::from("x.txt"))],
+            "Test token::parameters-1 failed."
+        );
+    }
+    #[test]
+    fn test_split_list() {
+        let tests: [(&str, Vec<&str>); 6] = [
+            ("W/\"a,b\", \"c\"", vec!["W/\"a,b\"", "\"c\""]),
+            ("gzip, deflate ,br", vec!["gzip", "deflate", "br"]),
+            (", ,keep-alive,, Upgrade ,", vec!["keep-alive", "Upgrade"]),
+            ("private=\"a, b\", max-age=60", vec!["private=\"a, b\"", "max-age=60"]),
+            ("\"a\\\",b\", c", vec!["\"a\\\",b\"", "c"]),
+            ("", vec![])
+        ];
+        
+        for &(value, ref expected) in tests.iter() {
+            assert_eq!(
+                split_list(value),
+                *expected,
+                "Test token::split_list failed for `{}`.", value
+            );
+        }
+    }
+    #[test]
+    fn test_quoted_string() {
+        assert_eq!(
+            quote_string("a \"b\" \\c"),
+            "\"a \\\"b\\\" \\\\c\"",
+            "Test token::quote_string-1 failed."
+        );
+        
+        let tests = [
+            ("\"plain\"", Ok((String::from("plain"), ""))),
+            ("\"a \\\"b\\\" \\\\c\"; rest", Ok((String::from("a \"b\" \\c"), "; rest"))),
+            ("\"\\q\tx\"", Ok((String::from("q\tx"), ""))),
+            ("\"\"", Ok((String::new(), ""))),
+            ("\"unterminated", Err(ParseError::InvalidQuotedString(String::from("\"unterminated")))),
+            ("\"escaped end\\\"", Err(ParseError::InvalidQuotedString(String::from("\"escaped end\\\"")))),
+            ("\"bad\r\nline\"", Err(ParseError::InvalidQuotedString(String::from("\"bad\r\nline\"")))),
+            ("\"null\\\u{0}\"", Err(ParseError::InvalidQuotedString(String::from("\"null\\\u{0}\"")))),
+            ("unquoted", Err(ParseError::InvalidQuotedString(String::from("unquoted"))))
+        ];
+        for &(input, ref expected) in tests.iter() {
+            assert_eq!(
+                parse_quoted_string(input),
+                *expected,
+                "Test token::parse_quoted_string failed for `{:?}`.", input
+            );
+        }
+        
+        let value = "say \"hi\" \\ bye";
+        assert_eq!(
+            parse_quoted_string(&quote_string(value)),
+            Ok((String::from(value), "")),
+            "Test token::parse_quoted_string failed to round-trip."
+        );
+        assert_eq!(
+            (unquote("\"a\\\"b\""), unquote("token"), unquote("\"a\" b")),
+            (String::from("a\"b"), String::from("token"), String::from("\"a\" b")),
+            "Test token::unquote-1 failed."
+        );
+    }
+}
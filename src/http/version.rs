@@ -0,0 +1,116 @@
+//! `version` is a module to handle the HTTP version of a HTTP message.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::fmt;
+use std::string::String;
+use super::ParseError;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+/// A `Version` is the HTTP version of a message, e.g. `HTTP/1.1`.
+///
+/// Versions are ordered by their `major` then `minor` numbers.
+pub struct Version {
+    /// The major version number.
+    pub major: u8,
+    /// The minor version number.
+    pub minor: u8
+}
+
+impl Version {
+    /// `HTTP/0.9`
+    pub const HTTP_09: Version = Version { major: 0, minor: 9 };
+    /// `HTTP/1.0`
+    pub const HTTP_10: Version = Version { major: 1, minor: 0 };
+    /// `HTTP/1.1`
+    pub const HTTP_11: Version = Version { major: 1, minor: 1 };
+    /// `HTTP/2.0`
+    pub const HTTP_20: Version = Version { major: 2, minor: 0 };
+    
+    /// Converts the passed `str` to a `Version`.
+    ///
+    /// The version must be of the form `HTTP/x.y` where `x` and `y` are decimal numbers,
+    /// the `HTTP` is case-insensitive.
+    ///
+    /// # Params
+    ///
+    /// version --- The `str` to convert to a `Version`.
+    pub fn from(version: &str) -> Result<Version, ParseError> {
+        let invalid = || ParseError::InvalidVersion(String::from(version));
+        
+        // The name of the protocol is case-insensitive.
+        match version.get(..5) {
+            Some(name) if name.eq_ignore_ascii_case("HTTP/") => (),
+            _ => return Err(invalid())
+        }
+        
+        let mut numbers = version[5..].splitn(2, '.');
+        let major = numbers.next().unwrap_or_default();
+        let minor = numbers.next().unwrap_or_default();
+        // Only plain digits are valid, `parse` would also accept a sign.
+        if [major, minor].iter().any(|n| n.is_empty() || !n.bytes().all(|b| b.is_ascii_digit())) {
+            return Err(invalid());
+        }
+        
+        match (major.parse(), minor.parse()) {
+            (Ok(major), Ok(minor)) => Ok(Version { major, minor }),
+            _ => Err(invalid())
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    /// Writes the version in its canonical form, e.g. `HTTP/1.1`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HTTP/{}.{}", self.major, self.minor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version() {
+        assert_eq!(
+            Version::from("HTTP/1.1").unwrap(),
+            Version::HTTP_11,
+            "Test Version::from-1 failed."
+        );
+        
+        assert_eq!(
+            Version::from("http/1.0").unwrap(),
+            Version::HTTP_10,
+            "Test Version::from-2 failed."
+        );
+        
+        assert_eq!(
+            Version::from("HTTP/2.1").unwrap(),
+            Version { major: 2, minor: 1 },
+            "Test Version::from-3 failed."
+        );
+        
+        for version in ["HTTP/one.two", "HTTP/11", "HTTP/1.", "HTTP/+1.1", "HTTP/1.1.1", "HTTP/256.0", "HTTPS/1.1", "HTT\u{e9}/1.1", ""].iter() {
+            assert_eq!(
+                Version::from(version),
+                Err(ParseError::InvalidVersion(String::from(*version))),
+                "Test Version::from-4 failed for `{}`.", version
+            );
+        }
+        
+        assert_eq!(
+            (Version::HTTP_11.to_string(), Version::from("http/2.0").unwrap().to_string()),
+            (String::from("HTTP/1.1"), String::from("HTTP/2.0")),
+            "Test Version::fmt-1 failed."
+        );
+        
+        assert!(
+            Version::HTTP_09 < Version::HTTP_10 && Version::HTTP_10 < Version::HTTP_11
+                && Version::HTTP_11 < Version::HTTP_20 && Version { major: 1, minor: 10 } > Version::HTTP_11,
+            "Test Version::cmp-1 failed."
+        );
+    }
+}
@@ -6,6 +6,13 @@
 //! Author --- Daniel Bechaz</br>
 //! Date --- 06/09/2017
 
+#[cfg(any(feature = "json", feature = "serde"))]
+extern crate serde;
+#[cfg(feature = "json")]
+extern crate serde_json;
+#[cfg(feature = "compression")]
+extern crate flate2;
+
 pub mod server;
 pub mod http;
 pub mod logging;
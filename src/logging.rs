@@ -76,10 +76,7 @@ impl Logger {
     pub fn start_custom<P: AsRef<Path>>(path: P, write_func: WriteFunc) -> Result<Logger, Error> {
         let file = match File::open(&path) {
             Ok(file) => file,
-            Err(_) => match File::create(path) {
-                Ok(file) => file,
-                Err(e) => return Err(e)
-            }
+            Err(_) => File::create(path)?
         };
         
         Ok(Logger { file, write_func })
@@ -114,9 +111,9 @@ mod tests {
     
     #[test]
     fn test_logger() {
-        if let Err(_) = Logger::start("test.log") {
+        if Logger::start("test.log").is_err() {
             panic!("Logger test-1 failed.");
-        } else if let Err(_) = remove_file("test.log") {
+        } else if remove_file("test.log").is_err() {
             panic!("Logger test-1 failed in cleanup.");
         }
     }
@@ -0,0 +1,171 @@
+//! `accept` is a module to accept the connections to a Web Server until it is shutdown,
+//! without polling the listener.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::io::ErrorKind;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+use super::server::Message;
+
+/// The delay before `accept` is retried after the first error which is not the fault of a
+/// single connection.
+pub const MIN_ACCEPT_BACKOFF: Duration = Duration::from_millis(5);
+/// The longest delay before `accept` is retried, however many errors there have been.
+pub const MAX_ACCEPT_BACKOFF: Duration = Duration::from_secs(1);
+
+/// An `AcceptLoop` blocks in `accept` for each connection to a listener and stops once the
+/// `Server` is shutdown.
+///
+/// `Server::shutdown` sends the `Shutdown` `Message` and then wakes the listener with a
+/// connection of its own, as by `wake`, which the `AcceptLoop` drops once it sees the
+/// `Message`.
+pub struct AcceptLoop {
+    /// The listener to accept connections from.
+    listener: TcpListener,
+    /// The `Receiver` of `Message`s for the `Server`.
+    receiver: Receiver<Message>,
+    #[cfg(test)]
+    /// The number of times `accept` has been called.
+    accepts: usize,
+    #[cfg(test)]
+    /// The number of times `accept` has been retried after a delay.
+    backoffs: usize
+}
+
+impl AcceptLoop {
+    /// Returns a new `AcceptLoop` over the passed listener, which is set to blocking.
+    ///
+    /// # Params
+    ///
+    /// listener --- The listener to accept connections from.</br>
+    /// receiver --- The `Receiver` of `Message`s for the `Server`.
+    pub fn new(listener: TcpListener, receiver: Receiver<Message>) -> AcceptLoop {
+        listener.set_nonblocking(false)
+            .expect("Listener cannot be set to blocking.");
+        
+        AcceptLoop {
+            listener,
+            receiver,
+            #[cfg(test)]
+            accepts: 0,
+            #[cfg(test)]
+            backoffs: 0
+        }
+    }
+    /// Returns `true` if the `Server` has been shutdown, or can no longer be reached.
+    fn is_shutdown(&self) -> bool {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(Message::Shutdown) | Err(TryRecvError::Disconnected) => return true,
+                // A generic `Message` means nothing to the `AcceptLoop`.
+                Ok(Message::Code(_)) => (),
+                Err(TryRecvError::Empty) => return false
+            }
+        }
+    }
+}
+
+impl Iterator for AcceptLoop {
+    type Item = TcpStream;
+    
+    /// Blocks until the next connection is accepted.</br>
+    /// Returns `None` once the `Server` is shutdown.
+    ///
+    /// An error which would fail every `accept` until something changes, e.g. running out
+    /// of file descriptors, is retried after a delay which doubles from
+    /// `MIN_ACCEPT_BACKOFF` up to `MAX_ACCEPT_BACKOFF`, rather than spinning.
+    fn next(&mut self) -> Option<TcpStream> {
+        let mut backoff = MIN_ACCEPT_BACKOFF;
+        
+        loop {
+            let accepted = self.listener.accept();
+            #[cfg(test)]
+            {
+                self.accepts += 1;
+            }
+            
+            // The connection which woke the listener is dropped along with the loop.
+            if self.is_shutdown() {
+                return None;
+            }
+            match accepted {
+                Ok((stream, _)) => return Some(stream),
+                // A failed connection is not fatal to the listener, e.g. a client which reset.
+                Err(ref e) if is_connection_error(e.kind()) => (),
+                Err(_) => {
+                    #[cfg(test)]
+                    {
+                        self.backoffs += 1;
+                    }
+                    thread::sleep(backoff);
+                    backoff = (backoff * 2).min(MAX_ACCEPT_BACKOFF);
+                }
+            }
+        }
+    }
+}
+
+/// Returns `true` if an `accept` error was the fault of the connection being accepted,
+/// so the next `accept` can be tried straight away.
+///
+/// # Params
+///
+/// kind --- The kind of the error.
+fn is_connection_error(kind: ErrorKind) -> bool {
+    matches!(kind, ErrorKind::ConnectionAborted | ErrorKind::ConnectionReset | ErrorKind::Interrupted)
+}
+
+/// Wakes an `AcceptLoop` blocked on the listener at the passed address by connecting to it.
+///
+/// An unspecified address, e.g. `0.0.0.0`, is reached through the loopback address.
+///
+/// # Params
+///
+/// addr --- The address the listener is bound to.
+pub fn wake(addr: SocketAddr) {
+    let ip = match addr.ip() {
+        IpAddr::V4(ip) if ip.is_unspecified() => IpAddr::V4(Ipv4Addr::LOCALHOST),
+        IpAddr::V6(ip) if ip.is_unspecified() => IpAddr::V6(Ipv6Addr::LOCALHOST),
+        ip => ip
+    };
+    
+    // The listener has already closed if nothing is listening.
+    let _ = TcpStream::connect(SocketAddr::new(ip, addr.port()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_accept_loop() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (sender, receiver) = channel();
+        let mut accept = AcceptLoop::new(listener, receiver);
+        let clients = (0..3).map(|_| TcpStream::connect(addr).unwrap()).collect::<Vec<_>>();
+        
+        assert_eq!(
+            accept.by_ref().take(clients.len()).count(),
+            clients.len(),
+            "Test AcceptLoop-1 failed."
+        );
+        
+        sender.send(Message::Shutdown).unwrap();
+        wake(addr);
+        assert!(accept.next().is_none(), "Test AcceptLoop-2 failed.");
+        // Every `accept` blocked until a connection arrived, none were polled or delayed.
+        assert_eq!(
+            (accept.accepts, accept.backoffs),
+            (clients.len() + 1, 0),
+            "Test AcceptLoop-3 failed."
+        );
+    }
+}
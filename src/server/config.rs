@@ -0,0 +1,190 @@
+//! `config` is a module to configure how long a Web Server keeps each connection open.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::time::Duration;
+
+/// How long a request may take to arrive once it has started, or to be sent the first
+/// request after connecting.
+pub const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long the header section of a request may take to arrive once it has started.
+pub const HEADER_DEADLINE: Duration = Duration::from_secs(10);
+/// How long a response may take to be written.
+pub const WRITE_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long a connection may wait for its next request before it is closed.
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(5);
+/// The most requests answered over a connection before it is closed.
+pub const MAX_REQUESTS: usize = 100;
+/// The most bytes in the body of a request, after any chunked framing is removed.
+pub const MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// How long a `Server` keeps each connection open, applied to every accepted stream.
+pub struct ServerConfig {
+    /// How long a request may take to arrive once it has started, or to be sent the first
+    /// request after connecting.
+    pub read_timeout: Duration,
+    /// How long the header section of a request may take to arrive once it has started,
+    /// however many reads it arrives over.
+    pub header_deadline: Duration,
+    /// How long a response may take to be written.
+    pub write_timeout: Duration,
+    /// How long a connection may wait for its next request before it is closed.
+    pub idle_keepalive_timeout: Duration,
+    /// The most requests answered over a connection, the last response closes it.
+    pub max_requests: usize,
+    /// The most bytes in the body of a request, after any chunked framing is removed.
+    pub max_body_bytes: usize,
+    /// If an HTTP/0.9 simple-request is answered, with its body alone.
+    pub accept_http09: bool
+}
+
+impl ServerConfig {
+    /// Returns a new `ServerConfig` with the default timeouts and `max_requests`.
+    pub fn new() -> ServerConfig {
+        ServerConfig::default()
+    }
+    /// Sets how long a request may take to arrive once it has started, or to be sent the
+    /// first request after connecting.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `timeout` is zero.
+    ///
+    /// # Params
+    ///
+    /// timeout --- The read timeout of each connection.
+    pub fn with_read_timeout(mut self, timeout: Duration) -> ServerConfig {
+        assert!(timeout > Duration::ZERO, "The read timeout must not be zero.");
+        self.read_timeout = timeout;
+        self
+    }
+    /// Sets how long the header section of a request may take to arrive once it has
+    /// started.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `deadline` is zero.
+    ///
+    /// # Params
+    ///
+    /// deadline --- The header deadline of each request.
+    pub fn with_header_deadline(mut self, deadline: Duration) -> ServerConfig {
+        assert!(deadline > Duration::ZERO, "The header deadline must not be zero.");
+        self.header_deadline = deadline;
+        self
+    }
+    /// Sets how long a response may take to be written.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `timeout` is zero.
+    ///
+    /// # Params
+    ///
+    /// timeout --- The write timeout of each connection.
+    pub fn with_write_timeout(mut self, timeout: Duration) -> ServerConfig {
+        assert!(timeout > Duration::ZERO, "The write timeout must not be zero.");
+        self.write_timeout = timeout;
+        self
+    }
+    /// Sets how long a connection may wait for its next request before it is closed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `timeout` is zero.
+    ///
+    /// # Params
+    ///
+    /// timeout --- The idle timeout of each connection.
+    pub fn with_idle_keepalive_timeout(mut self, timeout: Duration) -> ServerConfig {
+        assert!(timeout > Duration::ZERO, "The idle keep-alive timeout must not be zero.");
+        self.idle_keepalive_timeout = timeout;
+        self
+    }
+    /// Sets the most requests answered over a connection before it is closed.
+    ///
+    /// # Params
+    ///
+    /// requests --- The most requests answered over each connection.
+    pub fn with_max_requests(mut self, requests: usize) -> ServerConfig {
+        self.max_requests = requests;
+        self
+    }
+    /// Sets the most bytes in the body of a request, a larger one is answered with a
+    /// `413 Content Too Large` and the connection is closed.
+    ///
+    /// # Params
+    ///
+    /// bytes --- The most bytes in the body of each request.
+    pub fn with_max_body_bytes(mut self, bytes: usize) -> ServerConfig {
+        self.max_body_bytes = bytes;
+        self
+    }
+    /// Sets if an HTTP/0.9 simple-request, e.g. `GET /`, is answered with the body of its
+    /// response alone rather than rejected.
+    ///
+    /// # Params
+    ///
+    /// accept --- If simple-requests are answered.
+    pub fn with_http09(mut self, accept: bool) -> ServerConfig {
+        self.accept_http09 = accept;
+        self
+    }
+}
+
+impl Default for ServerConfig {
+    /// Returns a config of a `READ_TIMEOUT` read timeout, a `HEADER_DEADLINE` header
+    /// deadline, a `WRITE_TIMEOUT` write timeout, an `IDLE_TIMEOUT` idle timeout,
+    /// `MAX_REQUESTS` requests and `MAX_BODY_BYTES` of body, which does not answer
+    /// HTTP/0.9 simple-requests.
+    fn default() -> ServerConfig {
+        ServerConfig {
+            read_timeout: READ_TIMEOUT,
+            header_deadline: HEADER_DEADLINE,
+            write_timeout: WRITE_TIMEOUT,
+            idle_keepalive_timeout: IDLE_TIMEOUT,
+            max_requests: MAX_REQUESTS,
+            max_body_bytes: MAX_BODY_BYTES,
+            accept_http09: false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_config() {
+        let config = ServerConfig::new()
+            .with_read_timeout(Duration::from_secs(1))
+            .with_header_deadline(Duration::from_secs(5))
+            .with_write_timeout(Duration::from_secs(2))
+            .with_idle_keepalive_timeout(Duration::from_secs(3))
+            .with_max_requests(4)
+            .with_max_body_bytes(6)
+            .with_http09(true);
+        assert_eq!(
+            config,
+            ServerConfig {
+                read_timeout: Duration::from_secs(1),
+                header_deadline: Duration::from_secs(5),
+                write_timeout: Duration::from_secs(2),
+                idle_keepalive_timeout: Duration::from_secs(3),
+                max_requests: 4,
+                max_body_bytes: 6,
+                accept_http09: true
+            },
+            "Test ServerConfig::new failed."
+        );
+    }
+    #[test]
+    #[should_panic]
+    fn test_server_config_zero_timeout() {
+        ServerConfig::new().with_read_timeout(Duration::ZERO);
+    }
+}
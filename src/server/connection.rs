@@ -0,0 +1,494 @@
+//! `connection` is a module to answer the requests sent over a connection to a Web Server.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+use http::{BodyFraming, MessageHTTP, ParseError, ParseLimits, ParseMode, Version, read_body, read_head_with_http09};
+use http::start_line::StartLine;
+use http::{headers, responses};
+use super::config::ServerConfig;
+
+/// Answers each request sent over `stream` with `handler` until the connection closes,
+/// as by `serve_connection_with_config` with the default `ServerConfig`.
+///
+/// # Params
+///
+/// stream --- The connection to the client.</br>
+/// handler --- Answers each request, passed the address of the client.
+pub fn serve_connection<F>(stream: TcpStream, handler: &F)
+    where F: Fn(MessageHTTP, SocketAddr) -> MessageHTTP {
+    serve_connection_with_config(stream, handler, &ServerConfig::default())
+}
+
+/// Answers each request sent over `stream` with `handler` until the connection closes.
+///
+/// The requests are answered as by `handle_connection_loop` with the timeouts of `config`
+/// applied to `stream`. A connection which waits longer than the `read_timeout` for its
+/// first request, or the `idle_keepalive_timeout` for each later one, is closed. A request
+/// which starts but does not arrive within the `read_timeout`, or whose header section does
+/// not arrive within the `header_deadline`, is answered with a `408 Request Timeout` and the
+/// connection is closed, as it is if a response is not written within the `write_timeout`.
+///
+/// # Params
+///
+/// stream --- The connection to the client.</br>
+/// handler --- Answers each request, passed the address of the client.</br>
+/// config --- How long the connection is kept open for.
+pub fn serve_connection_with_config<F>(stream: TcpStream, handler: &F, config: &ServerConfig)
+    where F: Fn(MessageHTTP, SocketAddr) -> MessageHTTP {
+    let peer = match stream.peer_addr() {
+        Ok(peer) => peer,
+        Err(_) => return
+    };
+    if stream.set_write_timeout(Some(config.write_timeout)).is_err() {
+        return;
+    }
+    connection_loop(&stream, peer, handler, config, &|timeout| stream.set_read_timeout(Some(timeout)))
+}
+
+/// Answers each request sent over `stream` with `handler` until the connection closes.
+///
+/// Requests are read as by `read_message_with_mode` with the default `ParseLimits`, but the
+/// `max_body_bytes` of `config`, and `ParseMode::Strict`, the header section of each must
+/// arrive within the `header_deadline` of `config` however slowly its bytes trickle in,
+/// the responses are written as by `MessageHTTP::write_response_to`.
+/// The connection is kept open for the next request unless the request or the response
+/// asks for it to close, the end of the response could only be found by closing it, or
+/// `max_requests` have been answered. A response without a `Content-Length` or
+/// `Transfer-Encoding` is given a `Content-Length`. A request which can not be read is
+/// answered as by `responses::parse_error` without calling `handler`, and a `handler`
+/// which panics is answered with a `500 Internal Server Error`, either way the connection
+/// is then closed. If `config` accepts HTTP/0.9 a simple-request is answered with the
+/// body of its response alone and the connection is closed.</br>
+/// The timeouts of `config` are not applied to `stream`, a read which fails before a
+/// request starts closes the connection and one which fails part way through, or a header
+/// section which passes its deadline, is answered with a `408 Request Timeout`.
+///
+/// # Params
+///
+/// stream --- The connection to the client.</br>
+/// peer --- The address of the client.</br>
+/// handler --- Answers each request, passed the address of the client.</br>
+/// config --- How long the connection is kept open for.
+pub fn handle_connection_loop<S, F>(stream: S, peer: SocketAddr, handler: &F, config: &ServerConfig)
+    where S: Read + Write, F: Fn(MessageHTTP, SocketAddr) -> MessageHTTP {
+    connection_loop(stream, peer, handler, config, &|_| Ok(()))
+}
+
+/// Answers each request sent over `stream` with `handler` until the connection closes, as
+/// by `handle_connection_loop`, changing the read timeout of `stream` with
+/// `set_read_timeout` between waiting for a request and reading it.
+///
+/// # Params
+///
+/// stream --- The connection to the client.</br>
+/// peer --- The address of the client.</br>
+/// handler --- Answers each request, passed the address of the client.</br>
+/// config --- How long the connection is kept open for.</br>
+/// set_read_timeout --- Sets the read timeout of `stream`.
+fn connection_loop<S, F>(stream: S, peer: SocketAddr, handler: &F, config: &ServerConfig, set_read_timeout: &dyn Fn(Duration) -> io::Result<()>)
+    where S: Read + Write, F: Fn(MessageHTTP, SocketAddr) -> MessageHTTP {
+    // The reader is kept between requests, it may hold the start of the next one.
+    let mut reader = BufReader::new(stream);
+    let mut requests = 0;
+    let limits = ParseLimits { max_body_bytes: config.max_body_bytes, ..ParseLimits::default() };
+    
+    loop {
+        // The first request may take as long to start as any read, later ones only as long
+        // as the connection may idle.
+        let wait = if requests == 0 { config.read_timeout } else { config.idle_keepalive_timeout };
+        
+        if set_read_timeout(wait).is_err() {
+            return;
+        }
+        // A client which closes or stalls between requests is not owed a response.
+        match reader.fill_buf() {
+            Ok(buf) if !buf.is_empty() => (),
+            _ => return
+        }
+        if set_read_timeout(config.read_timeout).is_err() {
+            return;
+        }
+        
+        // The deadline runs from the first byte of the request, not the first read of it.
+        let head = read_head_with_http09(
+            &mut HeaderDeadline {
+                reader: &mut reader,
+                deadline: Instant::now() + config.header_deadline,
+                read_timeout: config.read_timeout,
+                set_read_timeout
+            },
+            limits,
+            ParseMode::Strict,
+            config.accept_http09
+        );
+        let req = head.and_then(|mut req| {
+            set_read_timeout(config.read_timeout).map_err(|e| ParseError::Io(e.to_string()))?;
+            read_body(&mut reader, &mut req, limits)?;
+            Ok(req)
+        });
+        let req = match req {
+            Ok(req) => req,
+            Err(e) => {
+                // The request started but the rest of it did not arrive in time.
+                let res = match e {
+                    ParseError::Io(_) => Some(responses::request_timeout()),
+                    e => responses::parse_error(&e)
+                };
+                
+                if let Some(res) = res {
+                    // The client may have gone away, there is no one left to report the error to.
+                    let _ = res.write_to(reader.get_mut());
+                }
+                return;
+            }
+        };
+        requests += 1;
+        let method = match req.start_line.method() {
+            Some(method) => method.clone(),
+            // A client must send a Request line.
+            None => {
+                let mut res = responses::bad_request("Bad Request");
+                
+                res.set_connection_close();
+                let _ = res.write_to(reader.get_mut());
+                return;
+            }
+        };
+        let keep_alive = req.wants_keep_alive();
+        let version = req.start_line.version();
+        
+        let mut res = match panic::catch_unwind(AssertUnwindSafe(|| handler(req, peer))) {
+            Ok(res) => res,
+            Err(_) => {
+                let mut res = responses::internal_server_error();
+                
+                res.set_connection_close();
+                res
+            }
+        };
+        // An HTTP/0.9 client only understands the body of the response.
+        if version == Version::HTTP_09 {
+            if let StartLine::StatusLine { ref mut version, .. } = res.start_line {
+                *version = Version::HTTP_09;
+            }
+        }
+        if !keep_alive || !frame_response(&mut res) || requests >= config.max_requests {
+            res.set_connection_close();
+        } else if version < Version::HTTP_11 && res.wants_keep_alive() {
+            // An HTTP/1.0 client only keeps the connection open if it is told to.
+            res.set_header(headers::CONNECTION, "keep-alive")
+                .expect("`keep-alive` is always a valid Header Field value.");
+        }
+        
+        if res.write_response_to(reader.get_mut(), &method).is_err() || !res.wants_keep_alive() {
+            return;
+        }
+    }
+}
+
+/// Reads the header section of a request from `reader`, failing with a timeout once
+/// `deadline` passes however the bytes of it are spread across reads.
+struct HeaderDeadline<'a, S: 'a> {
+    /// The reader of the connection.
+    reader: &'a mut BufReader<S>,
+    /// When the header section must have arrived by.
+    deadline: Instant,
+    /// The longest any one read may take.
+    read_timeout: Duration,
+    /// Sets the read timeout of the connection.
+    set_read_timeout: &'a dyn Fn(Duration) -> io::Result<()>
+}
+
+impl<'a, S: Read> Read for HeaderDeadline<'a, S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        
+        if remaining == Duration::ZERO {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "The header section did not arrive in time."));
+        }
+        // Only a read which reaches the connection can block past the deadline.
+        if self.reader.buffer().is_empty() {
+            (self.set_read_timeout)(remaining.min(self.read_timeout))?;
+        }
+        self.reader.read(buf)
+    }
+}
+
+/// Adds a `Content-Length` to a response which has neither it nor a `Transfer-Encoding`,
+/// so the client can find the end of it without the connection closing.</br>
+/// Returns `false` if the end of the response can still only be found by closing the
+/// connection.
+///
+/// # Params
+///
+/// res --- The response to frame.
+fn frame_response(res: &mut MessageHTTP) -> bool {
+    // A response which can not have a body ends with its header section.
+    if res.start_line.as_status().is_some_and(|(_, code, _)| !code.allows_body()) {
+        return true;
+    }
+    match res.body_framing() {
+        BodyFraming::ContentLength(_) | BodyFraming::Chunked => true,
+        BodyFraming::Close if res.transfer_encodings().is_empty() => {
+            let length = res.message_body.len().to_string();
+            
+            res.set_header(headers::CONTENT_LENGTH, &length)
+                .expect("A `Content-Length` is always a valid Header Field value.");
+            true
+        },
+        _ => false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{self, Cursor};
+    use std::thread;
+    use http::{ParseError, ResponseBuilder, StatusCode, read_message};
+    
+    /// An in-memory connection, reading the requests from `input` and writing the
+    /// responses to `output`.
+    struct Duplex {
+        input: Cursor<Vec<u8>>,
+        output: Vec<u8>,
+        /// If reading past the end of `input` times out rather than the client closing the
+        /// connection.
+        stalls: bool
+    }
+    
+    impl Read for Duplex {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.input.read(buf)? {
+                0 if self.stalls && !buf.is_empty() => Err(io::Error::new(io::ErrorKind::TimedOut, "The client stalled.")),
+                read => Ok(read)
+            }
+        }
+    }
+    
+    impl Write for Duplex {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.output.write(buf)
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+    
+    /// A client which sends one byte of `input` every `delay`.
+    struct Drip {
+        input: Cursor<Vec<u8>>,
+        output: Vec<u8>,
+        delay: Duration
+    }
+    
+    impl Read for Drip {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = buf.len().min(1);
+            
+            thread::sleep(self.delay);
+            self.input.read(&mut buf[..len])
+        }
+    }
+    
+    impl Write for Drip {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.output.write(buf)
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+    
+    /// The status code, `Connection`, `Content-Length` and body of a response.
+    type Response = (StatusCode, Option<String>, Option<String>, Vec<u8>);
+    
+    /// Returns each response written when `requests` are sent to `handler` over one
+    /// connection, which then closes or stalls.
+    fn exchange<F>(requests: &str, stalls: bool, handler: F, config: ServerConfig) -> Vec<Response>
+        where F: Fn(MessageHTTP, SocketAddr) -> MessageHTTP {
+        let mut duplex = Duplex { input: Cursor::new(requests.as_bytes().to_vec()), output: Vec::new(), stalls };
+        let peer = "127.0.0.1:8080".parse().unwrap();
+        
+        handle_connection_loop(&mut duplex, peer, &handler, &config);
+        
+        let mut output = Cursor::new(duplex.output);
+        let mut responses = Vec::new();
+        
+        loop {
+            match read_message(&mut output) {
+                Ok(res) => responses.push((
+                    res.start_line.as_status().unwrap().1,
+                    res.header("Connection").map(String::from),
+                    res.header("Content-Length").map(String::from),
+                    res.message_body
+                )),
+                Err(ParseError::ConnectionClosed) => return responses,
+                Err(e) => panic!("Failed to read a response: {}", e)
+            }
+        }
+    }
+
+    #[test]
+    fn test_handle_connection_loop() {
+        let echo = |req: MessageHTTP, _: SocketAddr| responses::ok(req.start_line.path().unwrap());
+        
+        // Pipelined requests are each answered with a framed response.
+        assert_eq!(
+            exchange("GET /a HTTP/1.1\r\nHost: a\r\n\r\nGET /b HTTP/1.1\r\nHost: a\r\n\r\n", false, echo, ServerConfig::default()),
+            vec![
+                (StatusCode::OK, None, Some(String::from("2")), b"/a".to_vec()),
+                (StatusCode::OK, None, Some(String::from("2")), b"/b".to_vec())
+            ],
+            "Test handle_connection_loop-1 failed."
+        );
+        // An empty body is still given a `Content-Length`.
+        assert_eq!(
+            exchange("GET /a HTTP/1.1\r\nHost: a\r\n\r\nGET /b HTTP/1.1\r\nHost: a\r\n\r\n", false, |_, _| responses::ok(""), ServerConfig::default()),
+            vec![
+                (StatusCode::OK, None, Some(String::from("0")), Vec::new()),
+                (StatusCode::OK, None, Some(String::from("0")), Vec::new())
+            ],
+            "Test handle_connection_loop-2 failed."
+        );
+        // The client asks for the connection to close.
+        assert_eq!(
+            exchange("GET /a HTTP/1.1\r\nHost: a\r\nConnection: close\r\n\r\nGET /b HTTP/1.1\r\nHost: a\r\n\r\n", false, echo, ServerConfig::default()),
+            vec![(StatusCode::OK, Some(String::from("close")), Some(String::from("2")), b"/a".to_vec())],
+            "Test handle_connection_loop-3 failed."
+        );
+        // The connection closes once `max_requests` are answered.
+        let config = ServerConfig::new().with_max_requests(2);
+        assert_eq!(
+            exchange("GET /a HTTP/1.1\r\nHost: a\r\n\r\nGET /b HTTP/1.1\r\nHost: a\r\n\r\nGET /c HTTP/1.1\r\nHost: a\r\n\r\n", false, echo, config),
+            vec![
+                (StatusCode::OK, None, Some(String::from("2")), b"/a".to_vec()),
+                (StatusCode::OK, Some(String::from("close")), Some(String::from("2")), b"/b".to_vec())
+            ],
+            "Test handle_connection_loop-4 failed."
+        );
+    }
+    #[test]
+    fn test_handle_connection_loop_unframed() {
+        // The end of a body which is not chunked can only be found by closing the connection.
+        let unframed = |_: MessageHTTP, _: SocketAddr| {
+            ResponseBuilder::new(StatusCode::OK)
+                .header("Transfer-Encoding", "gzip")
+                .build()
+                .unwrap()
+        };
+        let mut duplex = Duplex {
+            input: Cursor::new(b"GET /a HTTP/1.1\r\nHost: a\r\n\r\nGET /b HTTP/1.1\r\nHost: a\r\n\r\n".to_vec()),
+            output: Vec::new(),
+            stalls: false
+        };
+        
+        handle_connection_loop(&mut duplex, "127.0.0.1:8080".parse().unwrap(), &unframed, &ServerConfig::default());
+        assert_eq!(
+            String::from_utf8(duplex.output).unwrap(),
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: gzip\r\nConnection: close\r\n\r\n",
+            "Test handle_connection_loop_unframed failed."
+        );
+    }
+    #[test]
+    fn test_handle_connection_loop_stalled() {
+        let echo = |req: MessageHTTP, _: SocketAddr| responses::ok(req.start_line.path().unwrap());
+        
+        // A request which stops part way through is answered with a `408 Request Timeout`.
+        assert_eq!(
+            exchange("GET /a HTTP/1.1\r\nHost: a\r\n\r\nGET /b HTTP/1.1\r\nHost", true, echo, ServerConfig::default()),
+            vec![
+                (StatusCode::OK, None, Some(String::from("2")), b"/a".to_vec()),
+                (StatusCode::REQUEST_TIMEOUT, Some(String::from("close")), Some(String::from("0")), Vec::new())
+            ],
+            "Test handle_connection_loop_stalled-1 failed."
+        );
+        // A connection which stops before or between requests is simply closed.
+        let tests = ["", "GET /a HTTP/1.1\r\nHost: a\r\n\r\n"];
+        for &requests in tests.iter() {
+            assert_eq!(
+                exchange(requests, true, echo, ServerConfig::default()).iter()
+                    .filter(|res| res.0 == StatusCode::REQUEST_TIMEOUT)
+                    .count(),
+                0,
+                "Test handle_connection_loop_stalled failed for `{}`.", requests
+            );
+        }
+    }
+    #[test]
+    fn test_handle_connection_loop_header_deadline() {
+        // Each byte arrives within any read timeout but the header section as a whole is late.
+        let mut drip = Drip {
+            input: Cursor::new(b"GET /a HTTP/1.1\r\nHost: a\r\n\r\n".to_vec()),
+            output: Vec::new(),
+            delay: Duration::from_millis(50)
+        };
+        let config = ServerConfig::new().with_header_deadline(Duration::from_millis(200));
+        
+        handle_connection_loop(&mut drip, "127.0.0.1:8080".parse().unwrap(), &|_, _| responses::ok("a"), &config);
+        assert_eq!(
+            String::from_utf8(drip.output).unwrap(),
+            "HTTP/1.1 408 Request Timeout\r\nConnection: close\r\nContent-Length: 0\r\n\r\n",
+            "Test handle_connection_loop_header_deadline failed."
+        );
+        assert!(
+            drip.input.position() < drip.input.get_ref().len() as u64,
+            "Test handle_connection_loop_header_deadline failed to stop reading."
+        );
+    }
+    #[test]
+    fn test_handle_connection_loop_body_too_large() {
+        let config = ServerConfig::new().with_max_body_bytes(8);
+        let length = |req: MessageHTTP, _: SocketAddr| responses::ok(req.message_body.len().to_string());
+        let too_large = vec![(StatusCode::CONTENT_TOO_LARGE, Some(String::from("close")), Some(String::from("0")), Vec::new())];
+        
+        // A declared length is rejected before the body is read.
+        assert_eq!(
+            exchange("POST /a HTTP/1.1\r\nHost: a\r\nContent-Length: 9999999999\r\n\r\nGET /b HTTP/1.1\r\nHost: a\r\n\r\n", true, length, config),
+            too_large,
+            "Test handle_connection_loop_body_too_large-1 failed."
+        );
+        // A chunked body is rejected once its chunks pass the limit.
+        assert_eq!(
+            exchange("POST /a HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: chunked\r\n\r\n5\r\n12345\r\n5\r\n12345\r\n", true, length, config),
+            too_large,
+            "Test handle_connection_loop_body_too_large-2 failed."
+        );
+        // A body at the limit is still answered.
+        assert_eq!(
+            exchange("POST /a HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: chunked\r\n\r\n8\r\n12345678\r\n0\r\n\r\n", false, length, config),
+            vec![(StatusCode::OK, None, Some(String::from("1")), b"8".to_vec())],
+            "Test handle_connection_loop_body_too_large-3 failed."
+        );
+    }
+    #[test]
+    fn test_handle_connection_loop_http09() {
+        let echo = |req: MessageHTTP, _: SocketAddr| responses::ok(req.start_line.path().unwrap());
+        let mut duplex = Duplex {
+            input: Cursor::new(b"GET /a\r\nGET /b HTTP/1.1\r\nHost: a\r\n\r\n".to_vec()),
+            output: Vec::new(),
+            stalls: false
+        };
+        
+        // A simple-request is answered with the body alone and the connection closed.
+        handle_connection_loop(&mut duplex, "127.0.0.1:8080".parse().unwrap(), &echo, &ServerConfig::new().with_http09(true));
+        assert_eq!(
+            String::from_utf8(duplex.output).unwrap(),
+            "/a",
+            "Test handle_connection_loop_http09-1 failed."
+        );
+        // Without the flag a simple-request is rejected.
+        assert_eq!(
+            exchange("GET /a\r\n", false, echo, ServerConfig::default()).iter().map(|res| res.0).collect::<Vec<StatusCode>>(),
+            vec![StatusCode::BAD_REQUEST],
+            "Test handle_connection_loop_http09-2 failed."
+        );
+    }
+}
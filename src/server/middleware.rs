@@ -0,0 +1,96 @@
+//! `middleware` is a module to run shared behaviour, e.g. logging or authorization, around
+//! every request answered by a `Router`.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::sync::Mutex;
+use http::MessageHTTP;
+use logging::Logger;
+
+/// A `Middleware` runs around the handling of every request by a `Router`, including the
+/// `404 Not Found` and `405 Method Not Allowed` fallbacks.
+///
+/// A `Middleware` may answer the request itself without calling `next`, pass `next` a
+/// changed copy of the request, or change the response `next` returns.
+pub trait Middleware: Send + Sync {
+    /// Returns the response to `req`.
+    ///
+    /// # Params
+    ///
+    /// req --- The request being answered.</br>
+    /// next --- Answers the request with the rest of the `Middleware` and the handler.
+    fn handle(&self, req: &MessageHTTP, next: &dyn Fn(&MessageHTTP) -> MessageHTTP) -> MessageHTTP;
+}
+
+impl<F> Middleware for F
+    where F: Fn(&MessageHTTP, &dyn Fn(&MessageHTTP) -> MessageHTTP) -> MessageHTTP + Send + Sync {
+    fn handle(&self, req: &MessageHTTP, next: &dyn Fn(&MessageHTTP) -> MessageHTTP) -> MessageHTTP {
+        self(req, next)
+    }
+}
+
+/// A `LogRequests` writes the request line of every request and the status code of its
+/// response to a `Logger`.
+pub struct LogRequests {
+    /// The `Logger` shared by every `Worker` thread.
+    logger: Mutex<Logger>
+}
+
+impl LogRequests {
+    /// Returns a new `LogRequests` writing to the passed `Logger`.
+    ///
+    /// # Params
+    ///
+    /// logger --- The `Logger` to write to.
+    pub fn new(logger: Logger) -> LogRequests {
+        LogRequests { logger: Mutex::new(logger) }
+    }
+}
+
+impl Middleware for LogRequests {
+    /// Writes `method target version status` to the `Logger` once the response is ready.
+    ///
+    /// Failing to write the log does not change the response.
+    fn handle(&self, req: &MessageHTTP, next: &dyn Fn(&MessageHTTP) -> MessageHTTP) -> MessageHTTP {
+        let res = next(req);
+        
+        if let (Some((method, target, version)), Some((_, status, _))) = (req.start_line.as_request(), res.start_line.as_status()) {
+            // A thread which panicked while logging leaves the `Logger` usable.
+            let mut logger = self.logger.lock()
+                .unwrap_or_else(|e| e.into_inner());
+            
+            let _ = logger.write(&format!("{} {} {} {}", method, target, version, status.as_u16()));
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, remove_file};
+    use http::responses;
+
+    #[test]
+    fn test_log_requests() {
+        let path = "test_log_requests.log";
+        let log = LogRequests::new(Logger::start(path).unwrap());
+        let req = MessageHTTP::from("GET /missing?a=1 HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        
+        assert_eq!(
+            log.handle(&req, &|_| responses::not_found()),
+            responses::not_found(),
+            "Test LogRequests::handle-1 failed."
+        );
+        
+        let contents = fs::read_to_string(path);
+        remove_file(path).expect("Test LogRequests::handle failed in cleanup.");
+        assert!(
+            contents.unwrap().contains("\nGET /missing?a=1 HTTP/1.1 404\n"),
+            "Test LogRequests::handle-2 failed."
+        );
+    }
+}
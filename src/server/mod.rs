@@ -5,7 +5,20 @@
 //! Author --- Daniel Bechaz</br>
 //! Date --- 06/09/2017
 
+#[allow(clippy::module_inception)]
 mod server;
 mod threading;
+mod router;
+mod middleware;
+mod static_files;
+mod connection;
+mod accept;
+mod config;
 
 pub use self::server::*;
+pub use self::router::*;
+pub use self::middleware::*;
+pub use self::static_files::*;
+pub use self::connection::*;
+pub use self::accept::*;
+pub use self::config::*;
@@ -0,0 +1,597 @@
+//! `router` is a module to dispatch requests to the handlers registered for their method and
+//! path.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::io::BufReader;
+use std::net::TcpStream;
+use http::{MessageHTTP, Method, ParseLimits, ParseMode, read_message_with_mode};
+use http::{responses, percent, path};
+use super::middleware::Middleware;
+
+/// A `Handler` answers a request, with the parameters captured from its path, with a
+/// response.
+pub type Handler = Box<dyn Fn(&MessageHTTP, &Params) -> MessageHTTP + Send + Sync>;
+
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+/// The parameters captured from the path of a request by the `:name` segments of a route.
+pub struct Params {
+    /// The name and percent-decoded value of each parameter, in the order they appear in
+    /// the route.
+    params: Vec<(String, String)>
+}
+
+impl Params {
+    /// Returns the value of the named parameter.
+    ///
+    /// # Params
+    ///
+    /// name --- The name of the parameter, without its `:`.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.params.iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+    /// Returns the name and value of each parameter, in the order they appear in the route.
+    pub fn pairs(&self) -> &[(String, String)] {
+        &self.params
+    }
+}
+
+/// A `Router` holds the handlers for each method and path a server answers.
+///
+/// A `Router` is `Send` and `Sync` so it can be shared between `Worker` threads in an `Arc`.
+#[derive(Default)]
+pub struct Router {
+    /// The method, path pattern and handler of each route, in the order they were registered.
+    routes: Vec<(Method, String, Handler)>,
+    /// The `Middleware` run around every request, outermost first.
+    middleware: Vec<Box<dyn Middleware>>
+}
+
+impl Router {
+    /// Returns a new `Router` with no routes.
+    pub fn new() -> Router {
+        Router::default()
+    }
+    /// Registers `handler` for requests with the passed method and path.</br>
+    /// Returns the `Router` so routes can be chained.
+    ///
+    /// The path is matched a segment at a time against the path of the request target,
+    /// without any query. A `:name` segment matches any non-empty segment and captures it,
+    /// percent-decoded, as a parameter. A final `*name` segment matches the rest of the
+    /// path, however many segments it has or none, and captures it raw so it can be passed
+    /// to `path::sanitize`. Every other segment must match exactly, so `/foo` and `/foo/`
+    /// are different routes. Registering a method and path a second time replaces the
+    /// earlier handler.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a `*name` segment is not the final segment of the path.
+    ///
+    /// # Params
+    ///
+    /// method --- The method of the requests to handle.</br>
+    /// path --- The path of the requests to handle, e.g. `/users/:id`.</br>
+    /// handler --- The function answering the requests.
+    pub fn add<F>(&mut self, method: Method, path: &str, handler: F) -> &mut Router
+        where F: Fn(&MessageHTTP, &Params) -> MessageHTTP + Send + Sync + 'static
+    {
+        let handler: Handler = Box::new(handler);
+        
+        assert!(
+            !path.split('/').rev().skip(1).any(|segment| segment.starts_with('*')),
+            "Called `Router::add` with a wildcard which is not the final segment of `{}`.", path
+        );
+        match self.routes.iter().position(|(m, p, _)| *m == method && p == path) {
+            Some(index) => self.routes[index].2 = handler,
+            None => self.routes.push((method, String::from(path), handler))
+        }
+        self
+    }
+    /// Registers `handler` for `GET` requests to the passed path, which also answers `HEAD`
+    /// requests unless a `HEAD` handler is registered.
+    ///
+    /// [Read more](#method.add)
+    ///
+    /// # Params
+    ///
+    /// path --- The path of the requests to handle.</br>
+    /// handler --- The function answering the requests.
+    pub fn get<F>(&mut self, path: &str, handler: F) -> &mut Router
+        where F: Fn(&MessageHTTP, &Params) -> MessageHTTP + Send + Sync + 'static
+    {
+        self.add(Method::Get, path, handler)
+    }
+    /// Registers `handler` for `POST` requests to the passed path.
+    ///
+    /// [Read more](#method.add)
+    ///
+    /// # Params
+    ///
+    /// path --- The path of the requests to handle.</br>
+    /// handler --- The function answering the requests.
+    pub fn post<F>(&mut self, path: &str, handler: F) -> &mut Router
+        where F: Fn(&MessageHTTP, &Params) -> MessageHTTP + Send + Sync + 'static
+    {
+        self.add(Method::Post, path, handler)
+    }
+    /// Registers `handler` for `PUT` requests to the passed path.
+    ///
+    /// [Read more](#method.add)
+    ///
+    /// # Params
+    ///
+    /// path --- The path of the requests to handle.</br>
+    /// handler --- The function answering the requests.
+    pub fn put<F>(&mut self, path: &str, handler: F) -> &mut Router
+        where F: Fn(&MessageHTTP, &Params) -> MessageHTTP + Send + Sync + 'static
+    {
+        self.add(Method::Put, path, handler)
+    }
+    /// Registers `handler` for `DELETE` requests to the passed path.
+    ///
+    /// [Read more](#method.add)
+    ///
+    /// # Params
+    ///
+    /// path --- The path of the requests to handle.</br>
+    /// handler --- The function answering the requests.
+    pub fn delete<F>(&mut self, path: &str, handler: F) -> &mut Router
+        where F: Fn(&MessageHTTP, &Params) -> MessageHTTP + Send + Sync + 'static
+    {
+        self.add(Method::Delete, path, handler)
+    }
+    /// Adds `middleware` around every request the `Router` answers.</br>
+    /// Returns the `Router` so `Middleware` can be chained.
+    ///
+    /// The first `Middleware` added is the outermost, it sees the request first and the
+    /// response last.
+    ///
+    /// # Params
+    ///
+    /// middleware --- The `Middleware` to add.
+    pub fn wrap<M: Middleware + 'static>(&mut self, middleware: M) -> &mut Router {
+        self.middleware.push(Box::new(middleware));
+        self
+    }
+    /// Returns the response to `req` after passing it through every `Middleware`.
+    ///
+    /// [Read more](#method.dispatch)
+    ///
+    /// # Params
+    ///
+    /// req --- The request to answer.
+    pub fn route(&self, req: &MessageHTTP) -> MessageHTTP {
+        self.next(0, req)
+    }
+    /// Returns the response to `req` from the `Middleware` at `index` and those inside it.
+    ///
+    /// # Params
+    ///
+    /// index --- The index of the outermost `Middleware` left to run.</br>
+    /// req --- The request to answer.
+    fn next(&self, index: usize, req: &MessageHTTP) -> MessageHTTP {
+        match self.middleware.get(index) {
+            Some(middleware) => middleware.handle(req, &|req| self.next(index + 1, req)),
+            None => self.dispatch(req)
+        }
+    }
+    /// Returns the response of the handler registered for the method and path of `req`,
+    /// without running any `Middleware`.
+    ///
+    /// The path is normalized as by `path::normalize` before it is matched, so `//a`,
+    /// `/a/./b` and `/a/x/../b` reach the same routes as `/a` and `/a/b`.</br>
+    /// When several routes of the method match, the one with a static segment where the
+    /// others have a parameter or wildcard, or a parameter where the others have a
+    /// wildcard, wins, comparing from the first segment. A `HEAD` request
+    /// without a matching `HEAD` route is answered by the `GET` route, the body is left
+    /// out when it is written with `MessageHTTP::write_response_to`.</br>
+    /// Returns a `404 Not Found` if no handler is registered for the path, a
+    /// `405 Method Not Allowed` listing the methods of the path if none is registered for
+    /// the method, or a `400 Bad Request` if `req` is not a request.
+    ///
+    /// # Params
+    ///
+    /// req --- The request to answer.
+    pub fn dispatch(&self, req: &MessageHTTP) -> MessageHTTP {
+        let (method, path) = match (req.start_line.method(), req.start_line.path()) {
+            (Some(method), Some(path)) => (method, path),
+            _ => return responses::bad_request("Bad Request")
+        };
+        // Equivalent paths must not slip past, or around, the route meant for them.
+        let path = path::normalize(path);
+        let matches: Vec<_> = self.routes.iter()
+            .filter_map(
+                |(m, pattern, handler)| capture(pattern, &path)
+                    .map(|(statics, params)| (m, statics, params, handler))
+            )
+            .collect();
+        // `max_by` returns the last of equal routes, searching in reverse the first route
+        // registered wins a tie.
+        let best = |method: &Method| matches.iter()
+            .filter(|found| found.0 == method)
+            .rev()
+            .max_by(|a, b| a.1.cmp(&b.1));
+        
+        let found = match *method {
+            Method::Head => best(method).or_else(|| best(&Method::Get)),
+            _ => best(method)
+        };
+        if let Some((_, _, params, handler)) = found {
+            return handler(req, params);
+        }
+        
+        let mut allowed: Vec<&str> = Vec::new();
+        for found in matches.iter() {
+            allowed.push(found.0.as_str());
+            if *found.0 == Method::Get {
+                allowed.push("HEAD");
+            }
+        }
+        if allowed.is_empty() {
+            responses::not_found()
+        } else {
+            responses::method_not_allowed(&allowed)
+        }
+    }
+    /// Reads one request from `stream` and writes the routed response to it.
+    ///
+    /// The request is read as by `read_message_with_mode` with the default `ParseLimits`
+    /// and `ParseMode::Strict`. A request which can not be read is answered as by
+    /// `responses::parse_error`.
+    ///
+    /// # Params
+    ///
+    /// stream --- The connection to the client.
+    pub fn serve(&self, mut stream: TcpStream) {
+        let req = read_message_with_mode(&mut BufReader::new(&stream), ParseLimits::default(), ParseMode::Strict);
+        let res = match req {
+            Ok(req) => match req.start_line.method() {
+                Some(method) => {
+                    // The client may have gone away, there is no one left to report the error to.
+                    let _ = self.route(&req).write_response_to(&mut stream, method);
+                    return;
+                },
+                // A client must send a Request line.
+                None => self.route(&req)
+            },
+            Err(e) => match responses::parse_error(&e) {
+                Some(res) => res,
+                None => return
+            }
+        };
+        
+        let _ = res.write_to(&mut stream);
+    }
+}
+
+/// Matches `path` against the segments of a route's `pattern`.</br>
+/// Returns the rank of each segment of the pattern, `2` if it is static, `1` if it is a
+/// parameter and `0` if it is a wildcard, and the captured parameters, or `None` if the
+/// path does not match.
+///
+/// # Params
+///
+/// pattern --- The path pattern of the route.</br>
+/// path --- The path of the request target.
+fn capture(pattern: &str, path: &str) -> Option<(Vec<u8>, Params)> {
+    let mut ranks = Vec::new();
+    let mut params = Params::default();
+    let segments: Vec<&str> = path.split('/').collect();
+    
+    for (index, expected) in pattern.split('/').enumerate() {
+        if let Some(name) = expected.strip_prefix('*') {
+            // The wildcard is always the final segment, the rest of the path is kept raw.
+            let rest = segments.get(index..).filter(|rest| !rest.is_empty())?.join("/");
+            
+            params.params.push((String::from(name), rest));
+            ranks.push(0);
+            return Some((ranks, params));
+        }
+        
+        let segment = *segments.get(index)?;
+        match expected.strip_prefix(':') {
+            Some(_) if segment.is_empty() => return None,
+            Some(name) => {
+                params.params.push((String::from(name), percent::decode(segment).ok()?));
+                ranks.push(1);
+            },
+            None if expected != segment => return None,
+            None => ranks.push(2)
+        }
+    }
+    if segments.len() > ranks.len() {
+        return None;
+    }
+    Some((ranks, params))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::thread;
+    use http::{HTTP, StatusCode};
+    
+    /// Returns a request with the passed method and target.
+    fn request(method: &str, target: &str) -> MessageHTTP {
+        MessageHTTP::from(&format!("{} {} HTTP/1.1\r\nHost: localhost\r\n\r\n", method, target))
+            .unwrap()
+    }
+    /// Returns the status code of the passed response.
+    fn status(res: &MessageHTTP) -> StatusCode {
+        res.start_line.as_status().unwrap().1
+    }
+
+    #[test]
+    fn test_router_route() {
+        let mut router = Router::new();
+        router.get("/", |_, _| responses::ok("index"))
+            .post("/submit", |req, _| responses::ok(req.message_body.clone()));
+        
+        assert_eq!(
+            router.route(&request("GET", "/?page=2")).message_body,
+            b"index".to_vec(),
+            "Test Router::route-1 failed."
+        );
+        assert_eq!(
+            router.route(&request("HEAD", "/")).message_body,
+            b"index".to_vec(),
+            "Test Router::route-2 failed."
+        );
+        assert_eq!(
+            status(&router.route(&request("GET", "/missing"))),
+            StatusCode::NOT_FOUND,
+            "Test Router::route-3 failed."
+        );
+        assert_eq!(
+            router.route(&MessageHTTP::from("HTTP/1.1 200 OK\r\n\r\n").unwrap()).to_http().unwrap(),
+            responses::bad_request("Bad Request").to_http().unwrap(),
+            "Test Router::route-4 failed."
+        );
+    }
+    #[test]
+    fn test_router_overlapping() {
+        let mut router = Router::new();
+        router.get("/a", |_, _| responses::ok("first"))
+            .get("/a", |_, _| responses::ok("second"))
+            .post("/a", |_, _| responses::ok("post"))
+            .get("/a/b", |_, _| responses::ok("nested"))
+            .add(Method::Head, "/a", |_, _| responses::no_content());
+        
+        let tests = [
+            ("GET", "/a", "second"),
+            ("POST", "/a", "post"),
+            ("GET", "/a/b", "nested"),
+            ("HEAD", "/a", "")
+        ];
+        for &(method, target, body) in tests.iter() {
+            assert_eq!(
+                router.route(&request(method, target)).message_body,
+                body.as_bytes().to_vec(),
+                "Test Router::route overlapping failed for `{} {}`.", method, target
+            );
+        }
+    }
+    #[test]
+    fn test_router_params() {
+        /// Returns the captured parameters as the body of the response.
+        fn echo(name: &str) -> impl Fn(&MessageHTTP, &Params) -> MessageHTTP {
+            let name = String::from(name);
+            
+            move |_, params| {
+                let pairs: Vec<String> = params.pairs().iter()
+                    .map(|(n, value)| format!("{}={}", n, value))
+                    .collect();
+                
+                responses::ok(format!("{} {}", name, pairs.join("&")))
+            }
+        }
+        
+        let mut router = Router::new();
+        router.get("/users/:id/posts/:post_id", echo("post"))
+            .get("/users/:id", echo("user"))
+            .get("/users/new", echo("new"))
+            .get("/users/:id/posts/latest", echo("latest"))
+            .get("/:section/posts/:post_id", echo("section"))
+            .get("/files/:name", echo("file"));
+        
+        let tests = [
+            ("/users/42/posts/7", "post id=42&post_id=7"),
+            ("/users/42", "user id=42"),
+            ("/users/new", "new "),
+            ("/users/42/posts/latest", "latest id=42"),
+            ("/blog/posts/7", "section section=blog&post_id=7"),
+            ("/files/my%20notes.txt", "file name=my notes.txt"),
+            ("//users/42", "user id=42"),
+            ("/users/./42", "user id=42"),
+            ("/users/x/../42/posts//7", "post id=42&post_id=7")
+        ];
+        for &(target, body) in tests.iter() {
+            assert_eq!(
+                router.route(&request("GET", target)).message_body,
+                body.as_bytes().to_vec(),
+                "Test Router::route params failed for `{}`.", target
+            );
+        }
+        
+        let missing = ["/users", "/users/", "/users/42/posts", "/users/42/posts/7/comments", "/files/%zz"];
+        for &target in missing.iter() {
+            assert_eq!(
+                status(&router.route(&request("GET", target))),
+                StatusCode::NOT_FOUND,
+                "Test Router::route params failed to reject `{}`.", target
+            );
+        }
+        
+        assert_eq!(
+            router.route(&request("GET", "/users/42")).message_body,
+            {
+                let mut router = Router::new();
+                router.get("/users/:id", echo("user")).get("/users/:name", echo("other"));
+                router.route(&request("GET", "/users/42")).message_body
+            },
+            "Test Router::route params failed for a tie."
+        );
+    }
+    #[test]
+    fn test_router_wildcard() {
+        let mut router = Router::new();
+        router.get("/static/*path", |_, params| responses::ok(format!("file {}", params.get("path").unwrap())))
+            .get("/static/special", |_, _| responses::ok("special"))
+            .get("/static/:name/index", |_, params| responses::ok(format!("index {}", params.get("name").unwrap())))
+            .get("/docs", |_, _| responses::ok("docs"))
+            .get("/docs/", |_, _| responses::ok("docs/"));
+        
+        let tests = [
+            ("/static/", "file "),
+            ("/static/a/b/c.css", "file a/b/c.css"),
+            ("/static/a%20b/c.css", "file a%20b/c.css"),
+            ("/static/a%20b/../c.css", "file c.css"),
+            ("/static/special", "special"),
+            ("/static/special/", "file special/"),
+            ("/static/a/index", "index a"),
+            ("/docs", "docs"),
+            ("/docs/", "docs/")
+        ];
+        for &(target, body) in tests.iter() {
+            assert_eq!(
+                router.route(&request("GET", target)).message_body,
+                body.as_bytes().to_vec(),
+                "Test Router::route wildcard failed for `{}`.", target
+            );
+        }
+        assert_eq!(
+            status(&router.route(&request("GET", "/static"))),
+            StatusCode::NOT_FOUND,
+            "Test Router::route wildcard failed to reject `/static`."
+        );
+    }
+    #[test]
+    #[should_panic]
+    fn test_router_wildcard_not_final() {
+        Router::new().get("/static/*path/index", |_, _| responses::no_content());
+    }
+    #[test]
+    fn test_router_method_not_allowed() {
+        let mut router = Router::new();
+        router.get("/", |_, _| responses::ok("index"))
+            .delete("/", |_, _| responses::no_content())
+            .put("/upload", |_, _| responses::no_content());
+        
+        let res = router.route(&request("POST", "/"));
+        assert_eq!(
+            (status(&res), res.header("Allow")),
+            (StatusCode::METHOD_NOT_ALLOWED, Some("GET, HEAD, DELETE")),
+            "Test Router::route-405-1 failed."
+        );
+        let res = router.route(&request("HEAD", "/upload"));
+        assert_eq!(
+            (status(&res), res.header("Allow")),
+            (StatusCode::METHOD_NOT_ALLOWED, Some("PUT")),
+            "Test Router::route-405-2 failed."
+        );
+    }
+    #[test]
+    fn test_router_serve() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let tests: [(&[u8], &str); 3] = [
+            (b"HEAD / HTTP/1.1\r\nHost: localhost\r\n\r\n", "HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\n"),
+            (b"GET /missing HTTP/1.1\r\nHost: localhost\r\n\r\n", "HTTP/1.1 404 Not Found\r\n"),
+            (b"GET / HTTP/1.1\r\nHost : localhost\r\n\r\n", "HTTP/1.1 400 Bad Request\r\n")
+        ];
+        
+        for &(req, res) in tests.iter() {
+            let mut client = TcpStream::connect(addr).unwrap();
+            let (stream, _) = listener.accept().unwrap();
+            let mut router = Router::new();
+            router.get("/", |_, _| responses::ok("index"));
+            
+            client.write_all(req).unwrap();
+            router.serve(stream);
+            
+            let mut received = String::new();
+            client.read_to_string(&mut received).unwrap();
+            assert!(
+                received.starts_with(res),
+                "Test Router::serve failed for `{}`.", String::from_utf8_lossy(req)
+            );
+        }
+    }
+    #[test]
+    fn test_router_middleware() {
+        /// Returns a `Middleware` which adds `name` to the `X-Trace` of the request and the
+        /// response.
+        fn trace(name: &'static str) -> impl Middleware {
+            move |req: &MessageHTTP, next: &dyn Fn(&MessageHTTP) -> MessageHTTP| {
+                let mut req = req.clone();
+                req.add_header("X-Trace", name).unwrap();
+                
+                let mut res = next(&req);
+                res.add_header("X-Trace", name).unwrap();
+                res
+            }
+        }
+        
+        let mut router = Router::new();
+        router.get("/", |req, _| responses::ok(req.header_values("X-Trace").collect::<Vec<_>>().join(" ")))
+            .wrap(trace("outer"))
+            .wrap(trace("inner"));
+        
+        let res = router.route(&request("GET", "/"));
+        assert_eq!(
+            (res.message_body.clone(), res.header_values("X-Trace").collect::<Vec<_>>()),
+            (b"outer inner".to_vec(), vec!["inner", "outer"]),
+            "Test Router::wrap-1 failed."
+        );
+        let res = router.route(&request("GET", "/missing"));
+        assert_eq!(
+            (status(&res), res.header_values("X-Trace").collect::<Vec<_>>()),
+            (StatusCode::NOT_FOUND, vec!["inner", "outer"]),
+            "Test Router::wrap-2 failed."
+        );
+        
+        // The inner `Middleware` and the handler never see a request without an `Authorization`.
+        router.wrap(
+            |req: &MessageHTTP, next: &dyn Fn(&MessageHTTP) -> MessageHTTP| match req.header("Authorization") {
+                Some(_) => next(req),
+                None => responses::bad_request("Unauthorized")
+            }
+        ).wrap(|_: &MessageHTTP, _: &dyn Fn(&MessageHTTP) -> MessageHTTP| -> MessageHTTP { panic!("Test Router::wrap-3 failed.") });
+        
+        let res = router.route(&request("GET", "/"));
+        assert_eq!(
+            (status(&res), res.header_values("X-Trace").collect::<Vec<_>>()),
+            (StatusCode::BAD_REQUEST, vec!["inner", "outer"]),
+            "Test Router::wrap-4 failed."
+        );
+    }
+    #[test]
+    fn test_router_shared() {
+        let mut router = Router::new();
+        router.get("/", |_, _| responses::ok("index"));
+        let router = Arc::new(router);
+        
+        let threads: Vec<_> = (0..4).map(
+            |_| {
+                let router = router.clone();
+                
+                thread::spawn(move || router.route(&request("GET", "/")).message_body)
+            }
+        ).collect();
+        for thread in threads {
+            assert_eq!(
+                thread.join().unwrap(),
+                b"index".to_vec(),
+                "Test Router shared failed."
+            );
+        }
+    }
+}
@@ -5,17 +5,25 @@
 //! Author --- Daniel Bechaz</br>
 //! Date --- 06/09/2017
 
-pub use std::net::{TcpListener, TcpStream};
+pub use std::net::{TcpListener, TcpStream, SocketAddr};
 use std::sync::mpsc::{channel, Sender, Receiver};
 pub use std::sync::mpsc::SendError;
 use super::threading::*;
+use std::sync::Arc;
 use std::thread;
 use std::any::Any;
+use http::MessageHTTP;
+use super::router::Router;
+use super::connection::serve_connection_with_config;
+use super::config::ServerConfig;
+use super::accept::{AcceptLoop, wake};
 
 /// A `Server` is an independant thread which handles concurrent connections using multiple `Worker` threads.
 pub struct Server {
     /// A handler to the `Server`s own thread.
     server: Option<thread::JoinHandle<()>>,
+    /// The address the `Server`s listener is bound to.
+    addr: SocketAddr,
     /// A `Sender` to signal the `Server` thread.
     pub sender: Sender<Message>
 }
@@ -42,6 +50,8 @@ impl Server {
     {
         let listener = TcpListener::bind(addr)
             .expect("Failed to bind to `addr`.");
+        let addr = listener.local_addr()
+            .expect("Failed to get the address of the listener.");
         let workers = WorkerPool::new(workers);
         let (sender, receiver) = channel();
         let server = Some(
@@ -52,19 +62,95 @@ impl Server {
             )
         );
         
-        Server { server, sender }
+        Server { server, addr, sender }
+    }
+    /// Returns a new `Server` with a listener bound the passed address, answering the
+    /// requests of each connection with `handler` on a `Worker` thread until it is shutdown.
+    ///
+    /// Each connection is served as by `serve_connection`.
+    ///
+    /// # Params
+    ///
+    /// addr --- The address to bind the `TcpListener` too.</br>
+    /// workers --- The number of `Worker` threads to spawn.</br>
+    /// handler --- Answers each request, passed the address of the client.
+    pub fn serve<F>(addr: &str, workers: usize, handler: F) -> Server
+        where F: Fn(MessageHTTP, SocketAddr) -> MessageHTTP + Send + Sync + 'static
+    {
+        Server::serve_with_config(addr, workers, ServerConfig::default(), handler)
+    }
+    /// Returns a new `Server` as by `serve`, applying the timeouts of the passed
+    /// `ServerConfig` to every accepted stream, as by `serve_connection_with_config`.
+    ///
+    /// [Read more](#method.serve)
+    ///
+    /// # Params
+    ///
+    /// addr --- The address to bind the `TcpListener` too.</br>
+    /// workers --- The number of `Worker` threads to spawn.</br>
+    /// config --- How long each connection is kept open for.</br>
+    /// handler --- Answers each request, passed the address of the client.
+    pub fn serve_with_config<F>(addr: &str, workers: usize, config: ServerConfig, handler: F) -> Server
+        where F: Fn(MessageHTTP, SocketAddr) -> MessageHTTP + Send + Sync + 'static
+    {
+        Server::start(addr, workers, accept_loop, (Arc::new(handler), config))
+    }
+    /// Returns a new `Server` answering each request with `Router::route`.
+    ///
+    /// [Read more](#method.serve)
+    ///
+    /// # Params
+    ///
+    /// addr --- The address to bind the `TcpListener` too.</br>
+    /// workers --- The number of `Worker` threads to spawn.</br>
+    /// router --- The routes of the `Server`.
+    pub fn with_router(addr: &str, workers: usize, router: Router) -> Server {
+        Server::serve(addr, workers, move |req, _| router.route(&req))
+    }
+    /// Returns the address the `Server` is listening on, e.g. to find the port it was given
+    /// when bound to port `0`.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
     }
     /// Blocks the calling thread until the `Server`s main thread terminates.
-    pub fn join(&mut self) -> Result<(), Box<Any + Send + 'static>> {
+    pub fn join(&mut self) -> Result<(), Box<dyn Any + Send + 'static>> {
         self.server.take().unwrap().join()
     }
-    /// Sends the `Shutdown` `Message` to the `Server` thread.</br>
+    /// Sends the `Shutdown` `Message` to the `Server` thread and wakes its listener, as by
+    /// `accept::wake`, so that an `AcceptLoop` blocked in `accept` sees it.</br>
     /// Returns `true` if the send succeeded and the `Server` thread joined, else it returns `false`.
+    ///
+    /// A main loop which is not an `AcceptLoop` receives the connection which wakes the
+    /// listener, it is closed without sending a request.
     pub fn shutdown(&mut self) -> bool {
-        match self.sender.send(Message::Shutdown) {
-            Ok(_) => true,
-            Err(_) => false
+        if self.sender.send(Message::Shutdown).is_err() {
+            return false;
         }
+        wake(self.addr);
+        true
+    }
+}
+
+/// Accepts connections and passes them to the handler until a `Shutdown` `Message` arrives.
+///
+/// Connections are accepted by an `AcceptLoop`, the thread blocks until each one arrives.
+///
+/// # Params
+///
+/// listener --- The listener to accept connections from.</br>
+/// workers --- The `Worker` threads to serve the connections on.</br>
+/// receiver --- The `Receiver` of `Message`s for the `Server`.</br>
+/// (handler, config) --- Answers each request, and how long each connection is kept open for.
+fn accept_loop<F>(listener: TcpListener, mut workers: WorkerPool, receiver: Receiver<Message>, (handler, config): (Arc<F>, ServerConfig))
+    where F: Fn(MessageHTTP, SocketAddr) -> MessageHTTP + Send + Sync + 'static {
+    for stream in AcceptLoop::new(listener, receiver) {
+        let handler = handler.clone();
+        
+        workers.send_job(move || serve_connection_with_config(stream, &*handler, &config))
+            .expect("Failed to send job to WorkerPool.");
+    }
+    if let Err(e) = workers.shutdown() {
+        panic!("{}", e);
     }
 }
 
@@ -73,3 +159,147 @@ impl Drop for Server {
         self.shutdown();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufReader, Write};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{Duration, Instant};
+    use http::{ParseError, StatusCode, read_message, responses};
+    
+    /// Returns the status code, `Connection` and body of the next response read from `reader`.
+    fn response<R: ::std::io::Read>(reader: &mut R) -> (StatusCode, Option<String>, Vec<u8>) {
+        let res = read_message(reader).unwrap();
+        
+        (res.start_line.as_status().unwrap().1, res.header("Connection").map(String::from), res.message_body)
+    }
+    
+    #[test]
+    fn test_server_serve() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handled = calls.clone();
+        let mut srv = Server::serve("127.0.0.1:0", 2,
+            move |req: MessageHTTP, peer: SocketAddr| {
+                handled.fetch_add(1, Ordering::SeqCst);
+                match req.start_line.path() {
+                    Some("/panic") => panic!("Test Server::serve panicking handler."),
+                    path => responses::ok(format!("{} {}", path.unwrap(), peer.ip()))
+                }
+            }
+        );
+        let addr = srv.local_addr();
+        
+        // Pipelined requests are answered in turn on the same connection.
+        let stream = TcpStream::connect(addr).unwrap();
+        let mut reader = BufReader::new(&stream);
+        (&stream).write_all(b"GET /a HTTP/1.1\r\nHost: localhost\r\n\r\nGET /b HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n").unwrap();
+        assert_eq!(
+            response(&mut reader),
+            (StatusCode::OK, None, b"/a 127.0.0.1".to_vec()),
+            "Test Server::serve-1 failed."
+        );
+        assert_eq!(
+            response(&mut reader),
+            (StatusCode::OK, Some(String::from("close")), b"/b 127.0.0.1".to_vec()),
+            "Test Server::serve-2 failed."
+        );
+        assert_eq!(
+            read_message(&mut reader),
+            Err(ParseError::ConnectionClosed),
+            "Test Server::serve-3 failed."
+        );
+        
+        // Each of these is answered and then the connection is closed.
+        let tests: [(&[u8], StatusCode); 3] = [
+            (b"GET /c HTTP/1.0\r\n\r\n", StatusCode::OK),
+            (b"GET /panic HTTP/1.1\r\nHost: localhost\r\n\r\n", StatusCode::INTERNAL_SERVER_ERROR),
+            (b"GET /d HTTP/1.1\r\nHost : localhost\r\n\r\n", StatusCode::BAD_REQUEST)
+        ];
+        for &(req, status) in tests.iter() {
+            let stream = TcpStream::connect(addr).unwrap();
+            let mut reader = BufReader::new(&stream);
+            
+            (&stream).write_all(req).unwrap();
+            let (res_status, connection, _) = response(&mut reader);
+            assert_eq!(
+                (res_status, connection),
+                (status, Some(String::from("close"))),
+                "Test Server::serve failed for `{}`.", String::from_utf8_lossy(req)
+            );
+            assert_eq!(
+                read_message(&mut reader),
+                Err(ParseError::ConnectionClosed),
+                "Test Server::serve failed to close for `{}`.", String::from_utf8_lossy(req)
+            );
+        }
+        
+        // The request with whitespace before a colon never reached the handler.
+        assert_eq!(
+            calls.load(Ordering::SeqCst),
+            4,
+            "Test Server::serve-4 failed."
+        );
+        srv.shutdown();
+        srv.join().unwrap();
+    }
+    #[test]
+    fn test_server_shutdown() {
+        let mut srv = Server::serve("127.0.0.1:0", 2, |_: MessageHTTP, _: SocketAddr| responses::ok(""));
+        
+        // No client is connecting, the `Server` is blocked in `accept`.
+        thread::sleep(Duration::from_millis(50));
+        let start = Instant::now();
+        assert!(srv.shutdown(), "Test Server::shutdown-1 failed.");
+        srv.join().unwrap();
+        assert!(
+            start.elapsed() < Duration::from_millis(100),
+            "Test Server::shutdown-2 failed."
+        );
+        assert!(!srv.shutdown(), "Test Server::shutdown-3 failed.");
+    }
+    #[test]
+    fn test_server_stalled_client() {
+        let config = ServerConfig::new().with_read_timeout(Duration::from_millis(100));
+        let mut srv = Server::serve_with_config("127.0.0.1:0", 1, config, |_: MessageHTTP, _: SocketAddr| responses::ok("done"));
+        let addr = srv.local_addr();
+        
+        // The only `Worker` is parked by a client which connects and never sends anything,
+        // once it is freed the next client is answered.
+        let start = Instant::now();
+        let stalled = TcpStream::connect(addr).unwrap();
+        let stream = TcpStream::connect(addr).unwrap();
+        (&stream).write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n").unwrap();
+        assert_eq!(
+            response(&mut BufReader::new(&stream)),
+            (StatusCode::OK, Some(String::from("close")), b"done".to_vec()),
+            "Test Server::serve_with_config-1 failed."
+        );
+        assert!(
+            start.elapsed() < Duration::from_secs(1),
+            "Test Server::serve_with_config-2 failed."
+        );
+        assert_eq!(
+            read_message(&mut BufReader::new(&stalled)),
+            Err(ParseError::ConnectionClosed),
+            "Test Server::serve_with_config-3 failed."
+        );
+        
+        // A request which stalls part way through is answered with a `408 Request Timeout`.
+        let stream = TcpStream::connect(addr).unwrap();
+        (&stream).write_all(b"GET / HTTP/1.1\r\nHost: loc").unwrap();
+        let mut reader = BufReader::new(&stream);
+        assert_eq!(
+            response(&mut reader),
+            (StatusCode::REQUEST_TIMEOUT, Some(String::from("close")), Vec::new()),
+            "Test Server::serve_with_config-4 failed."
+        );
+        assert_eq!(
+            read_message(&mut reader),
+            Err(ParseError::ConnectionClosed),
+            "Test Server::serve_with_config-5 failed."
+        );
+        srv.shutdown();
+        srv.join().unwrap();
+    }
+}
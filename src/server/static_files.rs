@@ -0,0 +1,385 @@
+//! `static_files` is a module to serve the files under a directory.
+//!
+//! #Last Modified
+//!
+//! Author --- Daniel Bechaz</br>
+//! Date --- 06/09/2017
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use http::{MessageHTTP, Method, ResponseBuilder, StatusCode};
+use http::{headers, mime, path, percent, responses, date};
+use super::router::Params;
+
+/// A `StaticFiles` answers `GET` and `HEAD` requests with the files under its root
+/// directory.
+pub struct StaticFiles {
+    /// The directory the files are served from.
+    root: PathBuf,
+    /// `true` if a directory without an `index.html` is answered with a listing of it.
+    listing: bool,
+    /// `true` if hidden files, whose names start with `.`, are included in a listing.
+    hidden: bool
+}
+
+impl StaticFiles {
+    /// Returns a new `StaticFiles` serving the files under `root`.
+    ///
+    /// # Params
+    ///
+    /// root --- The directory to serve the files from.
+    pub fn new<P: Into<PathBuf>>(root: P) -> StaticFiles {
+        StaticFiles { root: root.into(), listing: false, hidden: true }
+    }
+    /// Sets whether a directory is answered with its `index.html` or, if it has none, a
+    /// generated HTML listing of its entries, rather than a `403 Forbidden`.</br>
+    /// Listings are disabled by default.
+    ///
+    /// # Params
+    ///
+    /// enabled --- `true` to answer directories with a listing.
+    pub fn with_directory_listing(mut self, enabled: bool) -> StaticFiles {
+        self.listing = enabled;
+        self
+    }
+    /// Sets whether hidden files, whose names start with `.`, are included in a directory
+    /// listing.</br>
+    /// Hidden files are included by default, they can still be fetched either way.
+    ///
+    /// # Params
+    ///
+    /// listed --- `true` to include hidden files in a listing.
+    pub fn with_hidden_files(mut self, listed: bool) -> StaticFiles {
+        self.hidden = listed;
+        self
+    }
+    /// Returns the response to `req`, for use as the handler of a `Router` route.
+    ///
+    /// The file is named by the `path` parameter if the route captured one, e.g.
+    /// `/static/*path`, otherwise by the whole path of the request. The path is sanitized
+    /// as by `path::sanitize` and an empty path names `index.html`. The file is read as
+    /// bytes and sent with a `Content-Type` from its extension and a `Content-Length`. A
+    /// directory is answered as set by `with_directory_listing`.</br>
+    /// Returns a `405 Method Not Allowed` unless `req` is a `GET` or `HEAD` request, a
+    /// `403 Forbidden` if the path escapes the root or names a directory, a `404 Not Found`
+    /// if there is no such file, or a `500 Internal Server Error` if it can not be read.
+    ///
+    /// # Params
+    ///
+    /// req --- The request to answer.</br>
+    /// params --- The parameters the route captured from the path of `req`.
+    pub fn handle(&self, req: &MessageHTTP, params: &Params) -> MessageHTTP {
+        match req.start_line.method() {
+            Some(&Method::Get) | Some(&Method::Head) => (),
+            Some(_) => return responses::method_not_allowed(&["GET", "HEAD"]),
+            None => return responses::bad_request("Bad Request")
+        }
+        
+        let target = match params.get("path").or_else(|| req.start_line.path()) {
+            Some(target) => target,
+            None => return responses::bad_request("Bad Request")
+        };
+        let relative = match path::sanitize(target) {
+            Ok(relative) => relative,
+            Err(_) => return forbidden()
+        };
+        let file = self.root.join(&relative);
+        let file = match fs::metadata(&file) {
+            Ok(ref metadata) if metadata.is_dir() => {
+                let index = file.join("index.html");
+                
+                if index.is_file() && (self.listing || relative.as_os_str().is_empty()) {
+                    index
+                } else if self.listing {
+                    return self.list(req, &relative, &file);
+                } else if relative.as_os_str().is_empty() {
+                    return responses::not_found();
+                } else {
+                    return forbidden();
+                }
+            },
+            Ok(_) => file,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return responses::not_found(),
+            Err(_) => return responses::internal_server_error()
+        };
+        
+        match fs::read(&file) {
+            Ok(contents) => ResponseBuilder::new(StatusCode::OK)
+                .header(headers::CONTENT_TYPE, mime::from_path(&file))
+                .header(headers::CONTENT_LENGTH, &contents.len().to_string())
+                .body(contents)
+                .build()
+                .expect("A `200 OK` with its `Content-Length` is always valid."),
+            Err(_) => responses::internal_server_error()
+        }
+    }
+    /// Returns an HTML listing of the entries of a directory, directories first and then
+    /// by name, with a link to the parent directory unless it is the root.
+    ///
+    /// # Params
+    ///
+    /// req --- The request for the directory.</br>
+    /// relative --- The path of the directory under the root.</br>
+    /// dir --- The path of the directory.
+    fn list(&self, req: &MessageHTTP, relative: &Path, dir: &Path) -> MessageHTTP {
+        let mut entries = Vec::new();
+        let read_dir = match fs::read_dir(dir) {
+            Ok(read_dir) => read_dir,
+            Err(_) => return responses::internal_server_error()
+        };
+        
+        for entry in read_dir {
+            let (entry, metadata) = match entry.and_then(|entry| entry.metadata().map(|metadata| (entry, metadata))) {
+                Ok(entry) => entry,
+                Err(_) => return responses::internal_server_error()
+            };
+            // A name which is not utf8 could never be requested.
+            let name = match entry.file_name().into_string() {
+                Ok(name) => name,
+                Err(_) => continue
+            };
+            
+            if self.hidden || !name.starts_with('.') {
+                entries.push((!metadata.is_dir(), name, metadata));
+            }
+        }
+        entries.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+        
+        // The links are absolute so they work whether or not the path ends with a `/`.
+        let mut base = String::from(req.start_line.path().unwrap_or("/"));
+        if !base.ends_with('/') {
+            base.push('/');
+        }
+        let title = escape_html(&percent::decode(&base).unwrap_or_else(|_| base.clone()));
+        
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Index of {0}</title></head>\n<body>\n<h1>Index of {0}</h1>\n<table>\n<tr><th>Name</th><th>Size</th><th>Modified</th></tr>\n",
+            title
+        );
+        if !relative.as_os_str().is_empty() {
+            html.push_str(&format!("<tr><td><a href=\"{}../\">../</a></td><td>-</td><td></td></tr>\n", escape_html(&base)));
+        }
+        for (is_file, name, metadata) in entries {
+            let (slash, size) = if is_file {
+                ("", metadata.len().to_string())
+            } else {
+                ("/", String::from("-"))
+            };
+            let modified = metadata.modified()
+                .map(date::format)
+                .unwrap_or_default();
+            
+            html.push_str(
+                &format!(
+                    "<tr><td><a href=\"{}{}{}\">{}{}</a></td><td>{}</td><td>{}</td></tr>\n",
+                    escape_html(&base), percent::encode(&name), slash, escape_html(&name), slash, size, modified
+                )
+            );
+        }
+        html.push_str("</table>\n</body>\n</html>\n");
+        
+        ResponseBuilder::new(StatusCode::OK)
+            .header(headers::CONTENT_TYPE, "text/html; charset=utf-8")
+            .header(headers::CONTENT_LENGTH, &html.len().to_string())
+            .body(html)
+            .build()
+            .expect("A `200 OK` with its `Content-Length` is always valid.")
+    }
+}
+
+/// Returns the passed text with the characters which are special in HTML escaped.
+///
+/// # Params
+///
+/// text --- The text to escape.
+fn escape_html(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    
+    for c in text.chars() {
+        match c {
+            '&' => res.push_str("&amp;"),
+            '<' => res.push_str("&lt;"),
+            '>' => res.push_str("&gt;"),
+            '"' => res.push_str("&quot;"),
+            '\'' => res.push_str("&#39;"),
+            c => res.push(c)
+        }
+    }
+    res
+}
+
+/// Returns a `403 Forbidden` with a plain-text body.
+fn forbidden() -> MessageHTTP {
+    ResponseBuilder::new(StatusCode::FORBIDDEN)
+        .header(headers::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .body("Forbidden")
+        .build()
+        .expect("A `403 Forbidden` is always valid.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::process;
+    use std::fs::File;
+    use std::io::Write;
+    use http::HTTP;
+    use server::Router;
+    
+    /// The PNG signature, which is not valid utf8.
+    static PNG: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+    
+    /// Returns a new directory holding `index.html`, `logo.png` and `css/site.css`.
+    ///
+    /// # Params
+    ///
+    /// name --- The name of the test using the directory.
+    fn root(name: &str) -> PathBuf {
+        let root = env::temp_dir().join(format!("web_server-{}-{}", name, process::id()));
+        
+        fs::create_dir_all(root.join("css")).unwrap();
+        File::create(root.join("index.html")).unwrap().write_all(b"<p>home</p>").unwrap();
+        File::create(root.join("logo.png")).unwrap().write_all(&PNG).unwrap();
+        File::create(root.join("css/site.css")).unwrap().write_all(b"p {}").unwrap();
+        root
+    }
+    /// Returns a request with the passed method and target.
+    fn request(method: &str, target: &str) -> MessageHTTP {
+        MessageHTTP::from(&format!("{} {} HTTP/1.1\r\nHost: localhost\r\n\r\n", method, target))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_static_files() {
+        let root = root("static_files");
+        let files = StaticFiles::new(root.clone());
+        let tests: [(&str, &[u8], &str); 4] = [
+            ("/", b"<p>home</p>", "text/html; charset=utf-8"),
+            ("/index.html?v=2", b"<p>home</p>", "text/html; charset=utf-8"),
+            ("/logo.png", &PNG, "image/png"),
+            ("/css/%73ite.css", b"p {}", "text/css; charset=utf-8")
+        ];
+        
+        for &(target, body, media_type) in tests.iter() {
+            let res = files.handle(&request("GET", target), &Params::default());
+            
+            assert_eq!(
+                (res.start_line.as_status().unwrap().1, res.header("Content-Type"), res.header("Content-Length"), res.message_body.as_slice()),
+                (StatusCode::OK, Some(media_type), Some(body.len().to_string().as_str()), body),
+                "Test StaticFiles::handle failed for `{}`.", target
+            );
+        }
+        
+        let errors = [
+            ("GET", "/missing.html", StatusCode::NOT_FOUND),
+            ("GET", "/css", StatusCode::FORBIDDEN),
+            ("GET", "/../secret", StatusCode::FORBIDDEN),
+            ("GET", "/css/%2e%2e/%2e%2e/secret", StatusCode::FORBIDDEN),
+            ("POST", "/index.html", StatusCode::METHOD_NOT_ALLOWED)
+        ];
+        for &(method, target, status) in errors.iter() {
+            assert_eq!(
+                files.handle(&request(method, target), &Params::default()).start_line.as_status().unwrap().1,
+                status,
+                "Test StaticFiles::handle failed for `{} {}`.", method, target
+            );
+        }
+        
+        fs::remove_dir_all(root).expect("Test StaticFiles::handle failed in cleanup.");
+    }
+    #[test]
+    fn test_static_files_listing() {
+        let root = root("static_files_listing");
+        File::create(root.join("css/a <b>.css")).unwrap().write_all(b"b {}").unwrap();
+        File::create(root.join("css/.hidden")).unwrap();
+        fs::create_dir(root.join("css/zz")).unwrap();
+        fs::create_dir(root.join("empty")).unwrap();
+        
+        assert_eq!(
+            StaticFiles::new(root.clone()).handle(&request("GET", "/css"), &Params::default()).start_line.as_status().unwrap().1,
+            StatusCode::FORBIDDEN,
+            "Test StaticFiles::with_directory_listing-1 failed."
+        );
+        
+        let files = StaticFiles::new(root.clone()).with_directory_listing(true);
+        let res = files.handle(&request("GET", "/css"), &Params::default());
+        let html = String::from_utf8(res.message_body.clone()).unwrap();
+        let rows: Vec<&str> = html.lines().filter(|line| line.starts_with("<tr><td>")).collect();
+        
+        assert_eq!(
+            (res.header("Content-Type"), rows.len()),
+            (Some("text/html; charset=utf-8"), 5),
+            "Test StaticFiles::with_directory_listing-2 failed."
+        );
+        assert_eq!(
+            rows[0],
+            "<tr><td><a href=\"/css/../\">../</a></td><td>-</td><td></td></tr>",
+            "Test StaticFiles::with_directory_listing-3 failed."
+        );
+        assert!(
+            rows[1].starts_with("<tr><td><a href=\"/css/zz/\">zz/</a></td><td>-</td><td>")
+                && rows[2].starts_with("<tr><td><a href=\"/css/.hidden\">.hidden</a></td><td>0</td><td>")
+                && rows[3].starts_with("<tr><td><a href=\"/css/a%20%3Cb%3E.css\">a &lt;b&gt;.css</a></td><td>4</td><td>")
+                && rows[4].starts_with("<tr><td><a href=\"/css/site.css\">site.css</a></td><td>4</td><td>")
+                && rows[4].ends_with("GMT</td></tr>"),
+            "Test StaticFiles::with_directory_listing-4 failed."
+        );
+        assert!(
+            !html.contains("<b>"),
+            "Test StaticFiles::with_directory_listing-5 failed."
+        );
+        
+        let files = files.with_hidden_files(false);
+        let html = String::from_utf8(files.handle(&request("GET", "/css/"), &Params::default()).message_body).unwrap();
+        assert!(
+            !html.contains(".hidden") && html.contains("a &lt;b&gt;.css"),
+            "Test StaticFiles::with_hidden_files-1 failed."
+        );
+        
+        // A directory with an `index.html` is answered with it and the root has no parent.
+        assert_eq!(
+            files.handle(&request("GET", "/"), &Params::default()).message_body,
+            b"<p>home</p>".to_vec(),
+            "Test StaticFiles::with_directory_listing-6 failed."
+        );
+        fs::remove_file(root.join("index.html")).unwrap();
+        let html = String::from_utf8(files.handle(&request("GET", "/"), &Params::default()).message_body).unwrap();
+        assert!(
+            html.contains("<title>Index of /</title>") && !html.contains("../") && html.contains("href=\"/empty/\""),
+            "Test StaticFiles::with_directory_listing-7 failed."
+        );
+        
+        fs::remove_dir_all(root).expect("Test StaticFiles::with_directory_listing failed in cleanup.");
+    }
+    #[test]
+    fn test_static_files_route() {
+        let root = root("static_files_route");
+        let files = StaticFiles::new(root.clone());
+        let mut router = Router::new();
+        router.get("/static/*path", move |req, params| files.handle(req, params));
+        
+        let mut res = Vec::new();
+        router.route(&request("HEAD", "/static/logo.png"))
+            .write_response_to(&mut res, &Method::Head)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(res).unwrap(),
+            "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: 8\r\n\r\n",
+            "Test StaticFiles::handle-route-1 failed."
+        );
+        assert_eq!(
+            router.route(&request("GET", "/static/css/site.css")).message_body,
+            b"p {}".to_vec(),
+            "Test StaticFiles::handle-route-2 failed."
+        );
+        assert_eq!(
+            router.route(&request("GET", "/static/")).to_http().unwrap(),
+            router.route(&request("GET", "/static/index.html")).to_http().unwrap(),
+            "Test StaticFiles::handle-route-3 failed."
+        );
+        
+        fs::remove_dir_all(root).expect("Test StaticFiles::handle failed in cleanup.");
+    }
+}
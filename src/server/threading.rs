@@ -36,7 +36,7 @@ impl<F: FnOnce()> FnBox for F {
 }
 
 /// A `Job` is a Boxed function pointer that can be called from it's boxed instance.
-type Job = Box<FnBox + Send + 'static>;
+type Job = Box<dyn FnBox + Send + 'static>;
 
 impl WorkerPool {
     /// Returns a new `WorkerPool` ready to receive messages.
@@ -74,7 +74,7 @@ impl WorkerPool {
     /// telling a `Worker` to terminate, the `Err` is returned.
     pub fn shutdown(&mut self) -> Result<(), &'static str> {
         for _ in &mut self.workers {
-            if let Err(_) = self.sender.send(Message::Terminate) {
+            if self.sender.send(Message::Terminate).is_err() {
                 return Err("Error while sending terminate signal to `Worker`. (No `Receiver` attached)");
             }
         }
@@ -85,11 +85,11 @@ impl WorkerPool {
 impl Drop for WorkerPool {
     /// Cleanly terminates all `Worker`s before the `WorkerPool` is cleaned up.
     fn drop(&mut self) {
-        if let Ok(_) = self.shutdown() {
+        if self.shutdown().is_ok() {
             for worker in &mut self.workers {
                 if let Some(thread) = worker.thread.take() {
                     thread.join()
-                        .expect(format!("`WorkerPool` failed while joining worker{}.", worker.id).as_str());
+                        .unwrap_or_else(|_| panic!("`WorkerPool` failed while joining worker{}.", worker.id));
                 }
             }
         }
@@ -115,9 +115,9 @@ impl Worker {
                 move || {
                     loop {
                         let message = receiver.lock()
-                            .expect(format!("Worker{} failed while locking the Receiver.", id).as_str())
+                            .unwrap_or_else(|_| panic!("Worker{} failed while locking the Receiver.", id))
                             .recv()
-                            .expect(format!("Worker{} failed while receiving a message.", id).as_str());
+                            .unwrap_or_else(|_| panic!("Worker{} failed while receiving a message.", id));
                         
                         match message {
                             Message::Message(job) => job.call_box(),